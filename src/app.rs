@@ -1,11 +1,16 @@
 use chrono::NaiveDateTime;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use serde::{Deserialize, Serialize};
-use polars::prelude::*;
 
 use crate::data;
 use crate::error::PlotError;
-use crate::state::{self, WEViolation};
+use crate::data::rules::{self, RuleSet};
+use crate::perf::{LoadMode, WorkerRequest, WorkerResult};
+use crate::constants::performance::{FOLLOW_POLL_MS, LIVE_BATCH_LINES, LIVE_POLL_MS, STREAMING_THRESHOLD_BYTES};
+use crate::constants::spc::{D2_TABLE, MR_D2, MR_D4};
+use crate::state::{self, AttributeChart, ControlLimits, CusumChart, EwmaChart, VariablesChart, WEViolation};
 
 #[derive(Serialize, Deserialize)]
 #[allow(dead_code)]
@@ -32,6 +37,22 @@ pub struct ViewConfig {
     pub spec_upper: f64,
     pub show_we_rules: bool,
     pub dark_mode: bool,
+    #[serde(default)]
+    pub frozen_limits: std::collections::HashMap<String, state::FrozenLimits>,
+    #[serde(default)]
+    pub import_options: std::collections::HashMap<PathBuf, data::formats::CsvOptions>,
+    #[serde(default)]
+    pub computed_columns: Vec<state::ComputedColumn>,
+}
+
+/// Add the `definitions` that `data_source` does not have yet. Ones using columns it lacks,
+/// or whose names it already uses, are left out.
+fn add_computed_columns(data_source: &mut data::DataSource, definitions: &[state::ComputedColumn]) {
+    for definition in definitions {
+        if let Ok(compiled) = data::expr::compile(&definition.expression, &data_source.column_names()) {
+            let _ = data_source.add_computed(&definition.name, compiled);
+        }
+    }
 }
 
 pub struct PlotOxide {
@@ -262,67 +283,455 @@ impl PlotOxide {
         true
     }
 
-    pub fn load_file(&mut self, path: PathBuf) -> Result<(), PlotError> {
-        // Use new DataSource for loading
-        let data_source = data::DataSource::load(&path)?;
-
-        // Extract data for validation (using DataSource methods)
-        let headers = data_source.column_names();
-        let num_cols = headers.len();
-        let height = data_source.height();
-        
-        // Validate parsed data and report issues
-        // We do this column by column to avoid materializing the whole dataset as row-major
-        let mut nan_counts = vec![0usize; num_cols];
-        
-        for (col_idx, _) in headers.iter().enumerate() {
-            if let Ok(col_data) = data_source.column_as_f64(col_idx) {
-                nan_counts[col_idx] = col_data.iter().filter(|v| v.is_nan()).count();
-            }
-        }
-
-        // Report parsing warnings for columns with significant NaN values
-        let mut warnings = Vec::new();
-        for (col_idx, &nan_count) in nan_counts.iter().enumerate() {
-            if nan_count > 0 && col_idx < headers.len() {
-                let pct = (nan_count as f64 / height as f64) * 100.0;
-                
-                // Get column type to be smarter about warnings
-                let is_text_col = if let Ok(series) = data_source.get_column_series(col_idx) {
-                    let dtype = series.dtype();
-                    matches!(dtype, DataType::String | DataType::Boolean) || dtype.is_categorical()
-                } else {
-                    false
-                };
+    /// Open a file picked or dropped by the user: delimited text goes through the import
+    /// dialog (pre-filled with any remembered options), other formats load directly
+    pub fn open_file(&mut self, path: PathBuf) {
+        if data::formats::is_delimited(&path) {
+            let options = self.state.import_options.get(&path).cloned().unwrap_or_default();
+            self.state.ui.import_dialog = Some(state::ImportDialog::new(path, options));
+            return;
+        }
+        self.load_file(path);
+    }
+
+    /// Load a file with the import options remembered for it (defaults if none)
+    pub fn load_file(&mut self, path: PathBuf) {
+        let options = self.state.import_options.get(&path).cloned().unwrap_or_default();
+        self.load_file_with_options(path, options);
+    }
+
+    /// Start loading a file on the background worker, cancelling any load already running.
+    /// The result is applied by `poll_worker`.
+    pub fn load_file_with_options(&mut self, path: PathBuf, options: data::formats::CsvOptions) {
+        self.cancel_load();
+        self.state.load_id += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        // Files too big for memory stay on disk; summarise the columns plotted by default
+        let large = std::fs::metadata(&path).is_ok_and(|m| m.len() > STREAMING_THRESHOLD_BYTES);
+        let mode = if self.state.always_stream || large {
+            LoadMode::Streaming { summarise: vec![0, 1] }
+        } else {
+            LoadMode::InMemory
+        };
+
+        self.state.worker.request(WorkerRequest::LoadFile {
+            id: self.state.load_id,
+            path: path.clone(),
+            options: options.clone(),
+            mode,
+            cancel: cancel.clone(),
+        });
+        self.state.loading = Some(state::LoadingState {
+            id: self.state.load_id,
+            path,
+            options,
+            cancel,
+            progress: None,
+        });
+    }
 
-                // Logic:
-                // 1. If it's a text/categorical column and > 20% are NaNs (when parsed as float), 
-                //    it's likely just a text column. Don't warn.
-                // 2. If it's a numeric column and has > 5% NaNs, warn about missing data.
-                // 3. If it's text but low NaN count (< 20%), it might be a dirty numeric column. Warn.
-
-                if is_text_col {
-                    if pct <= 20.0 && pct > 0.0 {
-                        warnings.push(format!(
-                            "Column '{}' (Text): {}/{} values ({:.1}%) could not be parsed as numbers",
-                            headers[col_idx], nan_count, height, pct
-                        ));
+    /// Abandon the load in progress, keeping the current data
+    pub fn cancel_load(&mut self) {
+        if let Some(loading) = self.state.loading.take() {
+            loading.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Apply finished background work. Results from superseded loads are ignored.
+    pub fn poll_worker(&mut self) {
+        while let Some(result) = self.state.worker.poll() {
+            let current = self.state.loading.as_ref().map(|l| l.id);
+            match result {
+                WorkerResult::LoadProgress { id, progress } => {
+                    if let Some(loading) = self.state.loading.as_mut().filter(|l| l.id == id) {
+                        loading.progress = Some(progress);
+                    }
+                }
+                WorkerResult::FileLoaded { id, path, source, warnings } if current == Some(id) => {
+                    if let Some(loading) = self.state.loading.take() {
+                        self.install_data(path, *source, warnings, loading.options);
+                    }
+                }
+                WorkerResult::LoadFailed { id, msg } if current == Some(id) => {
+                    self.state.loading = None;
+                    self.state.ui.set_error(msg);
+                }
+                WorkerResult::FileLoaded { .. } | WorkerResult::LoadFailed { .. } | WorkerResult::LoadCancelled { .. } => {}
+                WorkerResult::SummaryReady { version, col, summary } if version == self.state.ui.data_version => {
+                    // A failed column stays pending so it is not requested again every frame
+                    match summary {
+                        Ok(summary) => {
+                            self.state.ui.pending_summaries.remove(&col);
+                            if let Some(ds) = self.state.data.as_mut() {
+                                ds.insert_summary(col, summary);
+                            }
+                        }
+                        Err(msg) => self.state.ui.set_error(msg),
+                    }
+                }
+                WorkerResult::SummaryReady { .. } => {}
+                WorkerResult::LinesParsed { id, received, rows } if self.state.live.as_ref().is_some_and(|l| l.id == id) => {
+                    if let Some(live) = self.state.live.as_mut() {
+                        live.parsing = false;
                     }
-                } else {
-                    if pct > 5.0 {
-                        warnings.push(format!(
-                            "Column '{}': {}/{} values ({:.1}%) are missing or invalid",
-                            headers[col_idx], nan_count, height, pct
-                        ));
+                    match rows {
+                        Ok(rows) => self.apply_live_rows(rows, received),
+                        Err(msg) => self.state.ui.set_error(format!("Live data dropped: {}", msg)),
                     }
                 }
+                WorkerResult::LinesParsed { .. } => {}
+                WorkerResult::Combined { id, name, source } if self.state.combining.as_ref().is_some_and(|(c, _)| *c == id) => {
+                    self.state.combining = None;
+                    match source {
+                        Ok(source) => {
+                            self.open_tab(*source, name, None);
+                        }
+                        Err(msg) => self.state.ui.set_error(format!("Could not combine data sets: {}", msg)),
+                    }
+                }
+                WorkerResult::Combined { .. } => {}
+                WorkerResult::LttbReady { key, points } => {
+                    // Dropped if the plot has moved on to another window since
+                    self.state.lttb_cache.receive(key, points);
+                }
+                WorkerResult::Error { msg } => {
+                    self.state.ui.set_error(msg);
+                }
             }
         }
+    }
 
-        // Store data source
-        self.state.data = Some(data_source);
-        self.state.view.x_index = 0;
-        self.state.view.y_indices = if num_cols > 1 { vec![1] } else { vec![] };
+    /// Read rows appended to the current file while following it, at most every
+    /// `FOLLOW_POLL_MS`. A file that has shrunk is reloaded from scratch.
+    pub fn poll_follow(&mut self) {
+        let follow = &mut self.state.follow;
+        let interval = std::time::Duration::from_millis(FOLLOW_POLL_MS);
+        if !follow.enabled || self.state.loading.is_some() || follow.last_poll.is_some_and(|t| t.elapsed() < interval) {
+            return;
+        }
+        follow.last_poll = Some(std::time::Instant::now());
+
+        let Some(ds) = self.state.data.as_mut().filter(|ds| ds.can_follow()) else {
+            return;
+        };
+        let options = self.state.current_file.as_ref()
+            .and_then(|path| self.state.import_options.get(path))
+            .cloned()
+            .unwrap_or_default();
+        // An unterminated last row is read again, so it counts as appended
+        let first_row = ds.height().saturating_sub(usize::from(ds.last_row_unterminated()));
+        match ds.read_appended(&options) {
+            Ok(0) => {}
+            Ok(_) => self.on_rows_appended(first_row),
+            Err(data::DataError::Truncated) => {
+                if let Some(path) = self.state.current_file.clone() {
+                    self.load_file(path);
+                }
+            }
+            Err(e) => {
+                self.state.follow.enabled = false;
+                self.state.ui.set_error(format!("Stopped following file: {}", e));
+            }
+        }
+    }
+
+    /// Refresh what depends on the data after rows were appended from `first_row` on.
+    /// Downsampled windows that end before the new rows are kept.
+    fn on_rows_appended(&mut self, first_row: usize) {
+        let first_x = if self.state.view.use_row_index {
+            Some(first_row as f64)
+        } else {
+            self.state.data.as_ref()
+                .and_then(|ds| ds.get_cached_column(self.state.view.x_index).ok())
+                .map(|x| x[first_row..].iter().copied().filter(|v| v.is_finite()).fold(f64::INFINITY, f64::min))
+        };
+        // Outlier filtering depends on statistics of the whole column, so every point may move
+        match first_x {
+            Some(x) if !self.state.filters.filter_outliers => self.state.lttb_cache.invalidate_from(x),
+            _ => self.state.lttb_cache.invalidate(),
+        }
+        self.state.outlier_stats_cache.clear();
+        self.state.ui.on_data_appended();
+    }
+
+    /// Start reading live data from `config.source`, ending any session already running.
+    /// The first batch of rows replaces the current data.
+    pub fn start_live(&mut self, config: data::live::LiveConfig) {
+        self.stop_live();
+        let listener = match data::live::LiveListener::start(&config.source) {
+            Ok(listener) => listener,
+            Err(e) => {
+                self.state.ui.set_error(format!("Could not open {}: {}", config.source, e));
+                return;
+            }
+        };
+        self.cancel_load();
+        self.state.follow.enabled = false;
+        self.state.load_id += 1;
+        self.state.live_config = config.clone();
+        self.state.live = Some(state::LiveSession {
+            id: self.state.load_id,
+            config,
+            listener,
+            header: None,
+            pending: Vec::new(),
+            parsing: false,
+            batches: None,
+            tab: None,
+            last_expiry: None,
+            received: 0,
+        });
+    }
+
+    /// End the live session, keeping the rows received so far
+    pub fn stop_live(&mut self) {
+        self.state.live = None;
+    }
+
+    /// Hand lines received from the live source to the worker, one batch at a time, and
+    /// drop rows that have aged out of the session's retention
+    pub fn poll_live(&mut self) {
+        let Some(live) = self.state.live.as_mut() else {
+            return;
+        };
+        live.pending.extend(live.listener.drain(LIVE_BATCH_LINES.saturating_sub(live.pending.len())));
+        if matches!(live.config.format, data::live::LineFormat::Csv { .. }) && live.header.is_none() && !live.pending.is_empty() {
+            live.header = Some(live.pending.remove(0));
+        }
+
+        if !live.parsing && !live.pending.is_empty() {
+            // Later batches are cast to the columns of the first
+            let schema = live.tab
+                .and_then(|tab| self.state.dataset(tab))
+                .map(|ds| ds.source_schema());
+            let Some(live) = self.state.live.as_mut() else {
+                return;
+            };
+            live.parsing = true;
+            self.state.worker.request(WorkerRequest::ParseLines {
+                id: live.id,
+                lines: std::mem::take(&mut live.pending),
+                format: live.config.format,
+                header: live.header.clone(),
+                schema,
+                received: std::time::Instant::now(),
+            });
+        }
+        self.expire_live_rows();
+    }
+
+    /// Add a parsed batch of live rows; the session's first batch opens a tab for them
+    fn apply_live_rows(&mut self, rows: polars::prelude::DataFrame, received: std::time::Instant) {
+        let Some(live) = self.state.live.as_mut() else {
+            return;
+        };
+        let count = rows.height();
+        live.received += count;
+        match (live.tab, live.batches.as_mut()) {
+            (Some(tab), Some(batches)) => {
+                batches.push(received, count);
+                let Some(ds) = self.state.dataset_mut(tab) else {
+                    return;
+                };
+                let first_row = ds.height();
+                match ds.append(&rows) {
+                    Ok(()) if self.state.active_tab_id() == Some(tab) => self.on_rows_appended(first_row),
+                    Ok(()) => {}
+                    Err(e) => self.state.ui.set_error(format!("Live data dropped: {}", e)),
+                }
+            }
+            _ => {
+                let mut batches = data::live::BatchLog::default();
+                batches.push(received, count);
+                live.batches = Some(batches);
+                let name = format!("📡 {}", live.config.source);
+                match data::DataSource::from_dataframe(rows, None) {
+                    Ok(ds) => {
+                        let tab = self.open_tab(ds, name, None);
+                        if let Some(live) = self.state.live.as_mut() {
+                            live.tab = Some(tab);
+                        }
+                    }
+                    Err(e) => self.state.ui.set_error(e.to_string()),
+                }
+            }
+        }
+        self.expire_live_rows();
+    }
+
+    /// Drop the oldest live rows the session's retention no longer keeps, at most every
+    /// `LIVE_POLL_MS`
+    fn expire_live_rows(&mut self) {
+        let Some(live) = self.state.live.as_mut() else {
+            return;
+        };
+        let (Some(tab), Some(batches)) = (live.tab, live.batches.as_mut()) else {
+            return;
+        };
+        let interval = std::time::Duration::from_millis(LIVE_POLL_MS);
+        if live.last_expiry.is_some_and(|t| t.elapsed() < interval) {
+            return;
+        }
+        let now = std::time::Instant::now();
+        live.last_expiry = Some(now);
+        let dropped = batches.expire(live.config.retention, now);
+        if dropped == 0 {
+            return;
+        }
+        let active = self.state.active_tab_id() == Some(tab);
+        let (use_row_index, x_index) = (self.state.view.use_row_index, self.state.view.x_index);
+        let Some(ds) = self.state.dataset_mut(tab) else {
+            return;
+        };
+        // Largest X dropped: downsampled windows reaching back to it held dropped points.
+        // Filtered rows don't line up with those dropped, so then every window goes.
+        let last_x = (active && !use_row_index && ds.unfiltered_height().is_none())
+            .then(|| ds.get_cached_column(x_index).ok())
+            .flatten()
+            .map(|x| x[..dropped.min(x.len())].iter().copied().filter(|v| v.is_finite()).fold(f64::NEG_INFINITY, f64::max));
+        ds.drop_front(dropped);
+        if !active {
+            return;
+        }
+        // Row indices have shifted, so windows of a row-index X axis all moved
+        match last_x {
+            Some(x) if !self.state.filters.filter_outliers => self.state.lttb_cache.invalidate_to(x),
+            _ => self.state.lttb_cache.invalidate(),
+        }
+        self.state.outlier_stats_cache.clear();
+        self.state.ui.on_rows_dropped();
+    }
+
+    /// Open `data_source` in a new tab and show it, returning the tab's id
+    fn open_tab(&mut self, data_source: data::DataSource, name: String, file: Option<PathBuf>) -> u64 {
+        self.park_active();
+        let id = self.state.next_tab_id;
+        self.state.next_tab_id += 1;
+        self.state.tabs.push(state::DatasetTab { id, name, file: file.clone(), parked: None });
+        self.state.active_tab = self.state.tabs.len() - 1;
+        self.state.current_file = file;
+        self.replace_data(data_source);
+        id
+    }
+
+    /// Collect `plan` on the background worker and open the result in a new tab named `name`
+    pub fn combine(&mut self, name: String, plan: polars::prelude::LazyFrame) {
+        self.state.load_id += 1;
+        self.state.worker.request(WorkerRequest::Combine { id: self.state.load_id, name: name.clone(), plan: Box::new(plan) });
+        self.state.combining = Some((self.state.load_id, name));
+    }
+
+    /// Show the tab at `index`, parking the data and column choices of the one shown now
+    pub fn switch_tab(&mut self, index: usize) {
+        if index == self.state.active_tab || index >= self.state.tabs.len() {
+            return;
+        }
+        self.park_active();
+        self.show_parked(index);
+    }
+
+    /// Close the tab at `index`, showing its neighbour if it was the one shown
+    pub fn close_tab(&mut self, index: usize) {
+        if index >= self.state.tabs.len() {
+            return;
+        }
+        let id = self.state.tabs.remove(index).id;
+        if index == self.state.active_tab {
+            self.state.data = None;
+            self.state.current_file = None;
+            self.state.view.y_indices.clear();
+            match self.state.tabs.len() {
+                0 => {
+                    self.state.active_tab = 0;
+                    self.on_dataset_changed();
+                }
+                len => self.show_parked(index.min(len - 1)),
+            }
+        } else if index < self.state.active_tab {
+            self.state.active_tab -= 1;
+        }
+        self.state.view.overlays.retain(|overlay| overlay.tab != id);
+        if self.state.live.as_ref().is_some_and(|live| live.tab == Some(id)) {
+            self.stop_live();
+        }
+    }
+
+    /// Move the shown data set and its column choices into its tab
+    fn park_active(&mut self) {
+        let Some(data) = self.state.data.take() else {
+            return;
+        };
+        let view = state::TabView {
+            x_index: self.state.view.x_index,
+            use_row_index: self.state.view.use_row_index,
+            y_indices: std::mem::take(&mut self.state.view.y_indices),
+        };
+        if let Some(tab) = self.state.tabs.get_mut(self.state.active_tab) {
+            tab.parked = Some((data, view));
+        }
+    }
+
+    /// Show the parked tab at `index`, restoring the columns it was plotted with
+    fn show_parked(&mut self, index: usize) {
+        self.state.active_tab = index;
+        let tab = &mut self.state.tabs[index];
+        let Some((data, view)) = tab.parked.take() else {
+            return;
+        };
+        self.state.current_file = tab.file.clone();
+        self.state.data = Some(data);
+        self.state.view.x_index = view.x_index;
+        self.state.view.use_row_index = view.use_row_index;
+        self.state.view.y_indices = view.y_indices;
+        // Following applies to the file shown
+        self.state.follow.enabled = false;
+        self.on_dataset_changed();
+    }
+
+    /// Min/max summary of a streamed column, asking the worker to build it if it is missing.
+    /// Returns None until the summary arrives.
+    pub fn streamed_summary(&mut self, col: usize) -> Option<Arc<data::pyramid::MinMaxPyramid>> {
+        let ds = self.state.data.as_ref()?;
+        if let Some(summary) = ds.summary(col) {
+            return Some(summary);
+        }
+        if self.state.ui.pending_summaries.insert(col) {
+            let (scan, column) = (Box::new(ds.file_scan()?.clone()), self.headers().get(col)?.clone());
+            self.state.worker.request(WorkerRequest::Summarise {
+                version: self.state.ui.data_version,
+                col,
+                column,
+                scan,
+            });
+        }
+        None
+    }
+
+    /// Make a freshly loaded file the current data set and reset everything tied to the old one
+    fn install_data(
+        &mut self,
+        path: PathBuf,
+        data_source: data::DataSource,
+        warnings: Vec<String>,
+        options: data::formats::CsvOptions,
+    ) {
+        if options == data::formats::CsvOptions::default() {
+            self.state.import_options.remove(&path);
+        } else {
+            self.state.import_options.insert(path.clone(), options);
+        }
+        // Reloading a file replaces its tab; any other file opens a new one
+        match self.state.tabs.iter().position(|tab| tab.file.as_ref() == Some(&path)) {
+            Some(index) => {
+                self.switch_tab(index);
+                self.replace_data(data_source);
+            }
+            None => {
+                let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().to_string());
+                self.open_tab(data_source, name, Some(path.clone()));
+            }
+        }
 
         // Update recent files list
         if !self.state.recent_files.contains(&path) {
@@ -337,10 +746,6 @@ impl PlotOxide {
         }
 
         self.state.current_file = Some(path);
-        self.state.view.reset_bounds = true; // Reset to auto-fit when loading new data
-
-        // Detect if X column is timestamp
-        self.state.view.x_is_timestamp = self.is_column_timestamp(self.state.view.x_index);
 
         // Show warnings if any parsing issues detected
         if !warnings.is_empty() {
@@ -350,12 +755,185 @@ impl PlotOxide {
             );
             self.state.ui.set_error(warning_msg);
         }
+    }
+
+    /// Make `data_source` the data of the tab shown, plotting its second column against its first
+    fn replace_data(&mut self, mut data_source: data::DataSource) {
+        add_computed_columns(&mut data_source, &self.state.computed_columns);
+        let num_cols = data_source.width();
+
+        // Store data source
+        self.state.data = Some(data_source);
+        self.state.view.x_index = 0;
+        self.state.view.y_indices = if num_cols > 1 { vec![1] } else { vec![] };
+        self.on_dataset_changed();
+    }
 
-        // Invalidate all caches (stats, table indices, outliers)
+    /// Add a computed column to the data shown, and to each other open data set that has the
+    /// columns it uses. The definition is kept for data sets opened later.
+    pub fn add_computed_column(&mut self, name: &str, expression: &str) -> Result<(), data::DataError> {
+        let ds = self.state.data.as_mut().ok_or_else(|| data::DataError::InvalidExpression("no data loaded".to_string()))?;
+        let compiled = data::expr::compile(expression, &ds.column_names())?;
+        ds.add_computed(name, compiled)?;
+        self.state.computed_columns.push(state::ComputedColumn {
+            name: name.to_string(),
+            expression: expression.to_string(),
+        });
+        self.apply_computed_columns();
+        Ok(())
+    }
+
+    /// Remove a computed column from every open data set and forget its definition
+    pub fn remove_computed_column(&mut self, name: &str) {
+        self.state.computed_columns.retain(|c| c.name != name);
+        // Columns after it move down one place
+        let shift = |ds: &mut data::DataSource, view: &mut state::TabView| {
+            let Some(removed) = ds.column_names().iter().position(|c| c == name) else {
+                return;
+            };
+            if ds.remove_computed(name).is_err() {
+                return;
+            }
+            view.y_indices.retain(|&i| i != removed);
+            for i in &mut view.y_indices {
+                if *i > removed {
+                    *i -= 1;
+                }
+            }
+            if view.x_index == removed {
+                view.x_index = 0;
+                view.use_row_index = true;
+            } else if view.x_index > removed {
+                view.x_index -= 1;
+            }
+        };
+        for tab in &mut self.state.tabs {
+            if let Some((ds, view)) = tab.parked.as_mut() {
+                shift(ds, view);
+            }
+        }
+        if let Some(ds) = self.state.data.as_mut() {
+            let mut view = state::TabView {
+                x_index: self.state.view.x_index,
+                use_row_index: self.state.view.use_row_index,
+                y_indices: std::mem::take(&mut self.state.view.y_indices),
+            };
+            shift(ds, &mut view);
+            self.state.view.x_index = view.x_index;
+            self.state.view.use_row_index = view.use_row_index;
+            self.state.view.y_indices = view.y_indices;
+            self.on_dataset_changed();
+        }
+    }
+
+    /// Add the computed columns each open data set is missing and has the columns for
+    fn apply_computed_columns(&mut self) {
+        let definitions = &self.state.computed_columns;
+        for tab in &mut self.state.tabs {
+            if let Some((ds, _)) = tab.parked.as_mut() {
+                add_computed_columns(ds, definitions);
+            }
+        }
+        if let Some(ds) = self.state.data.as_mut() {
+            add_computed_columns(ds, definitions);
+        }
+        // Filters may use the new columns
+        self.filter_rows_or_show_all();
+        // New columns go at the end, so no column index changes
+        self.state.ui.on_data_loaded();
+    }
+
+    /// Add a row filter typed in the filter bar. It is kept only if it compiles against the
+    /// data shown and the rows can be filtered by it.
+    pub fn add_row_filter(&mut self, expression: &str) -> Result<(), data::DataError> {
+        // Checked up front: a filter that does not compile would otherwise be skipped silently
+        let _ = data::expr::parse(expression, &self.headers())?;
+        self.state.filters.expressions.push(state::FilterExpression {
+            expression: expression.to_string(),
+            enabled: true,
+            error: None,
+        });
+        let result = self.filter_rows();
+        if result.is_err() {
+            self.state.filters.expressions.pop();
+        }
+        self.apply_row_filters();
+        result
+    }
+
+    /// Filter the rows shown again after filters were added, toggled or removed
+    pub fn apply_row_filters(&mut self) {
+        self.filter_rows_or_show_all();
         self.state.outlier_stats_cache.clear();
+        self.state.lttb_cache.invalidate();
         self.state.ui.on_data_loaded();
+    }
 
-        Ok(())
+    /// Filter the data shown, or show every row and report why the filters failed rather
+    /// than leave rows hidden by filters that do not apply
+    fn filter_rows_or_show_all(&mut self) {
+        if let Err(e) = self.filter_rows() {
+            if let Some(ds) = self.state.data.as_mut() {
+                let _ = ds.apply_filters(None);
+            }
+            for filter in self.state.filters.expressions.iter_mut().filter(|f| f.enabled) {
+                filter.error = Some(format!("Not applied: {}", e));
+            }
+            self.state.ui.set_error(format!("Filters not applied: {}", e));
+        }
+    }
+
+    /// Filter the data shown by the enabled filter expressions. Ones that do not compile
+    /// against its columns, or any while streaming, are skipped and marked with the reason.
+    fn filter_rows(&mut self) -> Result<(), data::DataError> {
+        let Some(ds) = self.state.data.as_mut() else {
+            self.state.filters.expressions.iter_mut().for_each(|f| f.error = None);
+            return Ok(());
+        };
+        let streaming = ds.is_streaming();
+        let columns = ds.column_names();
+        let mut predicate: Option<polars::prelude::Expr> = None;
+        for filter in &mut self.state.filters.expressions {
+            filter.error = None;
+            if !filter.enabled {
+                continue;
+            }
+            if streaming {
+                filter.error = Some("Not applied: rows are not filtered while streaming from disk".to_string());
+                continue;
+            }
+            match data::expr::parse(&filter.expression, &columns) {
+                Ok(expr) => predicate = Some(match predicate {
+                    Some(all) => all.and(expr),
+                    None => expr,
+                }),
+                Err(e) => filter.error = Some(format!("Not applied: {}", e)),
+            }
+        }
+        if streaming {
+            return Ok(());
+        }
+        ds.apply_filters(predicate)
+    }
+
+    /// Reset the column choices, view and caches tied to the data set shown before
+    fn on_dataset_changed(&mut self) {
+        self.filter_rows_or_show_all();
+        self.state.spc.sample_size_column = None;
+        self.state.spc.strata_column = None;
+        self.state.spc.gage_part_column = None;
+        self.state.spc.gage_operator_column = None;
+        self.state.spc.gage_measurement_column = None;
+        self.state.view.reset_bounds = true; // Reset to auto-fit when loading new data
+
+        // Detect if X column is timestamp
+        self.state.view.x_is_timestamp = !self.state.view.use_row_index && self.is_column_timestamp(self.state.view.x_index);
+
+        // Invalidate all caches (stats, table indices, outliers, downsampled series)
+        self.state.outlier_stats_cache.clear();
+        self.state.lttb_cache.invalidate();
+        self.state.downsampler.force_settle();
+        self.state.ui.on_data_loaded();
     }
 
     pub fn reset_view(&mut self) {
@@ -386,6 +964,9 @@ impl PlotOxide {
             spec_upper: self.state.spc.spec_upper,
             show_we_rules: self.state.spc.show_we_rules,
             dark_mode: self.state.view.dark_mode,
+            frozen_limits: self.state.spc.frozen_limits.clone(),
+            import_options: self.state.import_options.clone(),
+            computed_columns: self.state.computed_columns.clone(),
         };
 
         if let Some(path) = rfd::FileDialog::new()
@@ -437,6 +1018,10 @@ impl PlotOxide {
                             self.state.spc.spec_upper = config.spec_upper;
                             self.state.spc.show_we_rules = config.show_we_rules;
                             self.state.view.dark_mode = config.dark_mode;
+                            self.state.spc.frozen_limits = config.frozen_limits;
+                            self.state.import_options = config.import_options;
+                            self.state.computed_columns = config.computed_columns;
+                            self.apply_computed_columns();
                         }
                         Err(e) => {
                             self.state.ui.set_error(format!("Failed to parse config file: {}", e));
@@ -464,6 +1049,76 @@ impl PlotOxide {
         (mean, std_dev)
     }
 
+    // Centre line and sigma for SPC limits: frozen Phase I values when the column is locked,
+    // otherwise computed from the values currently plotted
+    pub fn limit_statistics(&self, y_idx: usize, values: &[f64]) -> (f64, f64) {
+        self.frozen_limits(y_idx)
+            .map(|frozen| (frozen.mean, frozen.std_dev))
+            .unwrap_or_else(|| Self::calculate_statistics(values))
+    }
+
+    // Phase I limits of a column, if they are frozen
+    pub fn frozen_limits(&self, y_idx: usize) -> Option<state::FrozenLimits> {
+        self.headers()
+            .get(y_idx)
+            .and_then(|name| self.state.spc.frozen_limits.get(name))
+            .copied()
+    }
+
+    // Freeze Phase I limits for a column from the configured baseline row range
+    pub fn freeze_limits(&mut self, y_idx: usize) -> Result<(), PlotError> {
+        let headers = self.headers();
+        let name = headers.get(y_idx).cloned().ok_or(PlotError::InvalidColumnIndex {
+            index: y_idx,
+            max: headers.len(),
+        })?;
+        let ds = self.state.data.as_ref().ok_or(PlotError::EmptyDataset)?;
+        let col = ds.get_cached_column(y_idx)?;
+
+        let (start, end) = self.state.spc.baseline_rows;
+        let end = end.min(col.len());
+        let baseline: Vec<f64> = col.get(start..end)
+            .unwrap_or_default()
+            .iter()
+            .copied()
+            .filter(|v| v.is_finite())
+            .collect();
+        if baseline.len() < 2 {
+            return Err(PlotError::InsufficientData {
+                operation: "Phase I limits".to_string(),
+                required: 2,
+                actual: baseline.len(),
+            });
+        }
+
+        // Attribute charts with a sample size column centre on defects per unit
+        let per_unit = self.state.spc.sample_size_column
+            .and_then(|size_idx| ds.get_cached_column(size_idx).ok())
+            .map(|sizes| {
+                let (defects, units) = (start..end.min(sizes.len()))
+                    .filter(|&i| col[i].is_finite() && sizes[i].is_finite() && sizes[i] > 0.0)
+                    .fold((0.0, 0.0), |(d, u), i| (d + col[i], u + sizes[i]));
+                defects / units
+            })
+            .filter(|rate| rate.is_finite());
+
+        let (mean, std_dev) = Self::calculate_statistics(&baseline);
+        self.state.spc.frozen_limits.insert(name, state::FrozenLimits {
+            baseline_start: start,
+            baseline_end: end,
+            mean,
+            std_dev,
+            per_unit,
+        });
+        Ok(())
+    }
+
+    pub fn unfreeze_limits(&mut self, y_idx: usize) {
+        if let Some(name) = self.headers().get(y_idx) {
+            self.state.spc.frozen_limits.remove(name);
+        }
+    }
+
     pub fn calculate_median(values: &[f64]) -> f64 {
         if values.is_empty() {
             return 0.0;
@@ -517,6 +1172,38 @@ impl PlotOxide {
         result
     }
 
+    // EWMA control chart: z_0 starts at the centre line and the limits widen towards
+    // the asymptotic L*sigma*sqrt(lambda/(2-lambda)) using the exact variance of z_i
+    pub fn calculate_ewma_chart(values: &[f64], lambda: f64, l: f64, center: f64, sigma: f64) -> EwmaChart {
+        if values.is_empty() || !(0.0..=1.0).contains(&lambda) || sigma <= 0.0 || !sigma.is_finite() {
+            return EwmaChart::default();
+        }
+
+        let asymptotic = lambda / (2.0 - lambda);
+        let mut points = Vec::with_capacity(values.len());
+        let mut ucl = Vec::with_capacity(values.len());
+        let mut lcl = Vec::with_capacity(values.len());
+        let mut z = center;
+        // (1 - lambda)^(2(i + 1)) kept as a running product, so no power takes the row index
+        let mut decay = 1.0;
+
+        for (i, &x) in values.iter().enumerate() {
+            z = lambda * x + (1.0 - lambda) * z;
+            decay *= (1.0 - lambda) * (1.0 - lambda);
+            let width = l * sigma * (asymptotic * (1.0 - decay)).sqrt();
+            points.push([i as f64, z]);
+            ucl.push(center + width);
+            lcl.push(center - width);
+        }
+
+        EwmaChart {
+            points,
+            center,
+            ucl,
+            lcl,
+        }
+    }
+
     pub fn downsample_lttb(data: &[[f64; 2]], threshold: usize) -> Vec<[f64; 2]> {
         // Largest-Triangle-Three-Buckets algorithm
         if data.len() <= threshold || threshold < 3 {
@@ -616,8 +1303,26 @@ impl PlotOxide {
         }
     }
 
-    pub fn calculate_xbarr(values: &[f64], subgroup_size: usize) -> (Vec<[f64; 2]>, Vec<[f64; 2]>, f64, f64, f64, f64, f64, f64) {
-        if values.is_empty() || subgroup_size < 2 {
+    // X-bar and S chart constants (A3, B3, B4) derived from c4, valid for any subgroup size >= 2
+    pub fn get_xbars_constants(n: usize) -> Option<(f64, f64, f64)> {
+        if n < 2 {
+            return None;
+        }
+        let c4 = data::stats::c4(n);
+        let a3 = 3.0 / (c4 * (n as f64).sqrt());
+        let spread = 3.0 * (1.0 - c4 * c4).sqrt() / c4;
+        let b3 = (1.0 - spread).max(0.0);
+        let b4 = 1.0 + spread;
+        Some((a3, b3, b4))
+    }
+
+    // Empty when there are no constants for the subgroup size (outside 2-10). With frozen
+    // Phase I limits the charts centre on their mean and the R-bar expected from their sigma.
+    pub fn calculate_xbarr(values: &[f64], subgroup_size: usize, frozen: Option<state::FrozenLimits>) -> (Vec<[f64; 2]>, Vec<[f64; 2]>, f64, f64, f64, f64, f64, f64) {
+        let Some((a2, d3, d4)) = Self::get_xbarr_constants(subgroup_size) else {
+            return (vec![], vec![], 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        };
+        if values.is_empty() {
             return (vec![], vec![], 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
         }
 
@@ -652,8 +1357,10 @@ impl PlotOxide {
         // Calculate mean range (R-bar)
         let r_mean: f64 = r_points.iter().map(|p| p[1]).sum::<f64>() / r_points.len() as f64;
 
-        // Get constants for control limits
-        let (a2, d3, d4) = Self::get_xbarr_constants(subgroup_size).unwrap_or((0.577, 0.0, 2.114));
+        let (xbar_mean, r_mean) = match frozen {
+            Some(frozen) => (frozen.mean, D2_TABLE[subgroup_size - 2] * frozen.std_dev),
+            None => (xbar_mean, r_mean),
+        };
 
         // X-bar chart control limits
         let xbar_ucl = xbar_mean + a2 * r_mean;
@@ -666,8 +1373,134 @@ impl PlotOxide {
         (xbar_points, r_points, xbar_mean, xbar_ucl, xbar_lcl, r_mean, r_ucl, r_lcl)
     }
 
-    // p-chart for attribute data (proportion defective)
-    pub fn calculate_pchart(defects: &[f64], sample_size: usize) -> (Vec<[f64; 2]>, f64, f64, f64) {
+    // X-bar and S chart - subgroup standard deviations instead of ranges for larger subgroups
+    // With frozen Phase I limits the charts centre on their mean and the S-bar expected from their sigma
+    pub fn calculate_xbars(values: &[f64], subgroup_size: usize, frozen: Option<state::FrozenLimits>) -> VariablesChart {
+        let Some((a3, b3, b4)) = Self::get_xbars_constants(subgroup_size) else {
+            return VariablesChart::default();
+        };
+
+        let num_subgroups = values.len() / subgroup_size;
+        if num_subgroups == 0 {
+            return VariablesChart::default();
+        }
+
+        let mut xbar_points = Vec::with_capacity(num_subgroups);
+        let mut s_points = Vec::with_capacity(num_subgroups);
+
+        for (i, subgroup) in values.chunks_exact(subgroup_size).enumerate() {
+            let mean = subgroup.iter().sum::<f64>() / subgroup_size as f64;
+            let variance = subgroup.iter()
+                .map(|v| (v - mean).powi(2))
+                .sum::<f64>() / (subgroup_size - 1) as f64;
+            xbar_points.push([i as f64, mean]);
+            s_points.push([i as f64, variance.sqrt()]);
+        }
+
+        let xbar_mean = xbar_points.iter().map(|p| p[1]).sum::<f64>() / num_subgroups as f64;
+        let s_bar = s_points.iter().map(|p| p[1]).sum::<f64>() / num_subgroups as f64;
+        let (xbar_mean, s_bar) = match frozen {
+            Some(frozen) => (frozen.mean, data::stats::c4(subgroup_size) * frozen.std_dev),
+            None => (xbar_mean, s_bar),
+        };
+
+        VariablesChart {
+            location: xbar_points,
+            location_limits: ControlLimits {
+                center: xbar_mean,
+                ucl: xbar_mean + a3 * s_bar,
+                lcl: xbar_mean - a3 * s_bar,
+            },
+            dispersion: s_points,
+            dispersion_limits: ControlLimits {
+                center: s_bar,
+                ucl: b4 * s_bar,
+                lcl: b3 * s_bar,
+            },
+            sigma: s_bar / data::stats::c4(subgroup_size),
+        }
+    }
+
+    // Individuals & Moving Range chart for one measurement per part
+    // Sigma is estimated from the average moving range (MR-bar / d2), not the overall std dev,
+    // unless frozen Phase I limits give the mean and sigma
+    pub fn calculate_imr(values: &[f64], frozen: Option<state::FrozenLimits>) -> VariablesChart {
+        if values.len() < 2 {
+            return VariablesChart::default();
+        }
+
+        let individuals: Vec<[f64; 2]> = values.iter()
+            .enumerate()
+            .map(|(i, &v)| [i as f64, v])
+            .collect();
+
+        // Moving range of span 2, first point has no MR
+        let moving_ranges: Vec<[f64; 2]> = values.windows(2)
+            .enumerate()
+            .map(|(i, w)| [(i + 1) as f64, (w[1] - w[0]).abs()])
+            .collect();
+
+        let (mean, mr_bar) = match frozen {
+            Some(frozen) => (frozen.mean, MR_D2 * frozen.std_dev),
+            None => (
+                values.iter().sum::<f64>() / values.len() as f64,
+                moving_ranges.iter().map(|p| p[1]).sum::<f64>() / moving_ranges.len() as f64,
+            ),
+        };
+        let sigma = mr_bar / MR_D2;
+
+        VariablesChart {
+            location: individuals,
+            location_limits: ControlLimits {
+                center: mean,
+                ucl: mean + 3.0 * sigma,
+                lcl: mean - 3.0 * sigma,
+            },
+            dispersion: moving_ranges,
+            dispersion_limits: ControlLimits {
+                center: mr_bar,
+                ucl: MR_D4 * mr_bar,
+                lcl: 0.0,
+            },
+            sigma,
+        }
+    }
+
+    // Tabular CUSUM: C+ and C- accumulate deviations beyond k*sigma from the target
+    // k and h are in sigma units; a point signals when either sum exceeds h*sigma
+    pub fn calculate_cusum(values: &[f64], target: f64, sigma: f64, k: f64, h: f64) -> CusumChart {
+        if values.is_empty() || sigma <= 0.0 || !sigma.is_finite() {
+            return CusumChart::default();
+        }
+
+        let slack = k * sigma;
+        let decision_interval = h * sigma;
+        let mut upper = Vec::with_capacity(values.len());
+        let mut lower = Vec::with_capacity(values.len());
+        let mut signals = Vec::new();
+        let (mut c_plus, mut c_minus) = (0.0f64, 0.0f64);
+
+        for (i, &x) in values.iter().enumerate() {
+            c_plus = (x - (target + slack) + c_plus).max(0.0);
+            c_minus = ((target - slack) - x + c_minus).max(0.0);
+            upper.push([i as f64, c_plus]);
+            lower.push([i as f64, c_minus]);
+            if c_plus > decision_interval || c_minus > decision_interval {
+                signals.push(i);
+            }
+        }
+
+        CusumChart {
+            upper,
+            lower,
+            target,
+            decision_interval,
+            signals,
+        }
+    }
+
+    // p-chart for attribute data (proportion defective); `p_bar` is a frozen Phase I centre
+    pub fn calculate_pchart(defects: &[f64], sample_size: usize, p_bar: Option<f64>) -> (Vec<[f64; 2]>, f64, f64, f64) {
         if defects.is_empty() || sample_size == 0 {
             return (vec![], 0.0, 0.0, 0.0);
         }
@@ -684,7 +1517,7 @@ impl PlotOxide {
         // Calculate p-bar (average proportion)
         let total_defects: f64 = defects.iter().sum();
         let total_inspected = defects.len() * sample_size;
-        let p_bar = total_defects / total_inspected as f64;
+        let p_bar = p_bar.unwrap_or(total_defects / total_inspected as f64);
 
         // Calculate control limits
         // UCL = p-bar + 3 * sqrt(p-bar * (1 - p-bar) / n)
@@ -696,6 +1529,85 @@ impl PlotOxide {
         (proportions, p_bar, ucl, lcl)
     }
 
+    // p-chart with a per-sample size (stair-stepped limits)
+    pub fn calculate_pchart_variable(defects: &[f64], sample_sizes: &[f64], p_bar: Option<f64>) -> AttributeChart {
+        let total_inspected: f64 = sample_sizes.iter().sum();
+        if defects.is_empty() || defects.len() != sample_sizes.len() || total_inspected <= 0.0 {
+            return AttributeChart::default();
+        }
+
+        let p_bar = p_bar.unwrap_or(defects.iter().sum::<f64>() / total_inspected);
+        let sigma = |n: f64| (p_bar * (1.0 - p_bar) / n).sqrt();
+
+        AttributeChart {
+            points: defects.iter()
+                .zip(sample_sizes)
+                .enumerate()
+                .map(|(i, (&d, &n))| [i as f64, d / n])
+                .collect(),
+            center: p_bar,
+            ucl: sample_sizes.iter().map(|&n| p_bar + 3.0 * sigma(n)).collect(),
+            lcl: sample_sizes.iter().map(|&n| (p_bar - 3.0 * sigma(n)).max(0.0)).collect(),
+        }
+    }
+
+    // np-chart: number defective per sample, constant sample size
+    pub fn calculate_npchart(defects: &[f64], sample_size: usize, p_bar: Option<f64>) -> AttributeChart {
+        if defects.is_empty() || sample_size == 0 {
+            return AttributeChart::default();
+        }
+
+        let n = sample_size as f64;
+        let p_bar = p_bar.unwrap_or(defects.iter().sum::<f64>() / (defects.len() as f64 * n));
+        let np_bar = n * p_bar;
+        let sigma = (np_bar * (1.0 - p_bar)).sqrt();
+
+        AttributeChart {
+            points: defects.iter().enumerate().map(|(i, &d)| [i as f64, d]).collect(),
+            center: np_bar,
+            ucl: vec![np_bar + 3.0 * sigma; defects.len()],
+            lcl: vec![(np_bar - 3.0 * sigma).max(0.0); defects.len()],
+        }
+    }
+
+    // c-chart: defect counts per inspection unit (Poisson)
+    pub fn calculate_cchart(counts: &[f64], c_bar: Option<f64>) -> AttributeChart {
+        if counts.is_empty() {
+            return AttributeChart::default();
+        }
+
+        let c_bar = c_bar.unwrap_or(counts.iter().sum::<f64>() / counts.len() as f64);
+        let sigma = c_bar.sqrt();
+
+        AttributeChart {
+            points: counts.iter().enumerate().map(|(i, &c)| [i as f64, c]).collect(),
+            center: c_bar,
+            ucl: vec![c_bar + 3.0 * sigma; counts.len()],
+            lcl: vec![(c_bar - 3.0 * sigma).max(0.0); counts.len()],
+        }
+    }
+
+    // u-chart: defects per unit with a (possibly varying) number of units per sample
+    pub fn calculate_uchart(counts: &[f64], units: &[f64], u_bar: Option<f64>) -> AttributeChart {
+        let total_units: f64 = units.iter().sum();
+        if counts.is_empty() || counts.len() != units.len() || total_units <= 0.0 {
+            return AttributeChart::default();
+        }
+
+        let u_bar = u_bar.unwrap_or(counts.iter().sum::<f64>() / total_units);
+
+        AttributeChart {
+            points: counts.iter()
+                .zip(units)
+                .enumerate()
+                .map(|(i, (&c, &n))| [i as f64, c / n])
+                .collect(),
+            center: u_bar,
+            ucl: units.iter().map(|&n| u_bar + 3.0 * (u_bar / n).sqrt()).collect(),
+            lcl: units.iter().map(|&n| (u_bar - 3.0 * (u_bar / n).sqrt()).max(0.0)).collect(),
+        }
+    }
+
     pub fn calculate_pareto(values: &[f64]) -> (Vec<(f64, usize)>, Vec<f64>) {
         // Create frequency map
         use std::collections::HashMap;
@@ -764,117 +1676,33 @@ impl PlotOxide {
         (hist_data, min, bin_width)
     }
 
-    pub fn detect_western_electric_violations(values: &[f64]) -> Vec<usize> {
-        Self::detect_western_electric_violations_detailed(values)
+    pub fn detect_western_electric_violations(values: &[f64], rules: &RuleSet) -> Vec<usize> {
+        Self::detect_western_electric_violations_detailed(values, rules)
             .iter()
             .map(|v| v.point_index)
-            .collect::<std::collections::HashSet<_>>()
-            .into_iter()
             .collect()
     }
 
-    pub fn detect_western_electric_violations_detailed(values: &[f64]) -> Vec<WEViolation> {
-        if values.len() < 8 {
+    pub fn detect_western_electric_violations_detailed(values: &[f64], rules: &RuleSet) -> Vec<WEViolation> {
+        if values.is_empty() {
             return vec![];
         }
 
         let (mean, std_dev) = Self::calculate_statistics(values);
-        let mut violation_map: std::collections::HashMap<usize, Vec<String>> = std::collections::HashMap::new();
-
-        // Rule 1: One point beyond 3σ (handled by outlier detection)
-        // Rule 2: 2 out of 3 consecutive points beyond 2σ on same side
-        // Rule 3: 4 out of 5 consecutive points beyond 1σ on same side
-        // Rule 4: 8 consecutive points on same side of mean
-        // Rule 5: 6 consecutive points increasing or decreasing (trend)
-        // Rule 6: 14 consecutive points alternating up and down
-
-        // Rule 4: 8 consecutive on same side
-        for i in 7..values.len() {
-            let slice = &values[i-7..=i];
-            let above = slice.iter().all(|&v| v > mean);
-            let below = slice.iter().all(|&v| v < mean);
-            if above || below {
-                for j in (i-7)..=i {
-                    violation_map.entry(j).or_insert_with(Vec::new).push("Rule 4: 8 consecutive on same side".to_string());
-                }
-            }
-        }
-
-        // Rule 2: 2 out of 3 beyond 2σ
-        for i in 2..values.len() {
-            let slice = &values[i-2..=i];
-            let above_2s = slice.iter().filter(|&&v| v > mean + 2.0 * std_dev).count();
-            let below_2s = slice.iter().filter(|&&v| v < mean - 2.0 * std_dev).count();
-            if above_2s >= 2 || below_2s >= 2 {
-                for j in (i-2)..=i {
-                    violation_map.entry(j).or_insert_with(Vec::new).push("Rule 2: 2/3 beyond 2σ".to_string());
-                }
-            }
-        }
-
-        // Rule 3: 4 out of 5 beyond 1σ
-        for i in 4..values.len() {
-            let slice = &values[i-4..=i];
-            let above_1s = slice.iter().filter(|&&v| v > mean + 1.0 * std_dev).count();
-            let below_1s = slice.iter().filter(|&&v| v < mean - 1.0 * std_dev).count();
-            if above_1s >= 4 || below_1s >= 4 {
-                for j in (i-4)..=i {
-                    violation_map.entry(j).or_insert_with(Vec::new).push("Rule 3: 4/5 beyond 1σ".to_string());
-                }
-            }
-        }
-
-        // Rule 5: 6 consecutive increasing or decreasing
-        for i in 5..values.len() {
-            let slice = &values[i-5..=i];
-            let increasing = slice.windows(2).all(|w| w[1] > w[0]);
-            let decreasing = slice.windows(2).all(|w| w[1] < w[0]);
-            if increasing || decreasing {
-                for j in (i-5)..=i {
-                    violation_map.entry(j).or_insert_with(Vec::new).push("Rule 5: 6 trending".to_string());
-                }
-            }
-        }
-
-        // Rule 6: 14 consecutive alternating
-        if values.len() >= 14 {
-            for i in 13..values.len() {
-                let slice = &values[i-13..=i];
-                let alternating = slice.windows(3).all(|w| {
-                    (w[1] > w[0] && w[1] > w[2]) || (w[1] < w[0] && w[1] < w[2])
-                });
-                if alternating {
-                    for j in (i-13)..=i {
-                        violation_map.entry(j).or_insert_with(Vec::new).push("Rule 6: 14 alternating".to_string());
-                    }
-                }
-            }
-        }
-
-        // Convert to vector of violations
-        let mut violations: Vec<WEViolation> = violation_map.into_iter()
-            .map(|(point_index, rules)| WEViolation { point_index, rules })
-            .collect();
-        violations.sort_by_key(|v| v.point_index);
-        violations
+        Self::detect_western_electric_violations_with_limits(values, mean, std_dev, rules)
     }
 
-    pub fn calculate_process_capability(values: &[f64], lsl: f64, usl: f64) -> (f64, f64) {
-        if values.is_empty() || usl <= lsl {
-            return (0.0, 0.0);
-        }
-
-        let (mean, std_dev) = Self::calculate_statistics(values);
-
-        // Cp = (USL - LSL) / (6 * sigma)
-        let cp = (usl - lsl) / (6.0 * std_dev);
-
-        // Cpk = min((USL - mean) / (3 * sigma), (mean - LSL) / (3 * sigma))
-        let cpu = (usl - mean) / (3.0 * std_dev);
-        let cpl = (mean - lsl) / (3.0 * std_dev);
-        let cpk = cpu.min(cpl);
-
-        (cp, cpk)
+    /// Run rule detection against a given centre line and sigma (e.g. MR-bar/d2 for I-MR charts)
+    pub fn detect_western_electric_violations_with_limits(
+        values: &[f64],
+        mean: f64,
+        std_dev: f64,
+        rules: &RuleSet,
+    ) -> Vec<WEViolation> {
+        rules::evaluate(values, mean, std_dev, rules)
+            .into_iter()
+            .map(|(point_index, rules)| WEViolation { point_index, rules })
+            .collect()
     }
 
     pub fn export_csv(&mut self) {
@@ -1015,4 +1843,258 @@ impl PlotOxide {
 
         Some((coeffs, r_squared))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tol: f64) -> bool {
+        (a - b).abs() < tol
+    }
+
+    #[test]
+    fn test_imr_limits() {
+        // Moving ranges 2, 1, 4, 2: MR-bar 2.25, sigma = 2.25 / 1.128
+        let chart = PlotOxide::calculate_imr(&[10.0, 12.0, 11.0, 15.0, 13.0], None);
+        let sigma = 2.25 / 1.128;
+        assert!(close(chart.sigma, sigma, 1e-12));
+        assert!(close(chart.location_limits.center, 12.2, 1e-12));
+        assert!(close(chart.location_limits.ucl, 12.2 + 3.0 * sigma, 1e-12));
+        assert!(close(chart.location_limits.lcl, 12.2 - 3.0 * sigma, 1e-12));
+        assert!(close(chart.dispersion_limits.center, 2.25, 1e-12));
+        assert!(close(chart.dispersion_limits.ucl, 3.267 * 2.25, 1e-12));
+        assert_eq!(chart.dispersion_limits.lcl, 0.0);
+        // The first point has no moving range
+        assert_eq!(chart.dispersion.first(), Some(&[1.0, 2.0]));
+        assert!(PlotOxide::calculate_imr(&[1.0], None).location.is_empty());
+    }
+
+    #[test]
+    fn test_xbarr_needs_constants() {
+        // Two subgroups of 5: means 3 and 8, ranges 4 and 4
+        let values: Vec<f64> = (1..=10).map(f64::from).collect();
+        let (xbar, r, xbar_mean, ucl, _, r_mean, r_ucl, r_lcl) = PlotOxide::calculate_xbarr(&values, 5, None);
+        assert_eq!(xbar, vec![[0.0, 3.0], [1.0, 8.0]]);
+        assert_eq!(r.len(), 2);
+        assert!(close(xbar_mean, 5.5, 1e-12));
+        assert!(close(r_mean, 4.0, 1e-12));
+        assert!(close(ucl, 5.5 + 0.577 * 4.0, 1e-12));
+        assert!(close(r_ucl, 2.114 * 4.0, 1e-12));
+        assert_eq!(r_lcl, 0.0);
+
+        // No R chart constants past n = 10, rather than the n = 5 ones
+        let values: Vec<f64> = (0..24).map(f64::from).collect();
+        assert!(PlotOxide::calculate_xbarr(&values, 12, None).0.is_empty());
+    }
+
+    #[test]
+    fn test_attribute_chart_limits() {
+        // np: 10 defective in 5 samples of 50, p-bar 0.04
+        let np = PlotOxide::calculate_npchart(&[2.0, 3.0, 1.0, 4.0, 0.0], 50, None);
+        assert!(close(np.center, 2.0, 1e-12));
+        assert!(close(np.ucl[0], 2.0 + 3.0 * (2.0f64 * 0.96).sqrt(), 1e-12));
+        assert_eq!(np.lcl[0], 0.0);
+
+        // c: c-bar 4, limits 4 ± 3·2 with the LCL floored at zero
+        let c = PlotOxide::calculate_cchart(&[3.0, 5.0, 4.0, 6.0, 2.0], None);
+        assert!(close(c.center, 4.0, 1e-12));
+        assert!(close(c.ucl[0], 10.0, 1e-12));
+        assert_eq!(c.lcl[0], 0.0);
+
+        // u: 10 defects over 6 units; limits widen for the sample with fewer units
+        let u = PlotOxide::calculate_uchart(&[4.0, 6.0], &[2.0, 4.0], None);
+        let u_bar = 10.0 / 6.0;
+        assert!(close(u.center, u_bar, 1e-12));
+        assert_eq!(u.points, vec![[0.0, 2.0], [1.0, 1.5]]);
+        assert!(close(u.ucl[0], u_bar + 3.0 * (u_bar / 2.0).sqrt(), 1e-12));
+        assert!(close(u.ucl[1], u_bar + 3.0 * (u_bar / 4.0).sqrt(), 1e-12));
+        assert!(u.ucl[0] > u.ucl[1]);
+
+        // p with sample sizes 100 and 200: p-bar 15 / 300
+        let p = PlotOxide::calculate_pchart_variable(&[5.0, 10.0], &[100.0, 200.0], None);
+        assert!(close(p.center, 0.05, 1e-12));
+        assert!(close(p.ucl[0], 0.05 + 3.0 * (0.05f64 * 0.95 / 100.0).sqrt(), 1e-12));
+        assert!(close(p.lcl[1], (0.05 - 3.0 * (0.05f64 * 0.95 / 200.0).sqrt()).max(0.0), 1e-12));
+        assert!(PlotOxide::calculate_uchart(&[1.0], &[1.0, 2.0], None).points.is_empty());
+    }
+
+    #[test]
+    fn test_cusum_worked_example() {
+        // Montgomery, Introduction to Statistical Quality Control, Example 9.1:
+        // target 10, sigma 1, k = 0.5, h = 5; C+ first exceeds 5 at period 29
+        let values = [
+            9.45, 7.99, 9.29, 11.66, 12.16, 10.18, 8.04, 11.46, 9.20, 10.34,
+            9.03, 11.47, 10.51, 9.40, 10.08, 9.37, 10.62, 10.31, 8.52, 10.84,
+            10.90, 9.33, 12.29, 11.50, 10.60, 11.08, 10.38, 11.62, 11.31, 10.52,
+        ];
+        let chart = PlotOxide::calculate_cusum(&values, 10.0, 1.0, 0.5, 5.0);
+        assert!(close(chart.upper[0][1], 0.0, 1e-9));
+        assert!(close(chart.lower[1][1], 1.56, 1e-9));
+        assert!(close(chart.upper[28][1], 5.28, 1e-9));
+        assert!(close(chart.upper[29][1], 5.30, 1e-9));
+        assert!(chart.lower[29][1] == 0.0);
+        assert_eq!(chart.signals, vec![28, 29]);
+        assert!(close(chart.decision_interval, 5.0, 1e-12));
+    }
+
+    #[test]
+    fn test_ewma_limits() {
+        // λ = 0.2, L = 3: the limits widen from 3·sqrt(λ/(2-λ)·(1-0.8²)) = 0.6σ towards 1σ
+        let values = vec![10.5, 9.0].into_iter().chain(std::iter::repeat_n(10.0, 48)).collect::<Vec<_>>();
+        let chart = PlotOxide::calculate_ewma_chart(&values, 0.2, 3.0, 10.0, 1.0);
+        assert!(close(chart.points[0][1], 10.1, 1e-12));
+        assert!(close(chart.points[1][1], 9.88, 1e-12));
+        assert!(close(chart.ucl[0], 10.6, 1e-12));
+        assert!(close(chart.lcl[0], 9.4, 1e-12));
+        assert!(close(chart.ucl[1], 10.0 + 3.0 * (0.2f64 / 1.8 * (1.0 - 0.8f64.powi(4))).sqrt(), 1e-12));
+        assert!(close(chart.ucl[49], 11.0, 1e-9));
+        assert!(PlotOxide::calculate_ewma_chart(&values, 0.2, 3.0, 10.0, 0.0).points.is_empty());
+    }
+
+    fn app_with(df: polars::prelude::DataFrame) -> PlotOxide {
+        let mut app = PlotOxide::default();
+        app.state.data = Some(data::DataSource::from_dataframe(df, None).unwrap());
+        app
+    }
+
+    #[test]
+    fn test_frozen_limits_stay_put() {
+        use polars::prelude::*;
+        let mut app = app_with(df!("t" => [0i64, 1, 2, 3, 4, 5], "y" => [1.0, 3.0, 1.0, 3.0, 50.0, 60.0]).unwrap());
+        app.state.spc.baseline_rows = (0, 4);
+        app.freeze_limits(1).unwrap();
+        assert_eq!(app.limit_statistics(1, &[]), (2.0, 1.0));
+
+        // New data under the same column name keeps the Phase I limits
+        app.state.data = Some(data::DataSource::from_dataframe(df!("t" => [0i64, 1], "y" => [100.0, 200.0]).unwrap(), None).unwrap());
+        assert_eq!(app.limit_statistics(1, &[100.0, 200.0]), (2.0, 1.0));
+        let frozen = &app.state.spc.frozen_limits["y"];
+        assert_eq!((frozen.baseline_start, frozen.baseline_end), (0, 4));
+
+        app.unfreeze_limits(1);
+        assert_eq!(app.limit_statistics(1, &[100.0, 200.0]), (150.0, 50.0));
+        // A baseline with fewer than two values is refused
+        app.state.spc.baseline_rows = (1, 2);
+        assert!(app.freeze_limits(1).is_err());
+    }
+
+    #[test]
+    fn test_frozen_limits_reach_control_charts() {
+        use polars::prelude::*;
+        let mut app = app_with(df!("y" => [9.0, 11.0, 10.0, 12.0, 8.0, 10.0, 11.0, 9.0]).unwrap());
+        app.state.spc.baseline_rows = (0, 8);
+        app.freeze_limits(0).unwrap();
+        let frozen = app.frozen_limits(0);
+        let values = |app: &PlotOxide| app.state.data.as_ref().unwrap().get_cached_column(0).unwrap().clone();
+        let charts = |values: &[f64]| (
+            PlotOxide::calculate_imr(values, frozen),
+            PlotOxide::calculate_xbars(values, 4, frozen),
+            PlotOxide::calculate_xbarr(values, 4, frozen),
+            PlotOxide::calculate_cchart(values, frozen.map(|f| f.mean)),
+        );
+        let (imr, xbars, xbarr, c) = charts(&values(&app));
+
+        // An out-of-control run appended after freezing leaves every chart's limits in place
+        let run = df!("y" => [30.0, 32.0, 31.0, 33.0, 30.0, 34.0, 32.0, 31.0]).unwrap();
+        app.state.data.as_mut().unwrap().append(&run).unwrap();
+        let values = values(&app);
+        let (imr_after, xbars_after, xbarr_after, c_after) = charts(&values);
+        assert_eq!(imr_after.location_limits, imr.location_limits);
+        assert_eq!(imr_after.dispersion_limits, imr.dispersion_limits);
+        assert_eq!(xbars_after.location_limits, xbars.location_limits);
+        assert_eq!(xbars_after.dispersion_limits, xbars.dispersion_limits);
+        assert_eq!((xbarr_after.2, xbarr_after.3, xbarr_after.5, xbarr_after.6), (xbarr.2, xbarr.3, xbarr.5, xbarr.6));
+        assert_eq!((c_after.center, c_after.ucl[0]), (c.center, c.ucl[0]));
+        assert!(imr_after.location[8..].iter().all(|p| imr_after.location_limits.is_out_of_control(p[1])));
+
+        // Centred on the Phase I mean with the Phase I sigma
+        let (mean, std_dev) = app.limit_statistics(0, &values);
+        assert_eq!(imr_after.location_limits, state::ControlLimits { center: mean, ucl: mean + 3.0 * std_dev, lcl: mean - 3.0 * std_dev });
+        assert!(close(xbars_after.sigma, std_dev, 1e-12));
+        // Unfrozen, the limits follow the run
+        assert!(PlotOxide::calculate_imr(&values, None).location_limits.center > 15.0);
+    }
+
+    #[test]
+    fn test_row_filters_that_do_not_apply_are_marked() {
+        use polars::prelude::*;
+        let mut app = app_with(df!("t" => [0i64, 1, 2], "value" => [5.0, 50.0, 500.0]).unwrap());
+        app.add_row_filter("value > 10").unwrap();
+        assert_eq!(app.state.row_count(), 2);
+        assert!(app.add_row_filter("missing > 1").is_err());
+
+        // Data without the column shows every row, and the filter says why it is off
+        app.state.data = Some(data::DataSource::from_dataframe(df!("t" => [0i64, 1]).unwrap(), None).unwrap());
+        app.on_dataset_changed();
+        let filter = &app.state.filters.expressions[0];
+        assert!(filter.enabled && filter.error.as_deref().is_some_and(|e| e.starts_with("Not applied")));
+        assert!(!app.state.filters.has_active_filters());
+        assert_eq!(app.state.data.as_ref().unwrap().unfiltered_height(), None);
+
+        // It applies again once the data has the column
+        app.state.data = Some(data::DataSource::from_dataframe(df!("value" => [1.0, 20.0]).unwrap(), None).unwrap());
+        app.on_dataset_changed();
+        assert_eq!(app.state.filters.expressions[0].error, None);
+        assert_eq!(app.state.row_count(), 1);
+    }
+
+    #[test]
+    fn test_tabs_park_and_restore() {
+        use polars::prelude::*;
+        let source = |df: DataFrame| data::DataSource::from_dataframe(df, None).unwrap();
+        let mut app = PlotOxide::default();
+        let a = app.open_tab(source(df!("t" => [0i64, 1, 2], "a" => [1.0, 2.0, 3.0]).unwrap()), "a".into(), None);
+        let b = app.open_tab(source(df!("t" => [0i64, 1], "b" => [5.0, 6.0], "c" => [7.0, 8.0]).unwrap()), "b".into(), None);
+        app.state.view.y_indices = vec![1, 2];
+
+        // Opening b parked a with its columns
+        assert_eq!(app.state.active_tab_id(), Some(b));
+        let (parked, view) = app.state.tabs[0].parked.as_ref().unwrap();
+        assert_eq!((parked.height(), view.y_indices.clone()), (3, vec![1]));
+
+        // Parked and shown data are both reachable by id
+        app.state.dataset_mut(a).unwrap().append(&df!("t" => [3i64], "a" => [4.0]).unwrap()).unwrap();
+        assert_eq!(app.state.dataset(a).unwrap().height(), 4);
+        assert_eq!(app.state.dataset_mut(b).unwrap().height(), 2);
+        assert!(app.state.dataset_mut(b + 1).is_none());
+
+        app.switch_tab(0);
+        assert_eq!(app.state.active_tab_id(), Some(a));
+        assert_eq!(app.state.data.as_ref().unwrap().height(), 4);
+        assert_eq!(app.state.view.y_indices, vec![1]);
+        assert_eq!(app.state.tabs[1].parked.as_ref().unwrap().1.y_indices, vec![1, 2]);
+        app.switch_tab(5);
+        assert_eq!(app.state.active_tab_id(), Some(a));
+
+        // Parking leaves nothing shown until a tab is shown again
+        app.park_active();
+        assert!(app.state.data.is_none() && app.state.tabs[0].parked.is_some());
+        app.show_parked(0);
+
+        // Closing the shown tab shows its neighbour and drops overlays of the closed one
+        app.state.view.overlays.push(state::OverlaySeries { tab: a, column: "a".into() });
+        app.close_tab(0);
+        assert_eq!(app.state.tabs.len(), 1);
+        assert_eq!(app.state.active_tab_id(), Some(b));
+        assert_eq!(app.state.view.y_indices, vec![1, 2]);
+        assert!(app.state.view.overlays.is_empty());
+        app.close_tab(0);
+        assert!(app.state.tabs.is_empty() && app.state.data.is_none());
+    }
+
+    #[test]
+    fn test_close_tab_before_shown_one() {
+        use polars::prelude::*;
+        let source = |df: DataFrame| data::DataSource::from_dataframe(df, None).unwrap();
+        let mut app = PlotOxide::default();
+        for name in ["a", "b", "c"] {
+            app.open_tab(source(df!("t" => [0i64], "y" => [1.0]).unwrap()), name.into(), None);
+        }
+        let shown = app.state.active_tab_id();
+        app.close_tab(0);
+        assert_eq!(app.state.active_tab, 1);
+        assert_eq!(app.state.active_tab_id(), shown);
+        assert!(app.state.data.is_some());
+    }
+}
@@ -19,15 +19,57 @@ pub mod spc {
     /// Default EWMA lambda (smoothing constant)
     pub const DEFAULT_EWMA_LAMBDA: f64 = 0.2;
 
+    /// Default EWMA control limit width L (in sigma units)
+    pub const DEFAULT_EWMA_L: f64 = 3.0;
+
     /// Default regression order (1=linear, 2=quadratic)
     pub const DEFAULT_REGRESSION_ORDER: usize = 1;
 
     /// Default X-bar R chart subgroup size
     pub const DEFAULT_XBARR_SUBGROUP: usize = 5;
 
+    /// d2 bias-correction constant for moving ranges of span 2
+    pub const MR_D2: f64 = 1.128;
+
+    /// D4 constant for the moving range chart (span 2)
+    pub const MR_D4: f64 = 3.267;
+
+    /// d2 constants for subgroup ranges, n = 2..=10
+    pub const D2_TABLE: [f64; 9] = [1.128, 1.693, 2.059, 2.326, 2.534, 2.704, 2.847, 2.970, 3.078];
+
+    /// Confidence level for capability index intervals
+    pub const CAPABILITY_CONFIDENCE: f64 = 0.95;
+
+    /// Default subgroup size for within sigma in capability studies (1 = moving range)
+    pub const DEFAULT_CAPABILITY_SUBGROUP: usize = 1;
+
+    /// Default number of baseline rows for Phase I limits
+    pub const DEFAULT_BASELINE_ROWS: usize = 25;
+
+    /// Default CUSUM reference value k (in sigma units)
+    pub const DEFAULT_CUSUM_K: f64 = 0.5;
+
+    /// Default CUSUM decision interval h (in sigma units)
+    pub const DEFAULT_CUSUM_H: f64 = 5.0;
+
+    /// Hotelling T² false alarm rate (matches 3σ limits)
+    pub const HOTELLING_ALPHA: f64 = 0.0027;
+
+    /// Most strata a group-by column may split a series into
+    pub const MAX_STRATA: usize = 24;
+
+    /// Gage R&R: interaction p-value above which it is pooled into repeatability
+    pub const GAGE_INTERACTION_ALPHA: f64 = 0.05;
+
+    /// Gage R&R: standard deviations spanned by the study variation
+    pub const GAGE_STUDY_SIGMAS: f64 = 6.0;
+
     /// Default p-chart sample size
     pub const DEFAULT_PCHART_SAMPLE: usize = 50;
 
+    /// Default u-chart units inspected per sample
+    pub const DEFAULT_UCHART_UNITS: f64 = 1.0;
+
     /// Default lower specification limit
     pub const DEFAULT_SPEC_LOWER: f64 = 0.0;
 
@@ -48,6 +90,45 @@ pub mod performance {
 
     /// Maximum number of recent files to track
     pub const MAX_RECENT_FILES: usize = 10;
+
+    /// Files larger than this are streamed from disk instead of loaded into memory
+    pub const STREAMING_THRESHOLD_BYTES: u64 = 1 << 30;
+
+    /// Rows per level-0 bucket of an in-memory column's min/max pyramid
+    pub const PYRAMID_BASE_ROWS: usize = 32;
+
+    /// Rows per level-0 bucket of a streamed column's min/max summary
+    pub const SUMMARY_BASE_ROWS: usize = 1024;
+
+    /// Rows read per step when summarising a streamed column
+    pub const STREAM_CHUNK_ROWS: usize = 1 << 20;
+
+    /// Largest visible row range a streamed plot reads point by point instead of summarised
+    pub const STREAM_RAW_ROWS: usize = 20_000;
+
+    /// Rows fetched per page by the data table while streaming
+    pub const STREAM_PAGE_ROWS: usize = 500;
+
+    /// How often a followed file is checked for appended rows
+    pub const FOLLOW_POLL_MS: u64 = 500;
+
+    /// How often a live source is checked for new lines
+    pub const LIVE_POLL_MS: u64 = 100;
+
+    /// Most lines parsed per batch from a live source
+    pub const LIVE_BATCH_LINES: usize = 50_000;
+
+    /// Rows kept by default from a live source
+    pub const DEFAULT_LIVE_ROWS: usize = 10_000;
+
+    /// Chunks appended rows may split a data set into before it is made contiguous again
+    pub const MAX_APPENDED_CHUNKS: usize = 32;
+}
+
+/// File import defaults
+pub mod import {
+    /// Rows shown in the import dialog preview
+    pub const PREVIEW_ROWS: usize = 20;
 }
 
 /// Plotting and visualization defaults
@@ -57,6 +138,12 @@ pub mod plot {
 
     /// Tolerance for point selection (in normalized plot coordinates)
     pub const POINT_SELECT_TOLERANCE: f64 = 0.0004;
+
+    /// Points kept in view by default when following a file
+    pub const DEFAULT_FOLLOW_POINTS: usize = 1000;
+
+    /// Seconds (X units) kept in view by default when following a file
+    pub const DEFAULT_FOLLOW_SECONDS: f64 = 60.0;
 }
 
 /// UI layout defaults
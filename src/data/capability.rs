@@ -0,0 +1,282 @@
+//! Process capability analysis (Cp/Cpk from within sigma, Pp/Ppk from overall sigma)
+
+use crate::constants::spc::{CAPABILITY_CONFIDENCE, D2_TABLE, MR_D2};
+use crate::data::stats::{
+    anderson_darling_statistic, chi_square_quantile, normal_cdf, normal_quantile, Distribution, DistributionKind,
+};
+
+/// Capability indices for one column against a pair of spec limits
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CapabilityReport {
+    /// Number of finite observations
+    pub n: usize,
+    /// Sample mean
+    pub mean: f64,
+    /// Within-subgroup sigma (R-bar/d2, or MR-bar/d2 for individuals)
+    pub sigma_within: f64,
+    /// Overall sample standard deviation
+    pub sigma_overall: f64,
+    /// Potential capability from within sigma
+    pub cp: f64,
+    /// Actual capability from within sigma
+    pub cpk: f64,
+    /// Potential performance from overall sigma
+    pub pp: f64,
+    /// Actual performance from overall sigma
+    pub ppk: f64,
+    /// Taguchi index around the target, if a target is set
+    pub cpm: Option<f64>,
+    /// Confidence intervals (lower, upper) at [`CAPABILITY_CONFIDENCE`]
+    pub cp_ci: (f64, f64),
+    pub cpk_ci: (f64, f64),
+    pub pp_ci: (f64, f64),
+    pub ppk_ci: (f64, f64),
+    /// Expected parts per million out of spec using within sigma
+    pub ppm_within: f64,
+    /// Expected parts per million out of spec using overall sigma
+    pub ppm_overall: f64,
+    /// Observed parts per million out of spec
+    pub ppm_observed: f64,
+}
+
+/// d2 constant for ranges of `n` observations (2..=10)
+pub fn d2(n: usize) -> Option<f64> {
+    D2_TABLE.get(n.checked_sub(2)?).copied()
+}
+
+/// Within-subgroup sigma: R-bar/d2 over consecutive subgroups, or MR-bar/d2 when `subgroup_size` is 1
+pub fn within_sigma(values: &[f64], subgroup_size: usize) -> Option<f64> {
+    if subgroup_size <= 1 {
+        if values.len() < 2 {
+            return None;
+        }
+        let mr_bar = values.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>()
+            / (values.len() - 1) as f64;
+        return Some(mr_bar / MR_D2);
+    }
+
+    let d2 = d2(subgroup_size)?;
+    let ranges: Vec<f64> = values.chunks_exact(subgroup_size)
+        .map(|g| {
+            let max = g.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let min = g.iter().copied().fold(f64::INFINITY, f64::min);
+            max - min
+        })
+        .collect();
+    if ranges.is_empty() {
+        return None;
+    }
+    Some(ranges.iter().sum::<f64>() / ranges.len() as f64 / d2)
+}
+
+/// Build a capability report. Returns None when there are too few values, the spec
+/// limits are inverted, or either sigma estimate is zero.
+pub fn capability_report(
+    values: &[f64],
+    lsl: f64,
+    usl: f64,
+    target: Option<f64>,
+    subgroup_size: usize,
+) -> Option<CapabilityReport> {
+    let n = values.len();
+    if n < 2 || usl <= lsl {
+        return None;
+    }
+
+    let mean = values.iter().sum::<f64>() / n as f64;
+    let sigma_overall = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt();
+    let sigma_within = within_sigma(values, subgroup_size)?;
+    if sigma_overall <= 0.0 || sigma_within <= 0.0 {
+        return None;
+    }
+
+    let potential = |sigma: f64| (usl - lsl) / (6.0 * sigma);
+    let actual = |sigma: f64| ((usl - mean) / (3.0 * sigma)).min((mean - lsl) / (3.0 * sigma));
+    let expected_ppm = |sigma: f64| {
+        (normal_cdf((lsl - mean) / sigma) + 1.0 - normal_cdf((usl - mean) / sigma)) * 1e6
+    };
+
+    let (cp, cpk) = (potential(sigma_within), actual(sigma_within));
+    let (pp, ppk) = (potential(sigma_overall), actual(sigma_overall));
+    let cpm = target.map(|t| (usl - lsl) / (6.0 * (sigma_overall.powi(2) + (mean - t).powi(2)).sqrt()));
+
+    let out_of_spec = values.iter().filter(|&&v| v < lsl || v > usl).count();
+
+    Some(CapabilityReport {
+        n,
+        mean,
+        sigma_within,
+        sigma_overall,
+        cp,
+        cpk,
+        pp,
+        ppk,
+        cpm,
+        cp_ci: potential_ci(cp, n),
+        cpk_ci: actual_ci(cpk, n),
+        pp_ci: potential_ci(pp, n),
+        ppk_ci: actual_ci(ppk, n),
+        ppm_within: expected_ppm(sigma_within),
+        ppm_overall: expected_ppm(sigma_overall),
+        ppm_observed: out_of_spec as f64 / n as f64 * 1e6,
+    })
+}
+
+/// A fitted candidate distribution with its goodness-of-fit score
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistributionFit {
+    /// Fitted distribution
+    pub distribution: Distribution,
+    /// Anderson-Darling A² against the data (lower = better fit)
+    pub ad_statistic: f64,
+}
+
+/// Fit every candidate family that applies to the data, best fit first
+pub fn fit_candidates(values: &[f64]) -> Vec<DistributionFit> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut fits: Vec<DistributionFit> = DistributionKind::ALL.iter()
+        .filter_map(|kind| kind.fit(&sorted))
+        .map(|distribution| DistributionFit {
+            distribution,
+            ad_statistic: anderson_darling_statistic(&sorted, |x| distribution.cdf(x)),
+        })
+        .filter(|fit| fit.ad_statistic.is_finite())
+        .collect();
+    fits.sort_by(|a, b| a.ad_statistic.partial_cmp(&b.ad_statistic).unwrap_or(std::cmp::Ordering::Equal));
+    fits
+}
+
+/// Percentile-based capability (Clements method) from a fitted distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PercentileCapability {
+    /// 0.135th percentile of the fit
+    pub lower_percentile: f64,
+    /// Median of the fit
+    pub median: f64,
+    /// 99.865th percentile of the fit
+    pub upper_percentile: f64,
+    /// (USL - LSL) / (X99.865 - X0.135)
+    pub pp: f64,
+    /// min(PPU, PPL) measured from the median
+    pub ppk: f64,
+    /// Expected parts per million out of spec under the fit
+    pub ppm: f64,
+}
+
+/// Clements capability: the normal ±3σ points are replaced by the fitted 0.135% and 99.865% percentiles
+pub fn percentile_capability(distribution: &Distribution, lsl: f64, usl: f64) -> PercentileCapability {
+    let lower_percentile = distribution.quantile(normal_cdf(-3.0));
+    let median = distribution.quantile(0.5);
+    let upper_percentile = distribution.quantile(normal_cdf(3.0));
+
+    let ppu = (usl - median) / (upper_percentile - median);
+    let ppl = (median - lsl) / (median - lower_percentile);
+
+    PercentileCapability {
+        lower_percentile,
+        median,
+        upper_percentile,
+        pp: (usl - lsl) / (upper_percentile - lower_percentile),
+        ppk: ppu.min(ppl),
+        ppm: (distribution.cdf(lsl) + 1.0 - distribution.cdf(usl)) * 1e6,
+    }
+}
+
+/// Chi-square interval for Cp / Pp
+fn potential_ci(index: f64, n: usize) -> (f64, f64) {
+    let alpha = 1.0 - CAPABILITY_CONFIDENCE;
+    let df = (n - 1) as f64;
+    (
+        index * (chi_square_quantile(alpha / 2.0, df) / df).sqrt(),
+        index * (chi_square_quantile(1.0 - alpha / 2.0, df) / df).sqrt(),
+    )
+}
+
+/// Bissell's normal approximation interval for Cpk / Ppk
+fn actual_ci(index: f64, n: usize) -> (f64, f64) {
+    let z = normal_quantile(1.0 - (1.0 - CAPABILITY_CONFIDENCE) / 2.0);
+    let n = n as f64;
+    let half_width = z * (1.0 / (9.0 * n) + index * index / (2.0 * (n - 1.0))).sqrt();
+    (index - half_width, index + half_width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within_sigma_moving_range() {
+        let values = [10.0, 12.0, 11.0, 13.0, 12.0];
+        // MR = 2, 1, 2, 1 -> MR-bar = 1.5
+        let sigma = within_sigma(&values, 1).unwrap();
+        assert!((sigma - 1.5 / MR_D2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_within_sigma_subgroups() {
+        let values = [1.0, 3.0, 2.0, 2.0, 6.0, 4.0];
+        // Ranges: 2, 4 -> R-bar = 3, d2(3) = 1.693
+        let sigma = within_sigma(&values, 3).unwrap();
+        assert!((sigma - 3.0 / 1.693).abs() < 1e-12);
+        assert!(within_sigma(&values, 11).is_none());
+    }
+
+    #[test]
+    fn test_capability_indices() {
+        let values: Vec<f64> = (0..100).map(|i| 50.0 + ((i * 37) % 11) as f64 - 5.0).collect();
+        let report = capability_report(&values, 20.0, 80.0, Some(50.0), 1).unwrap();
+
+        assert_eq!(report.n, 100);
+        assert!((report.pp - 60.0 / (6.0 * report.sigma_overall)).abs() < 1e-12);
+        assert!(report.ppk <= report.pp);
+        assert!(report.cpm.unwrap() <= report.pp + 1e-12);
+        assert!(report.cp_ci.0 < report.cp && report.cp < report.cp_ci.1);
+        assert!(report.ppk_ci.0 < report.ppk && report.ppk < report.ppk_ci.1);
+        assert_eq!(report.ppm_observed, 0.0);
+    }
+
+    #[test]
+    fn test_expected_ppm_at_three_sigma() {
+        // Symmetric +-1 data: overall sigma ~1, so spec at +-3 gives ~2700 PPM
+        let values: Vec<f64> = (0..10_000).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 }).collect();
+        let report = capability_report(&values, -3.0, 3.0, None, 1).unwrap();
+        assert!((report.ppm_overall - 2_700.0).abs() < 5.0);
+        assert!(report.cpm.is_none());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(capability_report(&[1.0], 0.0, 2.0, None, 1).is_none());
+        assert!(capability_report(&[1.0, 2.0, 3.0], 5.0, 1.0, None, 1).is_none());
+        assert!(capability_report(&[1.0, 1.0, 1.0], 0.0, 2.0, None, 1).is_none());
+    }
+
+    #[test]
+    fn test_percentile_capability_matches_normal_theory() {
+        // For a normal fit the Clements indices equal Pp/Ppk
+        let dist = Distribution::Normal { mean: 10.0, std_dev: 1.0 };
+        let pc = percentile_capability(&dist, 4.0, 19.0);
+        assert!((pc.pp - 15.0 / 6.0).abs() < 1e-6);
+        assert!((pc.ppk - 2.0).abs() < 1e-6);
+        assert!((pc.median - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_candidates_ranks_skewed_data() {
+        let dist = Distribution::Lognormal { mu: 0.0, sigma: 0.8 };
+        let values: Vec<f64> = (0..400).map(|i| dist.quantile((i as f64 + 0.5) / 400.0)).collect();
+        let fits = fit_candidates(&values);
+
+        assert!(fits.len() >= 4);
+        assert!(fits.windows(2).all(|w| w[0].ad_statistic <= w[1].ad_statistic));
+        assert_ne!(fits[0].distribution.kind(), DistributionKind::Normal);
+
+        // Negative data rules out the positive-only families
+        let shifted: Vec<f64> = values.iter().map(|v| v - 5.0).collect();
+        let kinds: Vec<DistributionKind> = fit_candidates(&shifted).iter().map(|f| f.distribution.kind()).collect();
+        assert!(!kinds.contains(&DistributionKind::Lognormal));
+        assert!(!kinds.contains(&DistributionKind::Weibull));
+    }
+}
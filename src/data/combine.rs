@@ -0,0 +1,194 @@
+//! Combining open data sets
+//!
+//! Files with the same columns are stacked into one, tagged with where each row came from;
+//! two data sets are joined on a key column, or on the nearest timestamp within a tolerance.
+//! Both build a lazy plan over the sources' `LazyFrame`s, collected on the background worker.
+
+use super::{DataError, DataSource};
+use polars::prelude::*;
+
+/// Which rows a key join keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Rows whose key is in both data sets
+    Inner,
+    /// Every row of the left data set, with nulls where the right has no match
+    Left,
+    /// Every row of both
+    Full,
+}
+
+impl JoinKind {
+    pub const ALL: [JoinKind; 3] = [JoinKind::Inner, JoinKind::Left, JoinKind::Full];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            JoinKind::Inner => "Inner",
+            JoinKind::Left => "Left",
+            JoinKind::Full => "Full outer",
+        }
+    }
+}
+
+/// How rows of two data sets are matched
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JoinOn {
+    /// Rows with equal key values
+    Key(JoinKind),
+    /// Each left row with the right row whose key is nearest, if within `tolerance` (in key
+    /// units; seconds for timestamps). Both sides are sorted by their keys first.
+    Nearest { tolerance: Option<f64> },
+}
+
+/// Stack data sets with the same columns, in the order given, adding a `tag_column` that
+/// names the source of each row. Columns are matched by name and take the first data set's
+/// order; types are widened where they differ (e.g. integers and floats).
+pub fn concat_plan(parts: &[(&str, &DataSource)], tag_column: &str) -> Result<LazyFrame, DataError> {
+    let Some((_, first)) = parts.first() else {
+        return Err(DataError::SchemaMismatch("nothing to combine".to_string()));
+    };
+    let columns = first.column_names();
+    let mut sorted = columns.clone();
+    sorted.sort();
+
+    let mut frames = Vec::with_capacity(parts.len());
+    for &(name, ds) in parts {
+        let mut names = ds.column_names();
+        names.sort();
+        if names != sorted {
+            return Err(DataError::SchemaMismatch(format!("{} has columns {}", name, names.join(", "))));
+        }
+        let select: Vec<Expr> = std::iter::once(lit(name).alias(tag_column))
+            .chain(columns.iter().filter(|c| *c != tag_column).map(|c| col(c.as_str())))
+            .collect();
+        frames.push(ds.lazy().select(select));
+    }
+    let args = UnionArgs { to_supertypes: true, ..Default::default() };
+    Ok(concat(frames, args)?)
+}
+
+/// Join `right` onto `left`, matching `left_on` with `right_on`. Right columns whose names
+/// clash with left ones get a "_right" suffix.
+pub fn join_plan(
+    left: &DataSource,
+    right: &DataSource,
+    left_on: &str,
+    right_on: &str,
+    on: JoinOn,
+) -> Result<LazyFrame, DataError> {
+    let (mut lf, mut rf) = (left.lazy(), right.lazy());
+    let key_type = lf.collect_schema()?
+        .get(left_on)
+        .cloned()
+        .ok_or_else(|| DataError::ColumnNotFound(left_on.to_string()))?;
+    if rf.collect_schema()?.get(right_on).is_none() {
+        return Err(DataError::ColumnNotFound(right_on.to_string()));
+    }
+    // Keys must have the same type on both sides
+    rf = rf.with_column(col(right_on).cast(key_type.clone()));
+
+    let args = match on {
+        JoinOn::Key(kind) => {
+            let how = match kind {
+                JoinKind::Inner => JoinType::Inner,
+                JoinKind::Left => JoinType::Left,
+                JoinKind::Full => JoinType::Full,
+            };
+            JoinArgs::new(how).with_coalesce(JoinCoalesce::CoalesceColumns)
+        }
+        JoinOn::Nearest { tolerance } => {
+            lf = lf.sort([left_on], Default::default());
+            rf = rf.sort([right_on], Default::default());
+            let tolerance = tolerance.map(|t| key_tolerance(t, &key_type)).transpose()?;
+            JoinArgs::new(JoinType::AsOf(AsOfOptions {
+                strategy: AsofStrategy::Nearest,
+                tolerance,
+                ..Default::default()
+            }))
+        }
+    };
+    Ok(lf.join(rf, [col(left_on)], [col(right_on)], args))
+}
+
+/// A tolerance given in key units (seconds for timestamps) in the key's physical units
+fn key_tolerance(tolerance: f64, key_type: &DataType) -> Result<AnyValue<'static>, DataError> {
+    let tolerance = tolerance.abs();
+    Ok(match key_type {
+        DataType::Datetime(unit, _) => {
+            let per_second = match unit {
+                TimeUnit::Milliseconds => 1e3,
+                TimeUnit::Microseconds => 1e6,
+                TimeUnit::Nanoseconds => 1e9,
+            };
+            AnyValue::Int64((tolerance * per_second).round() as i64)
+        }
+        DataType::Date => AnyValue::Int32((tolerance / 86_400.0).round() as i32),
+        dtype if dtype.is_primitive_numeric() => AnyValue::Float64(tolerance),
+        dtype => {
+            return Err(DataError::UnsupportedFormat(format!("nearest-match join on a {} column", dtype)));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(df: DataFrame) -> DataSource {
+        DataSource::from_dataframe(df, None).unwrap()
+    }
+
+    #[test]
+    fn test_concat_tags_rows() {
+        let a = source(df!("x" => [1i64, 2], "y" => [0.5, 1.5]).unwrap());
+        let b = source(df!("y" => [2.5], "x" => [3.0]).unwrap());
+        let out = concat_plan(&[("a.csv", &a), ("b.csv", &b)], "source").unwrap().collect().unwrap();
+
+        assert_eq!(out.get_column_names(), vec!["source", "x", "y"]);
+        assert_eq!(out.height(), 3);
+        assert_eq!(out.column("x").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(out.column("source").unwrap().str().unwrap().get(2), Some("b.csv"));
+
+        let c = source(df!("x" => [1], "z" => [2]).unwrap());
+        assert!(matches!(concat_plan(&[("a", &a), ("c", &c)], "source"), Err(DataError::SchemaMismatch(_))));
+    }
+
+    #[test]
+    fn test_key_join() {
+        let left = source(df!("id" => [1i64, 2, 3], "v" => [10.0, 20.0, 30.0]).unwrap());
+        let right = source(df!("part" => [2i32, 3, 4], "v" => [0.2, 0.3, 0.4]).unwrap());
+
+        let inner = join_plan(&left, &right, "id", "part", JoinOn::Key(JoinKind::Inner)).unwrap().collect().unwrap();
+        assert_eq!(inner.height(), 2);
+        assert_eq!(inner.get_column_names(), vec!["id", "v", "v_right"]);
+
+        let full = join_plan(&left, &right, "id", "part", JoinOn::Key(JoinKind::Full)).unwrap().collect().unwrap();
+        assert_eq!(full.height(), 4);
+        assert_eq!(full.column("id").unwrap().null_count(), 0);
+
+        assert!(matches!(
+            join_plan(&left, &right, "id", "missing", JoinOn::Key(JoinKind::Left)),
+            Err(DataError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_nearest_timestamp_join() {
+        let ms = |s: &[i64]| Series::new("t".into(), s.iter().map(|s| s * 1000).collect::<Vec<_>>())
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap();
+        // Measurements out of order; parameters logged at 0 s, 10 s and 20 s
+        let left = source(DataFrame::new(vec![ms(&[12, 1, 40]).into(), Series::new("m".into(), [1.0, 2.0, 3.0]).into()]).unwrap());
+        let right = source(DataFrame::new(vec![ms(&[0, 10, 20]).into(), Series::new("p".into(), [100, 200, 300]).into()]).unwrap());
+
+        let out = join_plan(&left, &right, "t", "t", JoinOn::Nearest { tolerance: Some(5.0) })
+            .unwrap()
+            .collect()
+            .unwrap();
+        let p = out.column("p").unwrap().i32().unwrap();
+        // Sorted by time: 1 s → 0 s, 12 s → 10 s, 40 s has nothing within 5 s
+        assert_eq!(p.get(0), Some(100));
+        assert_eq!(p.get(1), Some(200));
+        assert_eq!(p.get(2), None);
+    }
+}
@@ -0,0 +1,612 @@
+//! A small expression language compiled to Polars expressions, for computed columns and
+//! row filters
+//!
+//! ```text
+//! (temp_out - temp_in) / flow
+//! if(`flow rate` > 0, rolling_mean(pressure, 10), 0)
+//! category == "A" and value > 100 and timestamp >= 2024-01-02
+//! ```
+//!
+//! Columns are referred to by name, in backticks when the name is not a plain identifier.
+//! Dates are written `2024-01-02`, times `2024-01-02T08:30` or `2024-01-02 08:30:15`.
+//! Operators, loosest first: `or`, `and`, `not`, comparisons (`== != < <= > >=`),
+//! `+ -`, `* / %`, unary `-`, `^`. Functions are listed in [`FUNCTIONS`]. The row functions
+//! `lag`, `diff` and `rolling_*` read a fixed number of earlier rows and `cumsum` keeps a
+//! running total, so a [`Compiled`] computed column can be evaluated for appended rows
+//! without going over the earlier ones again.
+
+use polars::prelude::*;
+
+/// Function names with their signatures, for help text
+pub const FUNCTIONS: &[(&str, &str)] = &[
+    ("abs", "abs(x)"),
+    ("sqrt", "sqrt(x)"),
+    ("exp", "exp(x)"),
+    ("ln", "ln(x)"),
+    ("log10", "log10(x)"),
+    ("sin", "sin(x)"),
+    ("cos", "cos(x)"),
+    ("tan", "tan(x)"),
+    ("floor", "floor(x)"),
+    ("ceil", "ceil(x)"),
+    ("round", "round(x, decimals)"),
+    ("min", "min(a, b)"),
+    ("max", "max(a, b)"),
+    ("if", "if(condition, then, else)"),
+    ("lag", "lag(x, n)"),
+    ("diff", "diff(x, n)"),
+    ("cumsum", "cumsum(x)"),
+    ("rolling_mean", "rolling_mean(x, window)"),
+    ("rolling_sum", "rolling_sum(x, window)"),
+    ("rolling_min", "rolling_min(x, window)"),
+    ("rolling_max", "rolling_max(x, window)"),
+    ("rolling_std", "rolling_std(x, window)"),
+];
+
+/// Why an expression could not be compiled, and where
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    /// Character offset into the source
+    pub offset: usize,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at character {})", self.message, self.offset + 1)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Boolean column marking the appended rows in the frame given to [`Compiled::over_new_rows`]
+pub const NEW_ROWS: &str = "__new_rows";
+
+/// Compile `source` to a Polars expression over the given columns
+pub fn parse(source: &str, columns: &[String]) -> Result<Expr, ParseError> {
+    Ok(parse_with(source, columns, None)?.0)
+}
+
+/// A computed column's expression, with what evaluating it for appended rows takes
+#[derive(Debug, Clone)]
+pub struct Compiled {
+    /// The expression over the whole data
+    pub expr: Expr,
+    /// Rows before the first appended one that its row functions read
+    pub lookback: usize,
+    source: String,
+    columns: Vec<String>,
+}
+
+/// Compile `source` for a computed column over the given columns
+pub fn compile(source: &str, columns: &[String]) -> Result<Compiled, ParseError> {
+    let (expr, lookback, _) = parse_with(source, columns, None)?;
+    Ok(Compiled { expr, lookback, source: source.to_string(), columns: columns.to_vec() })
+}
+
+impl Compiled {
+    /// The expression over a frame of the `lookback` rows before some appended rows followed
+    /// by those rows, which have [`NEW_ROWS`] set. Running totals add up the new rows only,
+    /// starting from `totals` (one per `cumsum`, in order; missing ones start at zero).
+    /// Also returns, per running total, an aggregate of what it adds up over the new rows.
+    pub fn over_new_rows(&self, totals: &[f64]) -> (Expr, Vec<Expr>) {
+        let (expr, _, sums) = parse_with(&self.source, &self.columns, Some(totals))
+            .expect("compiled from the same source and columns before");
+        (expr, sums)
+    }
+}
+
+/// Parse `source`, returning the expression, how many earlier rows it reads and, when
+/// `totals` is given, the sums over new rows of its running totals
+fn parse_with(source: &str, columns: &[String], totals: Option<&[f64]>) -> Result<(Expr, usize, Vec<Expr>), ParseError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        columns,
+        end: source.chars().count(),
+        lookback: 0,
+        totals,
+        sums: Vec::new(),
+    };
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok((expr, parser.lookback, parser.sums)),
+        Some(_) => Err(parser.error("Unexpected input")),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Float(f64),
+    Date(chrono::NaiveDate),
+    Datetime(chrono::NaiveDateTime),
+    Str(String),
+    Ident(String),
+    /// A backtick-quoted column name
+    Column(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+const OPERATORS: [&str; 14] = ["==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "^", "!", "="];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if let Some((token, len)) = date_literal(&chars[i..], start)? {
+            i += len;
+            token
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Exponent, e.g. 1.5e-3
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let sign = usize::from(matches!(chars.get(i + 1), Some('+' | '-')));
+                if chars.get(i + 1 + sign).is_some_and(|d| d.is_ascii_digit()) {
+                    i += 1 + sign;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            match text.parse::<i64>() {
+                Ok(n) => Token::Int(n),
+                Err(_) => Token::Float(text.parse().map_err(|_| ParseError {
+                    message: format!("Invalid number '{}'", text),
+                    offset: start,
+                })?),
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect())
+        } else if c == '"' || c == '\'' || c == '`' {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err(ParseError { message: "Unclosed quote".to_string(), offset: start });
+            }
+            let text: String = chars[start + 1..i].iter().collect();
+            i += 1;
+            if c == '`' { Token::Column(text) } else { Token::Str(text) }
+        } else if c == '(' {
+            i += 1;
+            Token::LParen
+        } else if c == ')' {
+            i += 1;
+            Token::RParen
+        } else if c == ',' {
+            i += 1;
+            Token::Comma
+        } else if let Some(op) = OPERATORS.iter().find(|op| op.chars().enumerate().all(|(k, o)| chars.get(i + k) == Some(&o))) {
+            i += op.len();
+            Token::Op(op)
+        } else {
+            return Err(ParseError { message: format!("Unexpected character '{}'", c), offset: start });
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+/// A date or date and time at the start of `chars`, with the number of characters it takes.
+/// Anything shaped like a date must be a valid one.
+fn date_literal(chars: &[char], offset: usize) -> Result<Option<(Token, usize)>, ParseError> {
+    let digits = |range: std::ops::Range<usize>| chars.get(range).is_some_and(|c| c.iter().all(char::is_ascii_digit));
+    if !(digits(0..4) && chars.get(4) == Some(&'-') && digits(5..7) && chars.get(7) == Some(&'-') && digits(8..10)) {
+        return Ok(None);
+    }
+    // A time follows after 'T' or a space
+    let len = if matches!(chars.get(10), Some('T' | ' ')) && digits(11..13) && chars.get(13) == Some(&':') {
+        11 + chars[11..].iter().take_while(|c| c.is_ascii_digit() || matches!(c, ':' | '.')).count()
+    } else {
+        10
+    };
+    let text: String = chars[..len].iter().collect();
+    let invalid = || ParseError { message: format!("Invalid date '{}'", text), offset };
+    let date = chrono::NaiveDate::parse_from_str(&text[..10], "%Y-%m-%d").map_err(|_| invalid())?;
+    if len == 10 {
+        return Ok(Some((Token::Date(date), len)));
+    }
+    let time = ["%H:%M:%S%.f", "%H:%M"].iter()
+        .find_map(|f| chrono::NaiveTime::parse_from_str(&text[11..], f).ok())
+        .ok_or_else(invalid)?;
+    Ok(Some((Token::Datetime(date.and_time(time)), len)))
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    columns: &'a [String],
+    /// Offset reported for errors at the end of the input
+    end: usize,
+    /// Most earlier rows read by the functions parsed so far at this level
+    lookback: usize,
+    /// Starting points of running totals over new rows, when evaluating appended rows
+    totals: Option<&'a [f64]>,
+    /// Sum over the new rows of what each running total adds up
+    sums: Vec<Expr>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(_, o)| *o)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let message = match self.peek() {
+            None => format!("{}: expression ends early", message),
+            Some(_) => message.to_string(),
+        };
+        ParseError { message, offset: self.offset() }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    /// Consume the keyword or operator if it is next
+    fn eat(&mut self, word: &str) -> bool {
+        let found = match self.peek() {
+            Some(Token::Op(op)) => *op == word,
+            Some(Token::Ident(ident)) => ident.eq_ignore_ascii_case(word),
+            _ => false,
+        };
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), ParseError> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected {}", what)))
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+        while self.eat("or") {
+            expr = expr.or(self.and()?);
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.not()?;
+        while self.eat("and") {
+            expr = expr.and(self.not()?);
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("not") || self.eat("!") {
+            return Ok(self.not()?.not());
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let left = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Op(op @ ("==" | "=" | "!=" | "<" | "<=" | ">" | ">="))) => *op,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.additive()?;
+        Ok(match op {
+            "==" | "=" => left.eq(right),
+            "!=" => left.neq(right),
+            "<" => left.lt(right),
+            "<=" => left.lt_eq(right),
+            ">" => left.gt(right),
+            _ => left.gt_eq(right),
+        })
+    }
+
+    fn additive(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.multiplicative()?;
+        loop {
+            if self.eat("+") {
+                expr = expr + self.multiplicative()?;
+            } else if self.eat("-") {
+                expr = expr - self.multiplicative()?;
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
+        loop {
+            if self.eat("*") {
+                expr = expr * self.unary()?;
+            } else if self.eat("/") {
+                // Integer columns divide to fractions too
+                expr = binary_expr(expr, Operator::TrueDivide, self.unary()?);
+            } else if self.eat("%") {
+                expr = expr % self.unary()?;
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("-") {
+            return Ok(lit(0) - self.unary()?);
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.atom()?;
+        if self.eat("^") {
+            // Right-associative, and binds tighter than a unary minus on its left
+            let exponent = self.unary()?;
+            return Ok(base.cast(DataType::Float64).pow(exponent));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        let offset = self.offset();
+        match self.next() {
+            Some(Token::Int(n)) => Ok(lit(n)),
+            Some(Token::Float(x)) => Ok(lit(x)),
+            Some(Token::Date(d)) => Ok(lit(d)),
+            Some(Token::Datetime(t)) => Ok(lit(t)),
+            Some(Token::Str(s)) => Ok(lit(s)),
+            Some(Token::Column(name)) => self.column(name, offset),
+            Some(Token::LParen) => {
+                let expr = self.or()?;
+                self.expect(Token::RParen, "')'")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.pos += 1;
+                    return self.call(&name, offset);
+                }
+                match name.to_ascii_lowercase().as_str() {
+                    "true" => Ok(lit(true)),
+                    "false" => Ok(lit(false)),
+                    _ => self.column(name, offset),
+                }
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.error("Expected a value"))
+            }
+        }
+    }
+
+    fn column(&self, name: String, offset: usize) -> Result<Expr, ParseError> {
+        if self.columns.contains(&name) {
+            Ok(col(name))
+        } else {
+            Err(ParseError { message: format!("No column named '{}'", name), offset })
+        }
+    }
+
+    /// A whole-number argument, such as a window length
+    fn count(&mut self, min: i64) -> Result<i64, ParseError> {
+        match self.peek() {
+            Some(Token::Int(n)) if *n >= min => {
+                let n = *n;
+                self.pos += 1;
+                Ok(n)
+            }
+            _ => Err(self.error(&format!("Expected a whole number of at least {}", min))),
+        }
+    }
+
+    /// Parse the arguments of a function call after its opening parenthesis: expressions,
+    /// then whole numbers (`counts` of them, each optional with the given default)
+    fn call(&mut self, name: &str, offset: usize) -> Result<Expr, ParseError> {
+        let lower = name.to_ascii_lowercase();
+        let (exprs, counts): (usize, &[(i64, Option<i64>)]) = match lower.as_str() {
+            "abs" | "sqrt" | "exp" | "ln" | "log" | "log10" | "sin" | "cos" | "tan" | "floor" | "ceil" | "cumsum" => (1, &[]),
+            "round" => (1, &[(0, Some(0))]),
+            "min" | "max" => (2, &[]),
+            "if" => (3, &[]),
+            "lag" | "diff" => (1, &[(1, Some(1))]),
+            "rolling_mean" | "rolling_sum" | "rolling_min" | "rolling_max" | "rolling_std" => (1, &[(1, None)]),
+            _ => return Err(ParseError { message: format!("Unknown function '{}'", name), offset }),
+        };
+
+        // Arguments read earlier rows of their own, on top of what this call reads
+        let outer = std::mem::take(&mut self.lookback);
+        let mut args = Vec::with_capacity(exprs);
+        for i in 0..exprs {
+            if i > 0 {
+                self.expect(Token::Comma, &format!("{} arguments to {}", exprs, lower))?;
+            }
+            args.push(self.or()?);
+        }
+        let mut numbers = Vec::with_capacity(counts.len());
+        for &(min, default) in counts {
+            if self.eat_comma() {
+                numbers.push(self.count(min)?);
+            } else {
+                numbers.push(default.ok_or_else(|| self.error(&format!("{} needs a window length", lower)))?);
+            }
+        }
+        self.expect(Token::RParen, "')'")?;
+        let reads = match lower.as_str() {
+            "lag" | "diff" => numbers[0] as usize,
+            name if name.starts_with("rolling_") => numbers[0] as usize - 1,
+            _ => 0,
+        };
+        self.lookback = outer.max(self.lookback + reads);
+
+        let mut args = args.into_iter();
+        let x = args.next().expect("at least one argument");
+        let window = |n: i64| RollingOptionsFixedWindow { window_size: n as usize, min_periods: 1, ..Default::default() };
+        Ok(match lower.as_str() {
+            "abs" => x.abs(),
+            "sqrt" => x.cast(DataType::Float64).sqrt(),
+            "exp" => x.cast(DataType::Float64).exp(),
+            "ln" | "log" => x.cast(DataType::Float64).log(std::f64::consts::E),
+            "log10" => x.cast(DataType::Float64).log(10.0),
+            "sin" => x.cast(DataType::Float64).sin(),
+            "cos" => x.cast(DataType::Float64).cos(),
+            "tan" => x.cast(DataType::Float64).tan(),
+            "floor" => x.cast(DataType::Float64).floor(),
+            "ceil" => x.cast(DataType::Float64).ceil(),
+            "round" => x.cast(DataType::Float64).round(numbers[0] as u32),
+            "cumsum" => match self.totals {
+                None => x.cum_sum(false),
+                Some(totals) => {
+                    let k = self.sums.len();
+                    let terms = when(col(NEW_ROWS)).then(x).otherwise(lit(0));
+                    self.sums.push(terms.clone().cast(DataType::Float64).sum().alias(format!("total_{}", k)));
+                    terms.cum_sum(false) + lit(totals.get(k).copied().unwrap_or(0.0))
+                }
+            },
+            "min" | "max" => {
+                let y = args.next().expect("two arguments");
+                let pick_x = if lower == "min" { x.clone().lt_eq(y.clone()) } else { x.clone().gt_eq(y.clone()) };
+                when(pick_x).then(x).otherwise(y)
+            }
+            "if" => {
+                let then = args.next().expect("three arguments");
+                when(x).then(then).otherwise(args.next().expect("three arguments"))
+            }
+            "lag" => x.shift(lit(numbers[0])),
+            "diff" => x.diff(numbers[0], polars::series::ops::NullBehavior::Ignore),
+            "rolling_mean" => x.rolling_mean(window(numbers[0])),
+            "rolling_sum" => x.rolling_sum(window(numbers[0])),
+            "rolling_min" => x.rolling_min(window(numbers[0])),
+            "rolling_max" => x.rolling_max(window(numbers[0])),
+            _ => x.rolling_std(window(numbers[0])),
+        })
+    }
+
+    fn eat_comma(&mut self) -> bool {
+        let found = self.peek() == Some(&Token::Comma);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> DataFrame {
+        df!(
+            "temp_in" => [10i64, 12, 14, 16],
+            "temp_out" => [20.0, 25.0, 30.0, 40.0],
+            "flow rate" => [2.0, 0.0, 4.0, 8.0],
+            "category" => ["A", "B", "A", "B"],
+        )
+        .unwrap()
+    }
+
+    fn eval(source: &str) -> Series {
+        let df = frame();
+        let columns: Vec<String> = df.get_column_names().iter().map(|c| c.to_string()).collect();
+        let expr = parse(source, &columns).unwrap();
+        df.lazy().select([expr.alias("out")]).collect().unwrap().column("out").unwrap().as_materialized_series().clone()
+    }
+
+    fn floats(series: &Series) -> Vec<Option<f64>> {
+        series.cast(&DataType::Float64).unwrap().f64().unwrap().into_iter().collect()
+    }
+
+    #[test]
+    fn test_arithmetic_and_precedence() {
+        assert_eq!(floats(&eval("(temp_out - temp_in) / `flow rate`"))[0], Some(5.0));
+        assert_eq!(floats(&eval("1 + 2 * 3 ^ 2")), vec![Some(19.0)]);
+        assert_eq!(floats(&eval("-2 ^ 2")), vec![Some(-4.0)]);
+        // Integer columns divide to fractions
+        assert_eq!(floats(&eval("temp_in / 4"))[0], Some(2.5));
+        assert_eq!(floats(&eval("temp_out * 1.8 + 32"))[0], Some(68.0));
+    }
+
+    #[test]
+    fn test_functions_and_conditionals() {
+        assert_eq!(floats(&eval("sqrt(abs(-16))"))[0], Some(4.0));
+        assert_eq!(floats(&eval("max(temp_in, 13)")), vec![Some(13.0), Some(13.0), Some(14.0), Some(16.0)]);
+        assert_eq!(
+            floats(&eval("if(category == \"A\" and temp_in > 10, 1, 0)")),
+            vec![Some(0.0), Some(0.0), Some(1.0), Some(0.0)]
+        );
+        assert_eq!(floats(&eval("round(temp_out / 3, 1)"))[0], Some(6.7));
+    }
+
+    #[test]
+    fn test_row_functions() {
+        assert_eq!(floats(&eval("lag(temp_in)")), vec![None, Some(10.0), Some(12.0), Some(14.0)]);
+        assert_eq!(floats(&eval("diff(temp_out, 2)")), vec![None, None, Some(10.0), Some(15.0)]);
+        assert_eq!(floats(&eval("rolling_mean(temp_out, 2)")), vec![Some(20.0), Some(22.5), Some(27.5), Some(35.0)]);
+        assert_eq!(floats(&eval("cumsum(temp_in)")), vec![Some(10.0), Some(22.0), Some(36.0), Some(52.0)]);
+
+        // Nested row functions read the rows each of them needs
+        let columns = vec!["a".to_string()];
+        assert_eq!(compile("a * 2", &columns).unwrap().lookback, 0);
+        assert_eq!(compile("lag(rolling_mean(a, 3), 2) + diff(a)", &columns).unwrap().lookback, 4);
+    }
+
+    #[test]
+    fn test_filter_predicates() {
+        let df = df!("category" => ["A", "B", "A"], "value" => [50, 150, 200]).unwrap();
+        let stamps = Series::new("timestamp".into(), [1_704_067_200_000i64, 1_704_189_600_000, 1_704_276_000_000])
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap();
+        let df = df.hstack(&[stamps.into()]).unwrap();
+        let columns: Vec<String> = df.get_column_names().iter().map(|c| c.to_string()).collect();
+        let filter = |source: &str| df.clone().lazy().filter(parse(source, &columns).unwrap()).collect().unwrap().height();
+
+        // 2024-01-01 00:00, 2024-01-02 10:00, 2024-01-03 10:00
+        assert_eq!(filter("category == \"A\" and value > 100"), 1);
+        assert_eq!(filter("timestamp >= 2024-01-02"), 2);
+        assert_eq!(filter("timestamp < 2024-01-02T10:00:01 and not (category = 'A')"), 1);
+        assert_eq!(filter("timestamp >= 2024-01-02 12:00 or value < 100"), 2);
+        assert!(parse("timestamp > 2024-13-01", &columns).is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let columns = vec!["a".to_string()];
+        assert_eq!(parse("a + b", &columns).unwrap_err().offset, 4);
+        assert!(parse("a +", &columns).unwrap_err().message.contains("ends early"));
+        assert!(parse("frobnicate(a)", &columns).unwrap_err().message.contains("Unknown function"));
+        assert!(parse("rolling_mean(a)", &columns).is_err());
+        assert!(parse("lag(a, 0)", &columns).is_err());
+        assert!(parse("(a", &columns).is_err());
+        assert!(parse("a a", &columns).is_err());
+        assert!(parse("`a", &columns).is_err());
+    }
+}
@@ -0,0 +1,1076 @@
+//! File format detection and readers
+//!
+//! Formats are sniffed from file content (magic bytes, first lines) so that misnamed or
+//! extension-less exports still load; the extension is only used to break ties.
+
+use super::DataError;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Bytes read from the start of a file for sniffing
+const SNIFF_BYTES: usize = 64 * 1024;
+
+/// Lines examined when guessing a delimiter
+const SNIFF_LINES: usize = 20;
+
+/// Delimiters tried when sniffing delimited text, in order of preference
+const CANDIDATE_SEPARATORS: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+/// Rows used for type inference unless a full scan is requested
+const INFER_SCHEMA_ROWS: usize = 100;
+
+/// Bytes read between progress reports when loading
+const READ_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Decoded bytes read from the start of a compressed or re-encoded file for a preview
+const PREVIEW_BYTES: u64 = 1024 * 1024;
+
+/// CSV batches parsed per step when loading with progress
+const PARSE_BATCHES_PER_STEP: usize = 8;
+
+/// Extensions offered in the open-file dialog
+pub const SUPPORTED_EXTENSIONS: [&str; 12] = [
+    "csv", "tsv", "tab", "txt", "parquet", "arrow", "feather", "ipc", "ndjson", "jsonl", "gz", "zst",
+];
+
+/// Data layout of a file (after any decompression)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Parquet,
+    /// Arrow IPC file format (Feather v2)
+    ArrowIpc,
+    /// Newline-delimited JSON objects
+    NdJson,
+    /// Delimited text (CSV, TSV, semicolon-separated)
+    Delimited { separator: u8 },
+}
+
+impl std::fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileFormat::Parquet => write!(f, "Parquet"),
+            FileFormat::ArrowIpc => write!(f, "Arrow IPC"),
+            FileFormat::NdJson => write!(f, "NDJSON"),
+            FileFormat::Delimited { separator: b'\t' } => write!(f, "TSV"),
+            FileFormat::Delimited { separator } => write!(f, "Delimited ('{}')", *separator as char),
+        }
+    }
+}
+
+/// Whole-file compression wrapping the data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Compression::None => write!(f, "uncompressed"),
+            Compression::Gzip => write!(f, "gzip"),
+            Compression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl Compression {
+    /// Detect compression from magic bytes
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// How far a file load has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadProgress {
+    /// Raw bytes read from disk so far, out of the file size
+    Reading { bytes: u64, total: u64 },
+    /// Rows parsed so far
+    Parsing { rows: usize },
+    /// Rows of a column summarised so far while opening a file for streaming
+    Summarising { rows: usize, total: usize },
+}
+
+/// Column type a user can force in the import dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnType {
+    Float,
+    Integer,
+    Text,
+    Boolean,
+    Date,
+    Datetime,
+}
+
+impl ColumnType {
+    pub const ALL: [ColumnType; 6] = [
+        ColumnType::Float,
+        ColumnType::Integer,
+        ColumnType::Text,
+        ColumnType::Boolean,
+        ColumnType::Date,
+        ColumnType::Datetime,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColumnType::Float => "Float",
+            ColumnType::Integer => "Integer",
+            ColumnType::Text => "Text",
+            ColumnType::Boolean => "Boolean",
+            ColumnType::Date => "Date",
+            ColumnType::Datetime => "Datetime",
+        }
+    }
+
+    fn dtype(&self) -> DataType {
+        match self {
+            ColumnType::Float => DataType::Float64,
+            ColumnType::Integer => DataType::Int64,
+            ColumnType::Text => DataType::String,
+            ColumnType::Boolean => DataType::Boolean,
+            ColumnType::Date => DataType::Date,
+            ColumnType::Datetime => DataType::Datetime(TimeUnit::Microseconds, None),
+        }
+    }
+}
+
+/// How to parse delimited text. Other formats carry their own schema and ignore these.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvOptions {
+    /// Field delimiter (None = sniff from content)
+    pub separator: Option<u8>,
+    /// Quote character (None disables quoting)
+    pub quote_char: Option<u8>,
+    /// Preamble lines to skip before the header
+    pub skip_rows: usize,
+    /// First row after the skipped lines holds column names
+    pub has_header: bool,
+    /// Numbers use ',' as the decimal separator (requires a non-comma delimiter)
+    pub decimal_comma: bool,
+    /// Cell values read as missing, e.g. "NA" or "-"
+    pub null_values: Vec<String>,
+    /// Forced column types by column name, overriding inference
+    pub dtype_overrides: BTreeMap<String, ColumnType>,
+    /// Infer types from every row instead of the first 100
+    pub full_scan_inference: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            separator: None,
+            quote_char: Some(b'"'),
+            skip_rows: 0,
+            has_header: true,
+            decimal_comma: false,
+            null_values: Vec::new(),
+            dtype_overrides: BTreeMap::new(),
+            full_scan_inference: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    fn infer_schema_length(&self) -> Option<usize> {
+        (!self.full_scan_inference).then_some(INFER_SCHEMA_ROWS)
+    }
+
+    fn null_values(&self) -> Option<NullValues> {
+        (!self.null_values.is_empty())
+            .then(|| NullValues::AllColumns(self.null_values.iter().map(|v| PlSmallStr::from(v.as_str())).collect()))
+    }
+
+    fn schema_overwrite(&self) -> Option<SchemaRef> {
+        (!self.dtype_overrides.is_empty()).then(|| {
+            Arc::new(
+                self.dtype_overrides.iter()
+                    .map(|(name, ty)| Field::new(PlSmallStr::from(name.as_str()), ty.dtype()))
+                    .collect::<Schema>(),
+            )
+        })
+    }
+}
+
+/// Guess the format of (decompressed) content from its first bytes.
+/// Returns None for binary content that matches no known format.
+pub fn sniff_format(bytes: &[u8], extension: Option<&str>) -> Option<FileFormat> {
+    if bytes.starts_with(b"PAR1") {
+        return Some(FileFormat::Parquet);
+    }
+    if bytes.starts_with(b"ARROW1") {
+        return Some(FileFormat::ArrowIpc);
+    }
+    if bytes.contains(&0) {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start_matches('\u{feff}');
+    if text.trim_start().starts_with('{') {
+        return Some(FileFormat::NdJson);
+    }
+
+    let prefer_tab = matches!(extension, Some("tsv" | "tab"));
+    Some(FileFormat::Delimited { separator: sniff_separator(text, prefer_tab) })
+}
+
+/// Pick the delimiter that splits the first lines into the most columns, preferring
+/// delimiters whose count is the same on every line. Quoted fields are ignored.
+pub fn sniff_separator(text: &str, prefer_tab: bool) -> u8 {
+    // A truncated prefix may end mid-line, so drop the last line when there are several
+    let mut lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).take(SNIFF_LINES + 1).collect();
+    if lines.len() > 1 {
+        lines.pop();
+    }
+
+    let count = |line: &str, sep: u8| {
+        let mut in_quotes = false;
+        line.bytes()
+            .filter(|&b| {
+                if b == b'"' {
+                    in_quotes = !in_quotes;
+                }
+                !in_quotes && b == sep
+            })
+            .count()
+    };
+
+    let score = |sep: u8| {
+        let counts: Vec<usize> = lines.iter().map(|l| count(l, sep)).collect();
+        let first = counts.first().copied().unwrap_or(0);
+        let consistent = first > 0 && counts.iter().all(|&c| c == first);
+        (consistent, first, prefer_tab && sep == b'\t')
+    };
+
+    CANDIDATE_SEPARATORS.iter()
+        .copied()
+        .map(|sep| (score(sep), sep))
+        .filter(|((_, first, _), _)| *first > 0)
+        // max_by_key keeps the last maximum, so iterate in reverse to favour earlier candidates on ties
+        .rev()
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, sep)| sep)
+        .unwrap_or(b',')
+}
+
+/// Open a file as a LazyFrame, sniffing its format and compression. `options` apply to
+/// delimited text only.
+pub fn scan(path: &Path, options: &CsvOptions) -> Result<(LazyFrame, FileFormat, Compression), DataError> {
+    let scan = open(path, options, None)?;
+    Ok((scan.lf, scan.format, scan.compression))
+}
+
+/// Read the first `rows` rows of a file with the given options, for the import preview.
+/// Compressed and non-UTF-8 text is decoded only as far as `PREVIEW_BYTES`, not all of it.
+pub fn preview(path: &Path, options: &CsvOptions, rows: usize) -> Result<DataFrame, DataError> {
+    let mut prefix = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path)?.take(SNIFF_BYTES as u64).read_to_end(&mut prefix)?;
+    let compression = Compression::detect(&prefix);
+    let needs_decoding = compression != Compression::None
+        || prefix.starts_with(&[0xff, 0xfe])
+        || prefix.starts_with(&[0xef, 0xbb, 0xbf]);
+    let whole_file = || Ok(open(path, options, Some(rows))?.lf.limit(rows as IdxSize).collect()?);
+    if !needs_decoding {
+        return whole_file();
+    }
+
+    let mut bytes = Vec::new();
+    decompressed(compression, std::fs::File::open(path)?)?.take(PREVIEW_BYTES).read_to_end(&mut bytes)?;
+    let truncated = bytes.len() as u64 == PREVIEW_BYTES;
+    let mut bytes = decode_text(bytes);
+    let extension = data_extension(path);
+    match detect_format(&bytes[..bytes.len().min(SNIFF_BYTES)], extension.as_deref(), options) {
+        Some(format @ (FileFormat::Delimited { .. } | FileFormat::NdJson)) => {
+            // A line cut off at the end of what was read is left out
+            if truncated {
+                bytes.truncate(bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1));
+            }
+            let df = read_bytes(bytes, format, options, Some(rows))?;
+            Ok(df.head(Some(rows)))
+        }
+        // Columnar files can't be read from a prefix
+        _ => whole_file(),
+    }
+}
+
+/// Whether a file holds delimited text, i.e. whether import options apply to it
+pub fn is_delimited(path: &Path) -> bool {
+    let mut prefix = Vec::with_capacity(SNIFF_BYTES);
+    let read = std::fs::File::open(path).and_then(|f| f.take(SNIFF_BYTES as u64).read_to_end(&mut prefix));
+    if read.is_err() {
+        return false;
+    }
+    let extension = data_extension(path);
+    if Compression::detect(&prefix) != Compression::None {
+        // Don't decompress a whole archive just to decide; trust the inner extension
+        return matches!(extension.as_deref(), Some("csv" | "tsv" | "tab" | "txt"));
+    }
+    matches!(
+        sniff_format(&decode_text(prefix), extension.as_deref()),
+        Some(FileFormat::Delimited { .. })
+    )
+}
+
+/// Read a whole file into a DataFrame, reporting bytes read and rows parsed as it goes.
+/// Also returns the offset `read_appended` picks up from if the file keeps growing: the
+/// end of the last complete line. A last line with no newline is loaded as a row, as it
+/// may be all there is, and the returned flag says so; it may also be a row still being
+/// written, so `read_appended` reads it again. Setting `cancel` stops the load at the next
+/// step with `DataError::Cancelled`.
+pub fn read_with_progress(
+    path: &Path,
+    options: &CsvOptions,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(LoadProgress),
+) -> Result<(DataFrame, FileFormat, Compression, u64, bool), DataError> {
+    let total = std::fs::metadata(path)?.len();
+    let mut prefix = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path)?.take(SNIFF_BYTES as u64).read_to_end(&mut prefix)?;
+    let compression = Compression::detect(&prefix);
+    let extension = data_extension(path);
+    let unrecognised = || DataError::UnsupportedFormat(format!("unrecognised content in {}", path.display()));
+
+    // Uncompressed columnar files keep the lazy scan, which reads them a row group at a time
+    if compression == Compression::None
+        && let Some(format @ (FileFormat::Parquet | FileFormat::ArrowIpc)) =
+            detect_format(&prefix, extension.as_deref(), options)
+    {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(DataError::Cancelled);
+        }
+        let df = open(path, options, None)?.lf.collect()?;
+        progress(LoadProgress::Reading { bytes: total, total });
+        progress(LoadProgress::Parsing { rows: df.height() });
+        return Ok((df, format, compression, total, false));
+    }
+
+    // Everything else is read into memory once, decompressed as it streams in
+    let mut reader = ProgressReader {
+        inner: std::fs::File::open(path)?,
+        read: 0,
+        reported: 0,
+        total,
+        cancel,
+        progress: &mut progress,
+    };
+    let mut bytes = Vec::with_capacity(if compression == Compression::None { total as usize } else { 0 });
+    let result = decompressed(compression, &mut reader).and_then(|mut r| r.read_to_end(&mut bytes));
+    if cancel.load(Ordering::Relaxed) {
+        return Err(DataError::Cancelled);
+    }
+    result?;
+    let read = reader.read;
+    let tail = &bytes[bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1)..];
+    let unterminated_bytes = if tail.iter().all(u8::is_ascii_whitespace) { 0 } else { tail.len() as u64 };
+    let bytes = decode_text(bytes);
+    let format = detect_format(&bytes[..bytes.len().min(SNIFF_BYTES)], extension.as_deref(), options)
+        .ok_or_else(unrecognised)?;
+
+    let df = match format {
+        FileFormat::Delimited { separator } => {
+            let mut reader = csv_options(separator, options, None).into_reader_with_file_handle(Cursor::new(&bytes[..]));
+            let mut batches = reader.batched_borrowed()?;
+            let mut df: Option<DataFrame> = None;
+            while let Some(chunk) = batches.next_batches(PARSE_BATCHES_PER_STEP)? {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(DataError::Cancelled);
+                }
+                for batch in chunk {
+                    match df.as_mut() {
+                        Some(df) => {
+                            df.vstack_mut(&batch)?;
+                        }
+                        None => df = Some(batch),
+                    }
+                }
+                progress(LoadProgress::Parsing { rows: df.as_ref().map_or(0, |df| df.height()) });
+            }
+            match df {
+                Some(mut df) => {
+                    df.rechunk_mut();
+                    df
+                }
+                // Header-only file: no batches, but the columns still exist
+                None => read_bytes(bytes, format, options, None)?,
+            }
+        }
+        _ => read_bytes(bytes, format, options, None)?,
+    };
+    progress(LoadProgress::Parsing { rows: df.height() });
+
+    // Only rows of plain delimited files are read again; with no rows the tail is the header
+    let unterminated = compression == Compression::None
+        && matches!(format, FileFormat::Delimited { .. })
+        && df.height() > 0
+        && unterminated_bytes > 0;
+    if unterminated {
+        return Ok((df, format, compression, read - unterminated_bytes, true));
+    }
+    Ok((df, format, compression, read, false))
+}
+
+/// Reads a file, reporting the bytes read about every `READ_CHUNK_BYTES` and failing once
+/// `cancel` is set
+struct ProgressReader<'a, R> {
+    inner: R,
+    read: u64,
+    reported: u64,
+    total: u64,
+    cancel: &'a AtomicBool,
+    progress: &'a mut dyn FnMut(LoadProgress),
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(std::io::Error::other("load cancelled"));
+        }
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        if n == 0 || self.read - self.reported >= READ_CHUNK_BYTES as u64 {
+            self.reported = self.read;
+            (self.progress)(LoadProgress::Reading { bytes: self.read, total: self.total });
+        }
+        Ok(n)
+    }
+}
+
+/// Rows appended to an uncompressed delimited file after its first `offset` bytes, parsed
+/// with the `schema` of the rows already loaded. Only complete lines are read, so a row
+/// still being written is left for next time; if `offset` fell inside a row, the rest of
+/// that row is skipped. Returns the rows and the offset to resume from, or None when no
+/// complete line has been added.
+pub fn read_appended(
+    path: &Path,
+    offset: u64,
+    separator: u8,
+    options: &CsvOptions,
+    schema: SchemaRef,
+) -> Result<Option<(DataFrame, u64)>, DataError> {
+    // Start one byte early to see whether `offset` is at the start of a line
+    let start = offset.saturating_sub(1);
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    let begin = if offset == 0 {
+        0
+    } else if bytes.first() == Some(&b'\n') {
+        1
+    } else {
+        match bytes.iter().skip(1).position(|&b| b == b'\n') {
+            Some(i) => i + 2,
+            None => return Ok(None),
+        }
+    };
+    let Some(end) = bytes.iter().rposition(|&b| b == b'\n').map(|i| i + 1).filter(|&end| end > begin) else {
+        return Ok(None);
+    };
+
+    let df = CsvReadOptions::default()
+        .with_has_header(false)
+        .with_schema(Some(schema))
+        .map_parse_options(|opts| {
+            opts.with_separator(separator)
+                .with_quote_char(options.quote_char)
+                .with_decimal_comma(options.decimal_comma)
+                .with_null_values(options.null_values())
+                .with_try_parse_dates(true)
+        })
+        .into_reader_with_file_handle(Cursor::new(&bytes[begin..end]))
+        .finish()?;
+    Ok(Some((df, start + end as u64)))
+}
+
+fn open(path: &Path, options: &CsvOptions, n_rows: Option<usize>) -> Result<FileScan, DataError> {
+    let mut prefix = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path)?.take(SNIFF_BYTES as u64).read_to_end(&mut prefix)?;
+    let compression = Compression::detect(&prefix);
+    let extension = data_extension(path);
+    let unrecognised = || DataError::UnsupportedFormat(format!("unrecognised content in {}", path.display()));
+    let scan = |lf, format, on_disk| FileScan {
+        lf,
+        format,
+        compression,
+        path: path.to_path_buf(),
+        options: options.clone(),
+        on_disk,
+    };
+
+    // Compressed and non-UTF-8 text files are decoded into memory and read from there
+    let needs_decoding = compression != Compression::None
+        || prefix.starts_with(&[0xff, 0xfe])
+        || prefix.starts_with(&[0xef, 0xbb, 0xbf]);
+    if needs_decoding {
+        let bytes = decode_text(decompress(std::fs::read(path)?)?);
+        let format = detect_format(&bytes[..bytes.len().min(SNIFF_BYTES)], extension.as_deref(), options)
+            .ok_or_else(unrecognised)?;
+        return Ok(scan(read_bytes(bytes, format, options, n_rows)?.lazy(), format, false));
+    }
+
+    let format = detect_format(&prefix, extension.as_deref(), options).ok_or_else(unrecognised)?;
+    let lf = match format {
+        FileFormat::Parquet => LazyFrame::scan_parquet(path, ScanArgsParquet {
+            low_memory: true,
+            parallel: polars::io::parquet::read::ParallelStrategy::Auto,
+            ..Default::default()
+        })?,
+        FileFormat::ArrowIpc => LazyFrame::scan_ipc(path, Default::default())?,
+        FileFormat::NdJson => return Ok(scan(read_ndjson(&std::fs::read(path)?)?.lazy(), format, false)),
+        FileFormat::Delimited { separator } => LazyCsvReader::new(path)
+            .with_separator(separator)
+            .with_quote_char(options.quote_char)
+            .with_skip_rows(options.skip_rows)
+            .with_has_header(options.has_header)
+            .with_decimal_comma(options.decimal_comma)
+            .with_null_values(options.null_values())
+            .with_dtype_overwrite(options.schema_overwrite())
+            .with_infer_schema_length(options.infer_schema_length())
+            .with_n_rows(n_rows)
+            .with_try_parse_dates(true)
+            .finish()?,
+    };
+    Ok(scan(lf, format, true))
+}
+
+/// A file opened for out-of-core access: rows and columns are read from disk only when
+/// asked for, so files larger than memory can be explored
+#[derive(Clone)]
+pub struct FileScan {
+    /// Lazy scan of the whole file
+    pub lf: LazyFrame,
+    pub format: FileFormat,
+    pub compression: Compression,
+    path: std::path::PathBuf,
+    options: CsvOptions,
+    /// Read straight from the file rather than decoded into memory first
+    on_disk: bool,
+}
+
+impl FileScan {
+    /// Open a file lazily with the given import options
+    pub fn open(path: &Path, options: &CsvOptions) -> Result<Self, DataError> {
+        open(path, options, None)
+    }
+
+    /// Number of rows in the file
+    pub fn height(&self) -> Result<usize, DataError> {
+        let counted = self.lf.clone().select([len()]).collect()?;
+        let rows = counted.get_columns()[0].cast(&DataType::UInt64)?.u64()?.get(0).unwrap_or(0);
+        Ok(rows as usize)
+    }
+
+    /// Read a range of rows, keeping only the named columns
+    pub fn rows(&self, rows: Range<usize>, columns: &[String]) -> Result<DataFrame, DataError> {
+        let projection: Vec<Expr> = columns.iter().map(|name| col(name.as_str())).collect();
+        Ok(self.lf.clone()
+            .select(projection)
+            .slice(rows.start as i64, rows.len() as IdxSize)
+            .collect()?)
+    }
+
+    /// Visit one column from start to end in chunks of about `chunk_rows` rows, without
+    /// reading the other columns or holding the whole column. Stops early if `visit` returns false.
+    pub fn column_chunks(
+        &self,
+        column: &str,
+        chunk_rows: usize,
+        mut visit: impl FnMut(&Series) -> Result<bool, DataError>,
+    ) -> Result<(), DataError> {
+        // Sliced CSV scans re-read the file from the top for every slice, so walk it once with
+        // the batched reader instead
+        if let (true, FileFormat::Delimited { separator }) = (self.on_disk, self.format) {
+            let mut reader = csv_options(separator, &self.options, None)
+                .with_columns(Some(Arc::new([PlSmallStr::from(column)])))
+                .with_chunk_size(chunk_rows)
+                .into_reader_with_file_handle(std::fs::File::open(&self.path)?);
+            let mut batches = reader.batched_borrowed()?;
+            while let Some(chunk) = batches.next_batches(1)? {
+                for batch in chunk {
+                    if !visit(batch.column(column)?.as_materialized_series())? {
+                        return Ok(());
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let mut offset = 0;
+        loop {
+            let chunk = self.rows(offset..offset + chunk_rows, &[column.to_string()])?;
+            if chunk.height() == 0 || !visit(chunk.column(column)?.as_materialized_series())? {
+                return Ok(());
+            }
+            offset += chunk.height();
+        }
+    }
+}
+
+/// Sniff the format, then settle the delimiter: an explicit choice wins, otherwise it is
+/// sniffed from the lines after any skipped preamble
+fn detect_format(prefix: &[u8], extension: Option<&str>, options: &CsvOptions) -> Option<FileFormat> {
+    let format = sniff_format(prefix, extension)?;
+    let FileFormat::Delimited { separator } = format else {
+        return Some(format);
+    };
+    let separator = match options.separator {
+        Some(separator) => separator,
+        None if options.skip_rows > 0 => {
+            let text = String::from_utf8_lossy(prefix);
+            let body: Vec<&str> = text.lines().skip(options.skip_rows).collect();
+            sniff_separator(&body.join("\n"), matches!(extension, Some("tsv" | "tab")))
+        }
+        None => separator,
+    };
+    Some(FileFormat::Delimited { separator })
+}
+
+/// Read a fully decoded file from memory
+fn read_bytes(bytes: Vec<u8>, format: FileFormat, options: &CsvOptions, n_rows: Option<usize>) -> Result<DataFrame, DataError> {
+    let df = match format {
+        FileFormat::Parquet => ParquetReader::new(Cursor::new(bytes)).finish()?,
+        FileFormat::ArrowIpc => IpcReader::new(Cursor::new(bytes)).finish()?,
+        FileFormat::NdJson => read_ndjson(&bytes)?,
+        FileFormat::Delimited { separator } => csv_options(separator, options, n_rows)
+            .into_reader_with_file_handle(Cursor::new(bytes))
+            .finish()?,
+    };
+    Ok(df)
+}
+
+/// Eager CSV reader settings matching the lazy scan in `open`
+fn csv_options(separator: u8, options: &CsvOptions, n_rows: Option<usize>) -> CsvReadOptions {
+    CsvReadOptions::default()
+        .with_skip_rows(options.skip_rows)
+        .with_has_header(options.has_header)
+        .with_infer_schema_length(options.infer_schema_length())
+        .with_schema_overwrite(options.schema_overwrite())
+        .with_n_rows(n_rows)
+        .map_parse_options(|opts| {
+            opts.with_separator(separator)
+                .with_quote_char(options.quote_char)
+                .with_decimal_comma(options.decimal_comma)
+                .with_null_values(options.null_values())
+                .with_try_parse_dates(true)
+        })
+}
+
+/// Stream the decompressed content of `reader`
+fn decompressed<'a>(compression: Compression, reader: impl Read + 'a) -> std::io::Result<Box<dyn Read + 'a>> {
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::Decoder::new(reader)?),
+    })
+}
+
+/// Decompress gzip/zstd content (uncompressed content is returned as is)
+fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, DataError> {
+    if Compression::detect(&bytes) == Compression::None {
+        return Ok(bytes);
+    }
+    let mut out = Vec::new();
+    polars::io::utils::compression::maybe_decompress_bytes(&bytes, &mut out)?;
+    Ok(out)
+}
+
+/// Strip a UTF-8 byte-order mark and transcode UTF-16LE (Excel "Unicode Text") to UTF-8
+fn decode_text(mut bytes: Vec<u8>) -> Vec<u8> {
+    if let Some(rest) = bytes.strip_prefix(&[0xff, 0xfe]) {
+        let units: Vec<u16> = rest.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        return String::from_utf16_lossy(&units).into_bytes();
+    }
+    if bytes.starts_with(&[0xef, 0xbb, 0xbf]) {
+        bytes.drain(..3);
+    }
+    bytes
+}
+
+/// Extension of the data inside the file, ignoring a trailing .gz/.zst
+fn data_extension(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    if matches!(ext.as_str(), "gz" | "zst") {
+        return data_extension(Path::new(path.file_stem()?));
+    }
+    Some(ext)
+}
+
+/// Parse newline-delimited JSON objects into a DataFrame. Columns appear in order of first
+/// occurrence; numbers become Int64/Float64, booleans Boolean, ISO-8601 strings Datetime, and
+/// anything else String. Missing keys become nulls.
+pub fn read_ndjson(bytes: &[u8]) -> Result<DataFrame, DataError> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rows: Vec<serde_json::Map<String, serde_json::Value>> = Vec::new();
+
+    for (line, value) in serde_json::Deserializer::from_slice(bytes).into_iter::<serde_json::Value>().enumerate() {
+        let value = value.map_err(|e| DataError::UnsupportedFormat(format!("NDJSON record {}: {}", line + 1, e)))?;
+        let serde_json::Value::Object(object) = value else {
+            return Err(DataError::UnsupportedFormat(format!("NDJSON record {} is not an object", line + 1)));
+        };
+        for key in object.keys() {
+            if !names.contains(key) {
+                names.push(key.clone());
+            }
+        }
+        rows.push(object);
+    }
+
+    let columns = names.iter()
+        .map(|name| {
+            let values: Vec<Option<&serde_json::Value>> = rows.iter()
+                .map(|row| row.get(name).filter(|v| !v.is_null()))
+                .collect();
+            json_column(name, &values).map(Column::from)
+        })
+        .collect::<Result<Vec<Column>, DataError>>()?;
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Build a typed Series from JSON values
+fn json_column(name: &str, values: &[Option<&serde_json::Value>]) -> Result<Series, DataError> {
+    let present = || values.iter().flatten();
+    let name = PlSmallStr::from(name);
+
+    if present().all(|v| v.is_i64()) {
+        return Ok(Series::new(name, values.iter().map(|v| v.and_then(|v| v.as_i64())).collect::<Vec<_>>()));
+    }
+    if present().all(|v| v.is_number()) {
+        return Ok(Series::new(name, values.iter().map(|v| v.and_then(|v| v.as_f64())).collect::<Vec<_>>()));
+    }
+    if present().all(|v| v.is_boolean()) {
+        return Ok(Series::new(name, values.iter().map(|v| v.and_then(|v| v.as_bool())).collect::<Vec<_>>()));
+    }
+
+    let strings: Vec<Option<String>> = values.iter()
+        .map(|v| {
+            v.map(|v| match v {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+        })
+        .collect();
+
+    // Timestamps are common in instrument logs; keep them temporal when every value parses
+    let timestamps: Option<Vec<Option<i64>>> = strings.iter()
+        .map(|s| match s {
+            Some(s) => parse_timestamp(s).map(Some),
+            None => Some(None),
+        })
+        .collect();
+    if let Some(micros) = timestamps.filter(|_| present().next().is_some()) {
+        return Ok(Series::new(name, micros).cast(&DataType::Datetime(TimeUnit::Microseconds, None))?);
+    }
+
+    Ok(Series::new(name, strings))
+}
+
+/// Parse an ISO-8601 date or date-time into microseconds since the epoch (UTC)
+fn parse_timestamp(s: &str) -> Option<i64> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp_micros());
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, format) {
+            return Some(dt.and_utc().timestamp_micros());
+        }
+    }
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_micros())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::Builder;
+
+    fn temp_file(suffix: &str, bytes: &[u8]) -> tempfile::NamedTempFile {
+        let mut file = Builder::new().suffix(suffix).tempfile().unwrap();
+        file.write_all(bytes).unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn test_sniff_separator() {
+        assert_eq!(sniff_separator("a,b,c\n1,2,3\n", false), b',');
+        assert_eq!(sniff_separator("a;b;c\n1,5;2,5;3\n4;5;6\n", false), b';');
+        assert_eq!(sniff_separator("a\tb\n1\t2\n3\t4\n", false), b'\t');
+        // Commas inside quotes don't count
+        assert_eq!(sniff_separator("\"x,y\"|z\n\"1,2\"|3\n", false), b'|');
+        // Single column falls back to comma
+        assert_eq!(sniff_separator("value\n1\n2\n", false), b',');
+    }
+
+    #[test]
+    fn test_sniff_format() {
+        assert_eq!(sniff_format(b"PAR1....", None), Some(FileFormat::Parquet));
+        assert_eq!(sniff_format(b"ARROW1\0\0", None), Some(FileFormat::ArrowIpc));
+        assert_eq!(sniff_format(b"  {\"a\": 1}\n{\"a\": 2}\n", None), Some(FileFormat::NdJson));
+        assert_eq!(sniff_format(b"a\tb\n1\t2\n", Some("csv")), Some(FileFormat::Delimited { separator: b'\t' }));
+        assert_eq!(sniff_format(&[1, 0, 2, 3], None), None);
+    }
+
+    #[test]
+    fn test_scan_semicolon_and_misnamed_tsv() {
+        let file = temp_file(".txt", b"x;y\n1;2\n3;4\n");
+        let (lf, format, _) = scan(file.path(), &CsvOptions::default()).unwrap();
+        assert_eq!(format, FileFormat::Delimited { separator: b';' });
+        assert_eq!(lf.collect().unwrap().shape(), (2, 2));
+
+        // Tab-separated content saved with a .csv extension
+        let file = temp_file(".csv", b"x\ty\n1\t2\n");
+        let (lf, format, compression) = scan(file.path(), &CsvOptions::default()).unwrap();
+        assert_eq!((format, compression), (FileFormat::Delimited { separator: b'\t' }, Compression::None));
+        assert_eq!(lf.collect().unwrap().width(), 2);
+    }
+
+    #[test]
+    fn test_scan_utf16_excel_export() {
+        let text = "x\ty\r\n1\t2\r\n3\t4\r\n";
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(text.encode_utf16().flat_map(|u| u.to_le_bytes()));
+        let file = temp_file(".txt", &bytes);
+
+        let df = scan(file.path(), &CsvOptions::default()).unwrap().0.collect().unwrap();
+        assert_eq!(df.get_column_names(), vec!["x", "y"]);
+        assert_eq!(df.height(), 2);
+    }
+
+    #[test]
+    fn test_scan_compressed_csv() {
+        let csv = b"x,y\n1,2\n3,4\n5,6\n";
+        let file = temp_file(".csv.zst", &zstd::encode_all(&csv[..], 3).unwrap());
+        let (lf, format, compression) = scan(file.path(), &CsvOptions::default()).unwrap();
+        assert_eq!((format, compression), (FileFormat::Delimited { separator: b',' }, Compression::Zstd));
+        assert_eq!(lf.collect().unwrap().shape(), (3, 2));
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(b"a;b\n1;2\n").unwrap();
+        let file = temp_file(".csv.gz", &gz.finish().unwrap());
+        let (lf, format, compression) = scan(file.path(), &CsvOptions::default()).unwrap();
+        assert_eq!((format, compression), (FileFormat::Delimited { separator: b';' }, Compression::Gzip));
+        assert_eq!(lf.collect().unwrap().shape(), (1, 2));
+    }
+
+    #[test]
+    fn test_scan_arrow_ipc() {
+        let mut df = df!("a" => [1.0, 2.0, 3.0], "b" => ["x", "y", "z"]).unwrap();
+        let file = Builder::new().suffix(".feather").tempfile().unwrap();
+        IpcWriter::new(file.reopen().unwrap()).finish(&mut df).unwrap();
+
+        let (lf, format, _) = scan(file.path(), &CsvOptions::default()).unwrap();
+        assert_eq!(format, FileFormat::ArrowIpc);
+        assert!(lf.collect().unwrap().equals(&df));
+    }
+
+    #[test]
+    fn test_scan_european_export_with_preamble() {
+        let file = temp_file(".csv", b"Instrument: X-200\nExported 2024-01-01\nlot;weight\nA;1,5\nB;-\nC;2,25\n");
+        let options = CsvOptions {
+            skip_rows: 2,
+            decimal_comma: true,
+            null_values: vec!["-".to_string()],
+            ..Default::default()
+        };
+        let (lf, format, _) = scan(file.path(), &options).unwrap();
+        assert_eq!(format, FileFormat::Delimited { separator: b';' });
+
+        let df = lf.collect().unwrap();
+        assert_eq!(df.get_column_names(), vec!["lot", "weight"]);
+        let weight = df.column("weight").unwrap();
+        assert_eq!(weight.dtype(), &DataType::Float64);
+        assert_eq!(weight.f64().unwrap().get(0), Some(1.5));
+        assert_eq!(weight.null_count(), 1);
+    }
+
+    #[test]
+    fn test_scan_overrides_and_full_inference() {
+        // The column only turns fractional after the default inference window
+        let mut csv = String::from("id,reading\n");
+        for i in 0..150 {
+            csv.push_str(&format!("{},{}\n", i, if i < 120 { "1".to_string() } else { format!("{}.5", i) }));
+        }
+        let file = temp_file(".csv", csv.as_bytes());
+
+        let full = CsvOptions { full_scan_inference: true, ..Default::default() };
+        let df = scan(file.path(), &full).unwrap().0.collect().unwrap();
+        assert_eq!(df.column("reading").unwrap().dtype(), &DataType::Float64);
+
+        let mut forced = CsvOptions::default();
+        forced.dtype_overrides.insert("id".to_string(), ColumnType::Text);
+        forced.dtype_overrides.insert("reading".to_string(), ColumnType::Float);
+        let df = scan(file.path(), &forced).unwrap().0.collect().unwrap();
+        assert_eq!(df.column("id").unwrap().dtype(), &DataType::String);
+        assert_eq!(df.column("reading").unwrap().f64().unwrap().get(149), Some(149.5));
+    }
+
+    #[test]
+    fn test_preview_headerless_and_explicit_separator() {
+        let file = temp_file(".txt", b"1|2,5\n3|4,5\n5|6,5\n");
+        let options = CsvOptions { separator: Some(b'|'), has_header: false, ..Default::default() };
+        let df = preview(file.path(), &options, 2).unwrap();
+        assert_eq!(df.shape(), (2, 2));
+        assert_eq!(df.column("column_2").unwrap().dtype(), &DataType::String);
+
+        assert!(is_delimited(file.path()));
+        let parquet = Builder::new().suffix(".parquet").tempfile().unwrap();
+        ParquetWriter::new(parquet.reopen().unwrap()).finish(&mut df!("a" => [1]).unwrap()).unwrap();
+        assert!(!is_delimited(parquet.path()));
+    }
+
+    #[test]
+    fn test_preview_decodes_only_the_start_of_an_archive() {
+        let mut csv = String::from("id,value\n");
+        for i in 0..200_000 {
+            csv.push_str(&format!("{},{}\n", i, i * 2));
+        }
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(csv.as_bytes()).unwrap();
+        let mut bytes = gz.finish().unwrap();
+        // Cut off the end of the archive: reading all of it would fail
+        bytes.truncate(bytes.len() - 1024);
+        let file = temp_file(".csv.gz", &bytes);
+        assert!(scan(file.path(), &CsvOptions::default()).is_err());
+
+        let df = preview(file.path(), &CsvOptions::default(), 20).unwrap();
+        assert_eq!(df.height(), 20);
+        assert_eq!(df.column("value").unwrap().i64().unwrap().get(19), Some(38));
+    }
+
+    #[test]
+    fn test_read_with_progress_matches_scan() {
+        let mut csv = String::from("time,value,lot\n");
+        for i in 0..50_000 {
+            csv.push_str(&format!("2024-01-01 00:{:02}:{:02},{}.25,L{}\n", (i / 60) % 60, i % 60, i, i % 7));
+        }
+        let file = temp_file(".csv", csv.as_bytes());
+        let options = CsvOptions::default();
+
+        let mut reports = Vec::new();
+        let (df, format, _, read, _) = read_with_progress(file.path(), &options, &AtomicBool::new(false), |p| reports.push(p)).unwrap();
+        assert_eq!(format, FileFormat::Delimited { separator: b',' });
+        assert!(df.equals(&scan(file.path(), &options).unwrap().0.collect().unwrap()));
+        assert!(matches!(df.column("time").unwrap().dtype(), DataType::Datetime(_, _)));
+
+        let total = csv.len() as u64;
+        assert_eq!(read, total);
+        assert!(reports.contains(&LoadProgress::Reading { bytes: total, total }));
+        assert_eq!(reports.last(), Some(&LoadProgress::Parsing { rows: 50_000 }));
+
+        let cancelled = read_with_progress(file.path(), &options, &AtomicBool::new(true), |_| {});
+        assert!(matches!(cancelled, Err(DataError::Cancelled)));
+    }
+
+    #[test]
+    fn test_read_with_progress_compressed_and_columnar() {
+        let mut csv = String::from("x,y\n");
+        for i in 0..10_000 {
+            csv.push_str(&format!("{},{}\n", i, i * 2));
+        }
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(csv.as_bytes()).unwrap();
+        let file = temp_file(".csv.gz", &gz.finish().unwrap());
+        let total = std::fs::metadata(file.path()).unwrap().len();
+
+        // Progress counts compressed bytes read from disk
+        let mut reports = Vec::new();
+        let (df, _, compression, read, _) =
+            read_with_progress(file.path(), &CsvOptions::default(), &AtomicBool::new(false), |p| reports.push(p)).unwrap();
+        assert_eq!(compression, Compression::Gzip);
+        assert_eq!(df.shape(), (10_000, 2));
+        assert_eq!(read, total);
+        assert!(reports.contains(&LoadProgress::Reading { bytes: total, total }));
+
+        let mut parquet_df = df!("a" => [1.0, 2.0, 3.0]).unwrap();
+        let parquet = Builder::new().suffix(".parquet").tempfile().unwrap();
+        ParquetWriter::new(parquet.reopen().unwrap()).finish(&mut parquet_df).unwrap();
+        let (df, format, _, _, _) =
+            read_with_progress(parquet.path(), &CsvOptions::default(), &AtomicBool::new(false), |_| {}).unwrap();
+        assert_eq!(format, FileFormat::Parquet);
+        assert!(df.equals(&parquet_df));
+    }
+
+    #[test]
+    fn test_read_appended() {
+        let csv = "time,value\n2024-01-01 00:00:00,1.5\n2024-01-01 00:00:01,2.5\n";
+        let mut file = temp_file(".csv", csv.as_bytes());
+        let options = CsvOptions::default();
+        let (df, _, _, offset, _) = read_with_progress(file.path(), &options, &AtomicBool::new(false), |_| {}).unwrap();
+        let schema = df.schema().clone();
+
+        // Nothing new yet, then a complete row and one still being written
+        assert!(read_appended(file.path(), offset, b',', &options, schema.clone()).unwrap().is_none());
+        file.write_all(b"2024-01-01 00:00:02,3.5\n2024-01-01 00:00:0").unwrap();
+        file.flush().unwrap();
+        let (rows, offset) = read_appended(file.path(), offset, b',', &options, schema.clone()).unwrap().unwrap();
+        assert_eq!(rows.height(), 1);
+        assert_eq!(rows.schema(), df.schema());
+        assert_eq!(rows.column("value").unwrap().f64().unwrap().get(0), Some(3.5));
+
+        file.write_all(b"3,4.5\n").unwrap();
+        file.flush().unwrap();
+        let (rows, end) = read_appended(file.path(), offset, b',', &options, schema.clone()).unwrap().unwrap();
+        assert_eq!(rows.column("value").unwrap().f64().unwrap().get(0), Some(4.5));
+        assert_eq!(end, std::fs::metadata(file.path()).unwrap().len());
+
+        // Resuming from inside the last row skips the rest of it
+        assert!(read_appended(file.path(), end - 3, b',', &options, schema).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_read_with_progress_without_trailing_newline() {
+        let csv = "x,y\n1,2\n3,4";
+        let file = temp_file(".csv", csv.as_bytes());
+        let (df, _, _, read, unterminated) =
+            read_with_progress(file.path(), &CsvOptions::default(), &AtomicBool::new(false), |_| {}).unwrap();
+        // The last line is loaded, but reading resumes at its start
+        assert_eq!(df.shape(), (2, 2));
+        assert!(unterminated);
+        assert_eq!(read, "x,y\n1,2\n".len() as u64);
+
+        let file = temp_file(".csv", b"x,y\n1,2\n3,4\n");
+        let (_, _, _, read, unterminated) =
+            read_with_progress(file.path(), &CsvOptions::default(), &AtomicBool::new(false), |_| {}).unwrap();
+        assert!(!unterminated);
+        assert_eq!(read, 12);
+
+        // A header alone is not a row to read again
+        let file = temp_file(".csv", b"x,y");
+        let (df, _, _, read, unterminated) =
+            read_with_progress(file.path(), &CsvOptions::default(), &AtomicBool::new(false), |_| {}).unwrap();
+        assert_eq!((df.height(), read, unterminated), (0, 3, false));
+    }
+
+    #[test]
+    fn test_read_ndjson() {
+        let bytes = b"{\"t\": \"2024-01-01T00:00:00Z\", \"v\": 1, \"ok\": true}\n\
+                      {\"t\": \"2024-01-01T00:00:01Z\", \"v\": 2.5, \"tag\": \"a\"}\n";
+        let df = read_ndjson(bytes).unwrap();
+
+        // File order, not sorted: relies on serde_json's preserve_order feature
+        assert_eq!(df.get_column_names(), vec!["t", "v", "ok", "tag"]);
+        assert!(matches!(df.column("t").unwrap().dtype(), DataType::Datetime(_, _)));
+        assert_eq!(df.column("v").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(df.column("ok").unwrap().null_count(), 1);
+        assert_eq!(df.column("tag").unwrap().dtype(), &DataType::String);
+
+        assert!(read_ndjson(b"[1, 2]\n").is_err());
+    }
+}
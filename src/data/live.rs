@@ -0,0 +1,423 @@
+//! Live data from a socket or stdin
+//!
+//! A listener thread reads newline-delimited records (CSV lines under a header line, or
+//! JSON objects) and hands them over as text. Batches of lines are parsed off the UI thread
+//! with `parse_lines` and appended to a `DataSource`, which keeps only the rows the
+//! `Retention` allows, like a ring buffer.
+
+use super::DataError;
+use polars::prelude::*;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::net::{SocketAddr, TcpListener, UdpSocket};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long listener threads block before checking whether they have been stopped
+const STOP_CHECK: Duration = Duration::from_millis(100);
+
+/// Where live records come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiveSource {
+    /// Listen for TCP connections on an address such as "127.0.0.1:5000"; any number of
+    /// clients may connect, one after another or at once
+    Tcp(String),
+    /// Receive UDP datagrams on an address, each holding one or more lines
+    Udp(String),
+    /// Read lines piped to the program
+    Stdin,
+}
+
+impl std::fmt::Display for LiveSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LiveSource::Tcp(addr) => write!(f, "TCP {}", addr),
+            LiveSource::Udp(addr) => write!(f, "UDP {}", addr),
+            LiveSource::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+/// How each line encodes a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineFormat {
+    /// Delimited values; the first line received names the columns
+    Csv { separator: u8 },
+    /// One JSON object per line; the keys of the first batch name the columns
+    Json,
+}
+
+/// Which rows a live data set keeps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Retention {
+    /// The newest rows, up to this many
+    Rows(usize),
+    /// Rows received within this many seconds
+    Seconds(f64),
+}
+
+/// Everything needed to start a live session
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveConfig {
+    pub source: LiveSource,
+    pub format: LineFormat,
+    pub retention: Retention,
+}
+
+impl Default for LiveConfig {
+    fn default() -> Self {
+        Self {
+            source: LiveSource::Tcp("127.0.0.1:5000".to_string()),
+            format: LineFormat::Csv { separator: b',' },
+            retention: Retention::Rows(crate::constants::performance::DEFAULT_LIVE_ROWS),
+        }
+    }
+}
+
+/// Lines arriving from a live source, read on background threads until dropped
+pub struct LiveListener {
+    lines: Receiver<String>,
+    stop: Arc<AtomicBool>,
+    local_addr: Option<SocketAddr>,
+    closed: bool,
+}
+
+impl LiveListener {
+    /// Open `source` and start reading from it. Binding errors are reported here; errors
+    /// on individual connections just end those connections.
+    pub fn start(source: &LiveSource) -> Result<Self, DataError> {
+        let (tx, lines) = channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let local_addr = match source {
+            LiveSource::Tcp(addr) => {
+                let listener = TcpListener::bind(addr)?;
+                listener.set_nonblocking(true)?;
+                let local_addr = listener.local_addr()?;
+                let stop = stop.clone();
+                thread::spawn(move || accept_loop(listener, tx, stop));
+                Some(local_addr)
+            }
+            LiveSource::Udp(addr) => {
+                let socket = UdpSocket::bind(addr)?;
+                socket.set_read_timeout(Some(STOP_CHECK))?;
+                let local_addr = socket.local_addr()?;
+                let stop = stop.clone();
+                thread::spawn(move || receive_loop(socket, tx, stop));
+                Some(local_addr)
+            }
+            LiveSource::Stdin => {
+                let stop = stop.clone();
+                // Blocks on stdin, so it only notices `stop` after the next line
+                thread::spawn(move || read_lines(std::io::stdin().lock(), &tx, &stop));
+                None
+            }
+        };
+        Ok(Self { lines, stop, local_addr, closed: false })
+    }
+
+    /// Address the socket is bound to (None for stdin)
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Take up to `max` of the lines received so far
+    pub fn drain(&mut self, max: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        while lines.len() < max {
+            match self.lines.try_recv() {
+                Ok(line) => lines.push(line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.closed = true;
+                    break;
+                }
+            }
+        }
+        lines
+    }
+
+    /// Whether the source has ended (stdin closed or the socket failed) and every line
+    /// has been drained
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+impl Drop for LiveListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn accept_loop(listener: TcpListener, tx: Sender<String>, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let (tx, stop) = (tx.clone(), stop.clone());
+                thread::spawn(move || {
+                    // The read timeout lets the reader notice `stop` on an idle connection
+                    let ready = stream.set_nonblocking(false).and_then(|_| stream.set_read_timeout(Some(STOP_CHECK)));
+                    if ready.is_ok() {
+                        read_lines(BufReader::new(stream), &tx, &stop);
+                    }
+                });
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(STOP_CHECK),
+            Err(_) => break,
+        }
+    }
+}
+
+fn receive_loop(socket: UdpSocket, tx: Sender<String>, stop: Arc<AtomicBool>) {
+    let mut buf = vec![0u8; 65_536];
+    while !stop.load(Ordering::Relaxed) {
+        match socket.recv(&mut buf) {
+            Ok(n) => {
+                for line in String::from_utf8_lossy(&buf[..n]).lines() {
+                    if !line.trim().is_empty() && tx.send(line.to_string()).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => break,
+        }
+    }
+}
+
+/// Forward complete lines until end of input, an error, or `stop`. A line cut short by a
+/// read timeout is kept and finished by the next read.
+fn read_lines(mut reader: impl BufRead, tx: &Sender<String>, stop: &AtomicBool) {
+    let mut line = Vec::new();
+    while !stop.load(Ordering::Relaxed) {
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                let text = String::from_utf8_lossy(&line);
+                let record = text.trim_end_matches(['\r', '\n']);
+                if !record.trim().is_empty() && tx.send(record.to_string()).is_err() {
+                    break;
+                }
+                line.clear();
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => {}
+            Err(_) => break,
+        }
+    }
+}
+
+/// Parse a batch of lines into rows. CSV lines are read under `header` (repeats of the
+/// header, e.g. from a client reconnecting, are skipped). With `schema` the rows are cast
+/// to it, columns missing from the batch are null and extra ones are dropped, so every batch
+/// lines up with the rows before it; without it, integer columns are widened to floats so
+/// that later fractional values still fit.
+pub fn parse_lines(
+    lines: &[String],
+    format: LineFormat,
+    header: Option<&str>,
+    schema: Option<&Schema>,
+) -> Result<DataFrame, DataError> {
+    let df = match format {
+        LineFormat::Csv { separator } => {
+            let header = header.ok_or_else(|| DataError::UnsupportedFormat("CSV lines without a header".to_string()))?;
+            let mut text = format!("{}\n", header);
+            for line in lines.iter().filter(|line| line.as_str() != header) {
+                text.push_str(line);
+                text.push('\n');
+            }
+            CsvReadOptions::default()
+                .with_has_header(true)
+                .with_infer_schema_length(None)
+                .map_parse_options(|opts| {
+                    opts.with_separator(separator)
+                        .with_truncate_ragged_lines(true)
+                        .with_try_parse_dates(true)
+                })
+                .into_reader_with_file_handle(std::io::Cursor::new(text.into_bytes()))
+                .finish()?
+        }
+        LineFormat::Json => super::formats::read_ndjson(lines.join("\n").as_bytes())?,
+    };
+
+    let height = df.height();
+    let columns = match schema {
+        Some(schema) => schema.iter()
+            .map(|(name, dtype)| match df.column(name) {
+                Ok(column) => column.cast(dtype),
+                Err(_) => Ok(Column::full_null(name.clone(), height, dtype)),
+            })
+            .collect::<PolarsResult<Vec<_>>>()?,
+        None => df.take_columns()
+            .into_iter()
+            .map(|column| match column.dtype().is_integer() {
+                true => column.cast(&DataType::Float64),
+                false => Ok(column),
+            })
+            .collect::<PolarsResult<Vec<_>>>()?,
+    };
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Arrival times of the batches a live data set holds, to work out which rows have aged
+/// out of its `Retention`
+#[derive(Debug, Clone, Default)]
+pub struct BatchLog {
+    batches: VecDeque<(Instant, usize)>,
+}
+
+impl BatchLog {
+    /// Record a batch of `rows` received at `at`
+    pub fn push(&mut self, at: Instant, rows: usize) {
+        self.batches.push_back((at, rows));
+    }
+
+    /// Rows held
+    pub fn rows(&self) -> usize {
+        self.batches.iter().map(|(_, rows)| rows).sum()
+    }
+
+    /// Forget the oldest rows that `retention` no longer keeps at `now`, returning how many
+    /// to drop from the front of the data
+    pub fn expire(&mut self, retention: Retention, now: Instant) -> usize {
+        let mut excess = match retention {
+            Retention::Rows(max) => self.rows().saturating_sub(max),
+            Retention::Seconds(secs) => {
+                let max_age = Duration::from_secs_f64(secs.max(0.0));
+                self.batches.iter()
+                    .take_while(|(at, _)| now.saturating_duration_since(*at) > max_age)
+                    .map(|(_, rows)| rows)
+                    .sum()
+            }
+        };
+        let dropped = excess;
+        while excess > 0 {
+            let Some((_, rows)) = self.batches.front_mut() else {
+                break;
+            };
+            if *rows > excess {
+                *rows -= excess;
+                break;
+            }
+            excess -= *rows;
+            self.batches.pop_front();
+        }
+        dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::DataSource;
+
+    /// Send a recording's lines to a TCP listener, as a data acquisition process would
+    fn replay(addr: SocketAddr, text: &str) -> std::io::Result<()> {
+        use std::io::Write;
+        use std::net::TcpStream;
+        let mut stream = TcpStream::connect(addr)?;
+        for line in text.lines() {
+            writeln!(stream, "{}", line)?;
+        }
+        stream.flush()
+    }
+
+    /// Lines from `listener` until `n` have arrived
+    fn collect(listener: &mut LiveListener, n: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for _ in 0..500 {
+            lines.extend(listener.drain(usize::MAX));
+            if lines.len() >= n {
+                return lines;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("Only {} of {} lines arrived", lines.len(), n);
+    }
+
+    #[test]
+    fn test_tcp_replay_into_ring_buffer() {
+        let mut csv = String::from("t,value\n");
+        for i in 0..250 {
+            csv.push_str(&format!("{},{}\n", i, i % 10));
+        }
+        let mut listener = LiveListener::start(&LiveSource::Tcp("127.0.0.1:0".to_string())).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let sender = thread::spawn(move || replay(addr, &csv));
+
+        let mut lines = collect(&mut listener, 251);
+        sender.join().unwrap().unwrap();
+        let header = lines.remove(0);
+        assert_eq!(header, "t,value");
+
+        // Batches are parsed against the schema of the first and trimmed to the last 100 rows
+        let format = LineFormat::Csv { separator: b',' };
+        let first = parse_lines(&lines[..50], format, Some(&header), None).unwrap();
+        assert_eq!(first.column("value").unwrap().dtype(), &DataType::Float64);
+        let mut ds = DataSource::from_dataframe(first, None).unwrap();
+        let mut log = BatchLog::default();
+        log.push(Instant::now(), 50);
+        assert_eq!(ds.get_cached_column(0).unwrap().len(), 50);
+
+        let schema = ds.dataframe().schema().clone();
+        let rest = parse_lines(&lines[50..], format, Some(&header), Some(&schema)).unwrap();
+        ds.append(&rest).unwrap();
+        log.push(Instant::now(), rest.height());
+        ds.drop_front(log.expire(Retention::Rows(100), Instant::now()));
+        assert_eq!(ds.height(), 100);
+        assert_eq!(log.rows(), 100);
+        assert_eq!(ds.get_cached_column(0).unwrap()[..2], [150.0, 151.0]);
+        assert_eq!(ds.get_f64(99, 0), Some(249.0));
+    }
+
+    #[test]
+    fn test_udp_lines() {
+        let mut listener = LiveListener::start(&LiveSource::Udp("127.0.0.1:0".to_string())).unwrap();
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.send_to(b"{\"a\": 1}\n{\"a\": 2.5}", listener.local_addr().unwrap()).unwrap();
+
+        let lines = collect(&mut listener, 2);
+        let df = parse_lines(&lines, LineFormat::Json, None, None).unwrap();
+        assert_eq!(df.column("a").unwrap().f64().unwrap().get(1), Some(2.5));
+    }
+
+    #[test]
+    fn test_parse_lines_conforms_to_schema() {
+        let format = LineFormat::Csv { separator: b';' };
+        let header = "t;v";
+        let lines = ["2024-01-01 00:00:00;1".to_string(), "2024-01-01 00:00:01;2".to_string()];
+        let first = parse_lines(&lines, format, Some(header), None).unwrap();
+        assert!(matches!(first.column("t").unwrap().dtype(), DataType::Datetime(_, _)));
+        assert_eq!(first.column("v").unwrap().dtype(), &DataType::Float64);
+
+        // A reconnecting client repeats the header; bad values become nulls
+        let lines = [header.to_string(), "2024-01-01 00:00:02;x".to_string()];
+        let next = parse_lines(&lines, format, Some(header), Some(first.schema())).unwrap();
+        assert_eq!(next.schema(), first.schema());
+        assert_eq!(next.height(), 1);
+        assert_eq!(next.column("v").unwrap().null_count(), 1);
+
+        // JSON keys missing from a batch become null columns; unknown keys are dropped
+        let json = parse_lines(&["{\"t\": \"x\", \"w\": 1}".to_string()], LineFormat::Json, None, Some(first.schema())).unwrap();
+        assert_eq!(json.get_column_names(), vec!["t", "v"]);
+        assert!(parse_lines(&lines, format, None, None).is_err());
+    }
+
+    #[test]
+    fn test_batch_log_expiry() {
+        let start = Instant::now();
+        let mut log = BatchLog::default();
+        log.push(start, 10);
+        log.push(start + Duration::from_secs(5), 10);
+        log.push(start + Duration::from_secs(10), 10);
+
+        assert_eq!(log.expire(Retention::Rows(25), start), 5);
+        assert_eq!(log.rows(), 25);
+        // Batches older than 4 s at t = 10 s: the (partly dropped) first and the second
+        assert_eq!(log.expire(Retention::Seconds(4.0), start + Duration::from_secs(10)), 15);
+        assert_eq!(log.rows(), 10);
+        assert_eq!(log.expire(Retention::Rows(100), start), 0);
+    }
+}
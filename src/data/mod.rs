@@ -1,5 +1,15 @@
+pub mod capability;
+pub mod combine;
+pub mod expr;
+pub mod formats;
+pub mod live;
+pub mod msa;
+pub mod multivariate;
+pub mod pyramid;
+pub mod rules;
 pub mod source;
 pub mod stats;
+pub mod strata;
 
 // Re-export key types for convenience
 #[allow(unused_imports)]
@@ -0,0 +1,342 @@
+//! Measurement system analysis: crossed Gage R&R by the ANOVA method
+
+use crate::constants::spc::{GAGE_INTERACTION_ALPHA, GAGE_STUDY_SIGMAS};
+use crate::data::stats::f_cdf;
+use thiserror::Error;
+
+/// Errors that can occur when building a Gage R&R study
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum MsaError {
+    /// Part, operator and measurement columns differ in length
+    #[error("Part, operator and measurement columns differ in length")]
+    LengthMismatch,
+    /// Fewer than two parts or operators
+    #[error("Need at least 2 parts and 2 operators (found {parts} parts, {operators} operators)")]
+    TooFewLevels { parts: usize, operators: usize },
+    /// Some operator/part cells have a different number of trials
+    #[error("Study is unbalanced: every operator must measure every part the same number of times")]
+    Unbalanced,
+    /// Each operator must measure each part at least twice
+    #[error("Each operator must measure each part at least twice")]
+    TooFewTrials,
+}
+
+/// One row of the ANOVA table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnovaRow {
+    pub df: f64,
+    pub ss: f64,
+    pub ms: f64,
+    /// F statistic (None for the error and total rows)
+    pub f: Option<f64>,
+    /// p-value of the F statistic
+    pub p: Option<f64>,
+}
+
+/// Variance component with its share of total variation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarianceComponent {
+    /// Variance estimate
+    pub variance: f64,
+    /// Percent of total variance
+    pub pct_contribution: f64,
+    /// Study variation (GAGE_STUDY_SIGMAS × standard deviation)
+    pub study_var: f64,
+    /// Percent of total study variation
+    pub pct_study_var: f64,
+}
+
+/// Crossed Gage R&R study results
+#[derive(Debug, Clone, PartialEq)]
+pub struct GageRR {
+    /// Part labels in order of first appearance
+    pub parts: Vec<String>,
+    /// Operator labels in order of first appearance
+    pub operators: Vec<String>,
+    /// Trials per operator/part cell
+    pub trials: usize,
+    /// Mean measurement per [operator][part]
+    pub cell_means: Vec<Vec<f64>>,
+    /// Range of trials per [operator][part]
+    pub cell_ranges: Vec<Vec<f64>>,
+
+    /// ANOVA rows: part, operator, part × operator, repeatability, total
+    pub anova_part: AnovaRow,
+    pub anova_operator: AnovaRow,
+    pub anova_interaction: AnovaRow,
+    pub anova_repeatability: AnovaRow,
+    pub anova_total: AnovaRow,
+    /// Interaction was not significant and was pooled into repeatability
+    pub interaction_pooled: bool,
+
+    /// Variance components
+    pub total_grr: VarianceComponent,
+    pub repeatability: VarianceComponent,
+    pub reproducibility: VarianceComponent,
+    pub operator: VarianceComponent,
+    pub interaction: VarianceComponent,
+    pub part_to_part: VarianceComponent,
+    pub total: VarianceComponent,
+
+    /// Number of distinct categories
+    pub ndc: usize,
+}
+
+/// Index labels by order of first appearance
+fn levels(labels: &[String]) -> (Vec<String>, Vec<usize>) {
+    let mut names: Vec<String> = Vec::new();
+    let mut lookup = std::collections::HashMap::new();
+    let indices = labels.iter()
+        .map(|label| {
+            *lookup.entry(label.as_str()).or_insert_with(|| {
+                names.push(label.clone());
+                names.len() - 1
+            })
+        })
+        .collect();
+    (names, indices)
+}
+
+/// Run a crossed Gage R&R study. Rows with a non-finite measurement are skipped.
+pub fn gage_rr_anova(parts: &[String], operators: &[String], measurements: &[f64]) -> Result<GageRR, MsaError> {
+    if parts.len() != operators.len() || parts.len() != measurements.len() {
+        return Err(MsaError::LengthMismatch);
+    }
+    let keep: Vec<usize> = (0..measurements.len()).filter(|&i| measurements[i].is_finite()).collect();
+    let part_labels: Vec<String> = keep.iter().map(|&i| parts[i].clone()).collect();
+    let operator_labels: Vec<String> = keep.iter().map(|&i| operators[i].clone()).collect();
+
+    let (part_names, part_idx) = levels(&part_labels);
+    let (operator_names, operator_idx) = levels(&operator_labels);
+    let (p, o) = (part_names.len(), operator_names.len());
+    if p < 2 || o < 2 {
+        return Err(MsaError::TooFewLevels { parts: p, operators: o });
+    }
+
+    // Group trials per [operator][part]
+    let mut cells: Vec<Vec<Vec<f64>>> = vec![vec![Vec::new(); p]; o];
+    for (k, &i) in keep.iter().enumerate() {
+        cells[operator_idx[k]][part_idx[k]].push(measurements[i]);
+    }
+    let r = cells[0][0].len();
+    if cells.iter().flatten().any(|c| c.len() != r) {
+        return Err(MsaError::Unbalanced);
+    }
+    if r < 2 {
+        return Err(MsaError::TooFewTrials);
+    }
+
+    let (pf, of, rf) = (p as f64, o as f64, r as f64);
+    let n = pf * of * rf;
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    let cell_means: Vec<Vec<f64>> = cells.iter().map(|row| row.iter().map(|c| mean(c)).collect()).collect();
+    let cell_ranges: Vec<Vec<f64>> = cells.iter()
+        .map(|row| {
+            row.iter()
+                .map(|c| {
+                    let max = c.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                    let min = c.iter().copied().fold(f64::INFINITY, f64::min);
+                    max - min
+                })
+                .collect()
+        })
+        .collect();
+
+    let grand = cells.iter().flatten().flatten().sum::<f64>() / n;
+    let part_means: Vec<f64> = (0..p).map(|j| (0..o).map(|i| cell_means[i][j]).sum::<f64>() / of).collect();
+    let operator_means: Vec<f64> = cell_means.iter().map(|row| row.iter().sum::<f64>() / pf).collect();
+
+    let ss_part = of * rf * part_means.iter().map(|m| (m - grand).powi(2)).sum::<f64>();
+    let ss_operator = pf * rf * operator_means.iter().map(|m| (m - grand).powi(2)).sum::<f64>();
+    let ss_total: f64 = cells.iter().flatten().flatten().map(|v| (v - grand).powi(2)).sum();
+    let ss_repeat: f64 = cells.iter()
+        .zip(&cell_means)
+        .flat_map(|(row, means)| row.iter().zip(means))
+        .map(|(c, m)| c.iter().map(|v| (v - m).powi(2)).sum::<f64>())
+        .sum();
+    let ss_interaction = (ss_total - ss_part - ss_operator - ss_repeat).max(0.0);
+    // What is left of a sum of squares after rounding counts as none
+    let floor = ss_total * 1e-12;
+    let (ss_repeat, ss_interaction) = (
+        if ss_repeat <= floor { 0.0 } else { ss_repeat },
+        if ss_interaction <= floor { 0.0 } else { ss_interaction },
+    );
+
+    let row = |df: f64, ss: f64| AnovaRow { df, ss, ms: ss / df, f: None, p: None };
+    let with_f = |mut r: AnovaRow, denominator: &AnovaRow| {
+        if denominator.ms > 0.0 {
+            let f = r.ms / denominator.ms;
+            r.f = Some(f);
+            r.p = Some(1.0 - f_cdf(f, r.df, denominator.df));
+        } else if r.ms > 0.0 {
+            // No error variation (e.g. perfectly repeatable readings): any effect is significant
+            r.f = Some(f64::INFINITY);
+            r.p = Some(0.0);
+        } else {
+            // Neither varies, so there is no effect to find
+            r.p = Some(1.0);
+        }
+        r
+    };
+
+    let repeat_row = row(pf * of * (rf - 1.0), ss_repeat);
+    let interaction_row = with_f(row((pf - 1.0) * (of - 1.0), ss_interaction), &repeat_row);
+    let interaction_pooled = interaction_row.p.is_some_and(|p| p > GAGE_INTERACTION_ALPHA);
+
+    // A non-significant interaction is pooled with repeatability before testing the main effects
+    let error_row = if interaction_pooled {
+        row(interaction_row.df + repeat_row.df, ss_interaction + ss_repeat)
+    } else {
+        interaction_row
+    };
+    let part_row = with_f(row(pf - 1.0, ss_part), &error_row);
+    let operator_row = with_f(row(of - 1.0, ss_operator), &error_row);
+
+    let var_repeat = if interaction_pooled { error_row.ms } else { repeat_row.ms };
+    let var_interaction = if interaction_pooled {
+        0.0
+    } else {
+        ((interaction_row.ms - repeat_row.ms) / rf).max(0.0)
+    };
+    let var_operator = ((operator_row.ms - error_row.ms) / (pf * rf)).max(0.0);
+    let var_part = ((part_row.ms - error_row.ms) / (of * rf)).max(0.0);
+    let var_repro = var_operator + var_interaction;
+    let var_grr = var_repeat + var_repro;
+    let var_total = var_grr + var_part;
+
+    let component = |variance: f64| VarianceComponent {
+        variance,
+        pct_contribution: if var_total > 0.0 { variance / var_total * 100.0 } else { 0.0 },
+        study_var: GAGE_STUDY_SIGMAS * variance.sqrt(),
+        pct_study_var: if var_total > 0.0 { (variance / var_total).sqrt() * 100.0 } else { 0.0 },
+    };
+
+    let ndc = if var_grr > 0.0 {
+        ((2.0 * var_part / var_grr).sqrt().floor() as usize).max(1)
+    } else {
+        1
+    };
+
+    Ok(GageRR {
+        parts: part_names,
+        operators: operator_names,
+        trials: r,
+        cell_means,
+        cell_ranges,
+        anova_part: part_row,
+        anova_operator: operator_row,
+        anova_interaction: interaction_row,
+        anova_repeatability: repeat_row,
+        anova_total: row(n - 1.0, ss_total),
+        interaction_pooled,
+        total_grr: component(var_grr),
+        repeatability: component(var_repeat),
+        reproducibility: component(var_repro),
+        operator: component(var_operator),
+        interaction: component(var_interaction),
+        part_to_part: component(var_part),
+        total: component(var_total),
+        ndc,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3 parts x 2 operators x 2 trials, as parallel columns
+    fn study(values: &[[f64; 2]; 6]) -> (Vec<String>, Vec<String>, Vec<f64>) {
+        let mut parts = Vec::new();
+        let mut operators = Vec::new();
+        let mut measurements = Vec::new();
+        for (cell, trials) in values.iter().enumerate() {
+            for &v in trials {
+                parts.push(format!("P{}", cell % 3 + 1));
+                operators.push(if cell < 3 { "A".to_string() } else { "B".to_string() });
+                measurements.push(v);
+            }
+        }
+        (parts, operators, measurements)
+    }
+
+    #[test]
+    fn test_sums_of_squares_partition() {
+        let (parts, operators, values) = study(&[
+            [10.0, 10.2], [12.0, 12.1], [14.0, 13.8],
+            [10.3, 10.1], [12.4, 12.2], [14.1, 14.3],
+        ]);
+        let grr = gage_rr_anova(&parts, &operators, &values).unwrap();
+
+        assert_eq!(grr.parts, vec!["P1", "P2", "P3"]);
+        assert_eq!(grr.operators, vec!["A", "B"]);
+        assert_eq!(grr.trials, 2);
+        let sum = grr.anova_part.ss + grr.anova_operator.ss + grr.anova_interaction.ss + grr.anova_repeatability.ss;
+        assert!((sum - grr.anova_total.ss).abs() < 1e-9);
+        assert_eq!(grr.anova_total.df, 11.0);
+
+        // Part differences dominate, so the gauge should discriminate several categories
+        assert!(grr.part_to_part.pct_contribution > 90.0);
+        assert!(grr.ndc >= 5);
+        let pct = grr.total_grr.pct_contribution + grr.part_to_part.pct_contribution;
+        assert!((pct - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_variance_components_add_up() {
+        let (parts, operators, values) = study(&[
+            [5.0, 5.4], [6.1, 5.8], [7.0, 7.5],
+            [5.9, 6.2], [6.8, 7.1], [7.9, 8.1],
+        ]);
+        let grr = gage_rr_anova(&parts, &operators, &values).unwrap();
+
+        let repro = grr.operator.variance + grr.interaction.variance;
+        assert!((grr.reproducibility.variance - repro).abs() < 1e-12);
+        let total = grr.repeatability.variance + grr.reproducibility.variance + grr.part_to_part.variance;
+        assert!((grr.total.variance - total).abs() < 1e-12);
+        assert!(grr.operator.variance > 0.0, "operator B reads high");
+        if grr.interaction_pooled {
+            assert_eq!(grr.interaction.variance, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_identical_repeats() {
+        // Operator B reads part 2 high: an interaction with no repeatability error at all
+        let (parts, operators, values) = study(&[
+            [10.0, 10.0], [12.0, 12.0], [14.0, 14.0],
+            [10.0, 10.0], [12.5, 12.5], [14.0, 14.0],
+        ]);
+        let grr = gage_rr_anova(&parts, &operators, &values).unwrap();
+        assert_eq!(grr.anova_interaction.p, Some(0.0));
+        assert!(!grr.interaction_pooled);
+        assert_eq!(grr.repeatability.variance, 0.0);
+        assert!(grr.interaction.variance > 0.0);
+
+        // B reads every part the same amount high: no interaction either, so it is pooled
+        let (parts, operators, values) = study(&[
+            [10.0, 10.0], [12.0, 12.0], [14.0, 14.0],
+            [10.5, 10.5], [12.5, 12.5], [14.5, 14.5],
+        ]);
+        let grr = gage_rr_anova(&parts, &operators, &values).unwrap();
+        assert!(grr.interaction_pooled);
+        assert_eq!(grr.anova_part.p, Some(0.0));
+        assert!(grr.operator.variance > 0.0);
+        assert!(grr.total.variance.is_finite() && grr.total_grr.pct_contribution.is_finite());
+    }
+
+    #[test]
+    fn test_invalid_studies() {
+        let (parts, operators, mut values) = study(&[[1.0, 1.1]; 6]);
+        assert_eq!(gage_rr_anova(&parts, &operators[..3], &values), Err(MsaError::LengthMismatch));
+
+        // Dropping one trial unbalances the design
+        values[0] = f64::NAN;
+        assert_eq!(gage_rr_anova(&parts, &operators, &values), Err(MsaError::Unbalanced));
+
+        let single_op = vec!["A".to_string(); parts.len()];
+        assert!(matches!(
+            gage_rr_anova(&parts, &single_op, &[1.0; 12]),
+            Err(MsaError::TooFewLevels { operators: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,233 @@
+//! Multivariate control charts: Hotelling T² for individual observations
+
+use crate::data::stats::f_quantile;
+use std::ops::Range;
+use thiserror::Error;
+
+/// Errors that can occur when building a T² chart
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum MultivariateError {
+    /// T² needs at least two variables
+    #[error("Select at least 2 Y columns")]
+    TooFewVariables,
+    /// Phase I needs more complete observations than variables
+    #[error("Phase I needs at least {needed} complete rows (found {found})")]
+    TooFewObservations { needed: usize, found: usize },
+    /// Variables are perfectly correlated or constant
+    #[error("Covariance matrix is singular (constant or perfectly correlated columns)")]
+    SingularCovariance,
+}
+
+/// Share of one variable in a point's T² value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contribution {
+    /// d_j · (S⁻¹d)_j; contributions of all variables sum to T²
+    pub contribution: f64,
+    /// Unconditional term (x_j - x̄_j)² / s_jj, ignoring correlation
+    pub univariate: f64,
+}
+
+/// Hotelling T² chart for individual multivariate observations
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotellingT2 {
+    /// Source row of each point (rows with a missing value are skipped)
+    pub rows: Vec<usize>,
+    /// T² statistic per point
+    pub t2: Vec<f64>,
+    /// Phase I mean vector
+    pub mean: Vec<f64>,
+    /// Phase I sample covariance matrix
+    pub covariance: Vec<Vec<f64>>,
+    /// Number of Phase I observations (m)
+    pub phase_one: usize,
+    /// Upper control limit for future observations from the F distribution
+    pub ucl: f64,
+    inverse: Vec<Vec<f64>>,
+    deviations: Vec<Vec<f64>>,
+}
+
+impl HotellingT2 {
+    /// Indices of points above the UCL
+    pub fn out_of_control(&self) -> Vec<usize> {
+        (0..self.t2.len()).filter(|&i| self.t2[i] > self.ucl).collect()
+    }
+
+    /// Decompose a point's T² into per-variable contributions
+    pub fn contributions(&self, point: usize) -> Option<Vec<Contribution>> {
+        let d = self.deviations.get(point)?;
+        let weighted = mat_vec(&self.inverse, d);
+        Some(
+            d.iter()
+                .zip(&weighted)
+                .enumerate()
+                .map(|(j, (&dj, &wj))| Contribution {
+                    contribution: dj * wj,
+                    univariate: dj * dj / self.covariance[j][j],
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Build a T² chart over the given columns. The mean vector and covariance are estimated
+/// from the complete rows inside `baseline` (all complete rows when None), and the UCL is
+/// p(m+1)(m-1) / (m(m-p)) · F(1-α; p, m-p).
+pub fn hotelling_t2(
+    columns: &[&[f64]],
+    baseline: Option<Range<usize>>,
+    alpha: f64,
+) -> Result<HotellingT2, MultivariateError> {
+    let p = columns.len();
+    if p < 2 {
+        return Err(MultivariateError::TooFewVariables);
+    }
+
+    let n_rows = columns.iter().map(|c| c.len()).min().unwrap_or(0);
+    let rows: Vec<usize> = (0..n_rows).filter(|&r| columns.iter().all(|c| c[r].is_finite())).collect();
+    let observation = |r: usize| -> Vec<f64> { columns.iter().map(|c| c[r]).collect() };
+
+    let phase_rows: Vec<usize> = rows.iter()
+        .copied()
+        .filter(|r| baseline.as_ref().is_none_or(|b| b.contains(r)))
+        .collect();
+    let m = phase_rows.len();
+    if m <= p + 1 {
+        return Err(MultivariateError::TooFewObservations { needed: p + 2, found: m });
+    }
+
+    let mut mean = vec![0.0; p];
+    for &r in &phase_rows {
+        for (j, v) in observation(r).into_iter().enumerate() {
+            mean[j] += v / m as f64;
+        }
+    }
+    let mut covariance = vec![vec![0.0; p]; p];
+    for &r in &phase_rows {
+        let d: Vec<f64> = observation(r).iter().zip(&mean).map(|(v, mu)| v - mu).collect();
+        for i in 0..p {
+            for j in 0..p {
+                covariance[i][j] += d[i] * d[j] / (m - 1) as f64;
+            }
+        }
+    }
+    let inverse = invert(&covariance).ok_or(MultivariateError::SingularCovariance)?;
+
+    let deviations: Vec<Vec<f64>> = rows.iter()
+        .map(|&r| observation(r).iter().zip(&mean).map(|(v, mu)| v - mu).collect())
+        .collect();
+    let t2 = deviations.iter()
+        .map(|d| d.iter().zip(mat_vec(&inverse, d)).map(|(a, b)| a * b).sum())
+        .collect();
+
+    let (pf, mf) = (p as f64, m as f64);
+    let ucl = pf * (mf + 1.0) * (mf - 1.0) / (mf * (mf - pf)) * f_quantile(1.0 - alpha, pf, mf - pf);
+
+    Ok(HotellingT2 { rows, t2, mean, covariance, phase_one: m, ucl, inverse, deviations })
+}
+
+fn mat_vec(matrix: &[Vec<f64>], v: &[f64]) -> Vec<f64> {
+    matrix.iter().map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum()).collect()
+}
+
+/// Gauss-Jordan inverse with partial pivoting; None if the matrix is (near) singular
+fn invert(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let scale = matrix.iter().flatten().fold(0.0f64, |acc, v| acc.max(v.abs()));
+    if scale == 0.0 {
+        return None;
+    }
+    let mut a: Vec<Vec<f64>> = matrix.to_vec();
+    let mut inv: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&x, &y| a[x][col].abs().total_cmp(&a[y][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 * scale {
+            return None;
+        }
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+
+        let diag = a[col][col];
+        for j in 0..n {
+            a[col][j] /= diag;
+            inv[col][j] /= diag;
+        }
+        for row in 0..n {
+            if row != col {
+                let factor = a[row][col];
+                for j in 0..n {
+                    a[row][j] -= factor * a[col][j];
+                    inv[row][j] -= factor * inv[col][j];
+                }
+            }
+        }
+    }
+    Some(inv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two correlated columns: y tracks x with a small alternating offset
+    fn correlated(n: usize) -> (Vec<f64>, Vec<f64>) {
+        let x: Vec<f64> = (0..n).map(|i| ((i * 7) % 13) as f64).collect();
+        let y: Vec<f64> = x.iter().enumerate().map(|(i, v)| v + if i % 2 == 0 { 0.5 } else { -0.5 }).collect();
+        (x, y)
+    }
+
+    #[test]
+    fn test_invert() {
+        let inv = invert(&[vec![4.0, 2.0], vec![2.0, 3.0]]).unwrap();
+        // 1/8 * [[3, -2], [-2, 4]]
+        assert!((inv[0][0] - 0.375).abs() < 1e-12);
+        assert!((inv[0][1] + 0.25).abs() < 1e-12);
+        assert!((inv[1][1] - 0.5).abs() < 1e-12);
+        assert!(invert(&[vec![1.0, 2.0], vec![2.0, 4.0]]).is_none());
+    }
+
+    #[test]
+    fn test_t2_flags_correlation_break() {
+        let (mut x, mut y) = correlated(40);
+        // Both values are ordinary on their own, but together they break the correlation
+        x.push(10.0);
+        y.push(2.0);
+        let chart = hotelling_t2(&[&x, &y], Some(0..40), 0.0027).unwrap();
+
+        assert_eq!(chart.phase_one, 40);
+        assert_eq!(chart.rows.len(), 41);
+        assert_eq!(chart.out_of_control(), vec![40]);
+        // Phase I points average to p(m-1)/m
+        let mean_t2 = chart.t2[..40].iter().sum::<f64>() / 40.0;
+        assert!((mean_t2 - 2.0 * 39.0 / 40.0).abs() < 1e-9);
+
+        let contributions = chart.contributions(40).unwrap();
+        let total: f64 = contributions.iter().map(|c| c.contribution).sum();
+        assert!((total - chart.t2[40]).abs() < 1e-9);
+        assert!(contributions.iter().all(|c| c.univariate < chart.ucl));
+        assert!(chart.contributions(41).is_none());
+    }
+
+    #[test]
+    fn test_ucl_and_missing_rows() {
+        let (mut x, y) = correlated(30);
+        x[3] = f64::NAN;
+        let chart = hotelling_t2(&[&x, &y], None, 0.0027).unwrap();
+        assert!(!chart.rows.contains(&3));
+        let (p, m) = (2.0, 29.0);
+        let expected = p * (m + 1.0) * (m - 1.0) / (m * (m - p)) * f_quantile(0.9973, p, m - p);
+        assert!((chart.ucl - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_errors() {
+        let (x, y) = correlated(30);
+        assert_eq!(hotelling_t2(&[&x], None, 0.0027), Err(MultivariateError::TooFewVariables));
+        assert_eq!(
+            hotelling_t2(&[&x, &y], Some(0..3), 0.0027),
+            Err(MultivariateError::TooFewObservations { needed: 4, found: 3 })
+        );
+        let doubled: Vec<f64> = x.iter().map(|v| v * 2.0).collect();
+        assert_eq!(hotelling_t2(&[&x, &doubled], None, 0.0027), Err(MultivariateError::SingularCovariance));
+    }
+}
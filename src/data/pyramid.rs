@@ -0,0 +1,433 @@
+//! Min/max pyramids: multi-level column summaries for drawing series too large to plot
+//! point by point
+//!
+//! Level 0 summarises fixed blocks of `base` rows; each level above merges `FANOUT`
+//! neighbouring buckets. A row range can then be answered at whichever level gives about
+//! one bucket per pixel, and drawing each bucket's min and max keeps every spike visible.
+
+use std::ops::Range;
+
+/// Buckets merged into one at the next level up
+const FANOUT: usize = 4;
+
+/// Summary of the finite values in a block of rows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    /// Number of finite values (missing values are skipped)
+    pub count: usize,
+    /// Sum of squared deviations from the mean
+    pub m2: f64,
+    /// Rows of the first and last finite values
+    pub first_row: usize,
+    pub last_row: usize,
+    /// Rows holding the min and max (the earliest on ties)
+    pub min_row: usize,
+    pub max_row: usize,
+}
+
+impl Default for Bucket {
+    fn default() -> Self {
+        Self {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            count: 0,
+            m2: 0.0,
+            first_row: 0,
+            last_row: 0,
+            min_row: 0,
+            max_row: 0,
+        }
+    }
+}
+
+impl Bucket {
+    fn push(&mut self, row: usize, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        if self.count == 0 {
+            self.first_row = row;
+        }
+        if value < self.min {
+            self.min = value;
+            self.min_row = row;
+        }
+        if value > self.max {
+            self.max = value;
+            self.max_row = row;
+        }
+        // Welford update
+        let delta = value - self.mean().unwrap_or(value);
+        self.sum += value;
+        self.count += 1;
+        self.m2 += delta * (value - self.sum / self.count as f64);
+        self.last_row = row;
+    }
+
+    /// Fold in the bucket that follows this one (Chan et al. for the squared deviations)
+    fn merge(&mut self, other: &Bucket) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            *self = *other;
+            return;
+        }
+        let (n_a, n_b) = (self.count as f64, other.count as f64);
+        let delta = other.sum / n_b - self.sum / n_a;
+        self.m2 += other.m2 + delta * delta * n_a * n_b / (n_a + n_b);
+        if other.min < self.min {
+            self.min = other.min;
+            self.min_row = other.min_row;
+        }
+        if other.max > self.max {
+            self.max = other.max;
+            self.max_row = other.max_row;
+        }
+        self.sum += other.sum;
+        self.count += other.count;
+        self.last_row = other.last_row;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    /// Population standard deviation
+    pub fn std_dev(&self) -> Option<f64> {
+        (self.count > 0).then(|| (self.m2 / self.count as f64).sqrt())
+    }
+}
+
+/// Multi-level min/max summary of one column
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinMaxPyramid {
+    /// Rows per level-0 bucket
+    base: usize,
+    /// levels[0] holds one bucket per `base` rows; the last level holds a single bucket
+    levels: Vec<Vec<Bucket>>,
+    /// Number of rows summarised
+    len: usize,
+    /// Finite values never decrease from row to row (e.g. a timestamp column)
+    sorted: bool,
+    /// Last finite value, to keep `sorted` up to date as rows are added
+    last: f64,
+}
+
+/// Builds a pyramid from values arriving in chunks, so a column never has to be in
+/// memory all at once
+#[derive(Debug, Clone)]
+pub struct PyramidBuilder {
+    pyramid: MinMaxPyramid,
+}
+
+impl PyramidBuilder {
+    pub fn new(base: usize) -> Self {
+        Self { pyramid: MinMaxPyramid::new(base) }
+    }
+
+    /// Append the next rows of the column
+    pub fn extend(&mut self, values: &[f64]) {
+        self.pyramid.extend(values);
+    }
+
+    pub fn finish(self) -> MinMaxPyramid {
+        self.pyramid
+    }
+}
+
+impl MinMaxPyramid {
+    /// An empty summary with `base` rows per level-0 bucket
+    pub fn new(base: usize) -> Self {
+        Self {
+            base: base.max(1),
+            levels: vec![Vec::new()],
+            len: 0,
+            sorted: true,
+            last: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Summarise an in-memory column
+    pub fn build(values: &[f64], base: usize) -> Self {
+        let mut pyramid = Self::new(base);
+        pyramid.extend(values);
+        pyramid
+    }
+
+    /// Add rows to the end of the column. Only the buckets covering the new rows are
+    /// merged again, so a few rows cost about one bucket per level.
+    pub fn extend(&mut self, values: &[f64]) {
+        if values.is_empty() {
+            return;
+        }
+        let first_changed = self.len / self.base;
+        let level0 = &mut self.levels[0];
+        for &value in values {
+            if value.is_finite() {
+                self.sorted &= value >= self.last;
+                self.last = value;
+            }
+            let i = self.len / self.base;
+            if i == level0.len() {
+                level0.push(Bucket::default());
+            }
+            level0[i].push(self.len, value);
+            self.len += 1;
+        }
+
+        // Merge the changed buckets up to a level with a single bucket
+        let mut changed = first_changed;
+        let mut level = 1;
+        while self.levels[level - 1].len() > 1 {
+            changed /= FANOUT;
+            if level == self.levels.len() {
+                self.levels.push(Vec::new());
+            }
+            let (below, above) = self.levels.split_at_mut(level);
+            let above = &mut above[0];
+            above.truncate(changed);
+            above.extend(below[level - 1][changed * FANOUT..].chunks(FANOUT).map(|group| {
+                let mut bucket = Bucket::default();
+                group.iter().for_each(|b| bucket.merge(b));
+                bucket
+            }));
+            level += 1;
+        }
+    }
+
+    /// Whether finite values never decrease, so X values can be mapped back to rows
+    pub fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    /// Summary of the whole column
+    pub fn total(&self) -> Bucket {
+        self.levels.last().and_then(|level| level.first()).copied().unwrap_or_default()
+    }
+
+    /// Rows covered by one bucket at `level`
+    fn bucket_rows(&self, level: usize) -> usize {
+        self.base * FANOUT.pow(level as u32)
+    }
+
+    /// Buckets covering `rows` at the finest level that needs no more than `max_buckets` of
+    /// them, with the rows each one spans. Partially covered buckets at the edges are included
+    /// whole, and two pyramids of the same length and base split a range identically.
+    pub fn query(&self, rows: Range<usize>, max_buckets: usize) -> Vec<(Range<usize>, Bucket)> {
+        let rows = rows.start.min(self.len)..rows.end.min(self.len);
+        if rows.is_empty() {
+            return Vec::new();
+        }
+        let level = (0..self.levels.len())
+            .find(|&level| {
+                let size = self.bucket_rows(level);
+                rows.end.div_ceil(size) - rows.start / size <= max_buckets.max(1)
+            })
+            .unwrap_or(self.levels.len() - 1);
+
+        let size = self.bucket_rows(level);
+        (rows.start / size..rows.end.div_ceil(size))
+            .filter_map(|i| {
+                let bucket = self.levels[level].get(i)?;
+                Some((i * size..((i + 1) * size).min(self.len), *bucket))
+            })
+            .collect()
+    }
+
+    /// Rows worth drawing from `rows` at about `max_buckets` buckets: the first, min, max and
+    /// last finite value of each bucket (M4), in row order. A line through them covers the
+    /// same pixels as a line through every row when there is about one bucket per pixel.
+    pub fn m4_rows(&self, rows: Range<usize>, max_buckets: usize) -> Vec<usize> {
+        let mut picked = Vec::new();
+        for (_, bucket) in self.query(rows, max_buckets) {
+            if bucket.is_empty() {
+                continue;
+            }
+            let mut corners = [bucket.first_row, bucket.min_row, bucket.max_row, bucket.last_row];
+            corners.sort_unstable();
+            for row in corners {
+                if picked.last() != Some(&row) {
+                    picked.push(row);
+                }
+            }
+        }
+        picked
+    }
+
+    /// First row that can hold a value >= `x`, to the nearest level-0 bucket (sorted columns only)
+    pub fn lower_bound(&self, x: f64) -> usize {
+        let i = partition_buckets(self.levels[0].len(), |i| self.max_through(i) < x);
+        (i * self.base).min(self.len)
+    }
+
+    /// Row after the last one that can hold a value <= `x`, to the nearest level-0 bucket
+    /// (sorted columns only)
+    pub fn upper_bound(&self, x: f64) -> usize {
+        let i = partition_buckets(self.levels[0].len(), |i| self.min_from(i) <= x);
+        (i * self.base).min(self.len)
+    }
+
+    /// Largest value in level-0 buckets up to `i`: in a sorted column, the max of the nearest
+    /// non-empty one. Empty (all-missing) buckets take their neighbour's bound, which keeps
+    /// the bucket searches monotone.
+    fn max_through(&self, i: usize) -> f64 {
+        self.levels[0][..=i].iter().rev().find(|b| !b.is_empty()).map_or(f64::NEG_INFINITY, |b| b.max)
+    }
+
+    /// Smallest value in level-0 buckets from `i` on (sorted columns only)
+    fn min_from(&self, i: usize) -> f64 {
+        self.levels[0][i..].iter().find(|b| !b.is_empty()).map_or(f64::INFINITY, |b| b.min)
+    }
+}
+
+/// Index of the first of `n` buckets for which `pred` is false, given it is true up to
+/// some point and false after
+fn partition_buckets(n: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut lo, mut hi) = (0, n);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Rows whose X values can fall in `x_min..=x_max`. With a pyramid of the X column this
+/// is a bucket-granular slice when it is sorted and every row otherwise; with no X
+/// column the X values are the row indices themselves.
+pub fn visible_rows(x: Option<&MinMaxPyramid>, x_min: f64, x_max: f64, rows: usize) -> Range<usize> {
+    match x {
+        Some(x) if x.is_sorted() => {
+            let start = x.lower_bound(x_min);
+            start..x.upper_bound(x_max).max(start)
+        }
+        Some(_) => 0..rows,
+        None => {
+            let start = (x_min.floor().max(0.0) as usize).min(rows);
+            start..(x_max.ceil().max(0.0) as usize + 1).clamp(start, rows)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunked_build_matches_whole() {
+        let values: Vec<f64> = (0..1000).map(|i| ((i * 37) % 101) as f64).collect();
+        let whole = MinMaxPyramid::build(&values, 16);
+
+        let mut builder = PyramidBuilder::new(16);
+        for chunk in values.chunks(77) {
+            builder.extend(chunk);
+        }
+        assert_eq!(builder.finish(), whole);
+
+        // Rows added one at a time end up in the same buckets
+        let mut grown = MinMaxPyramid::new(16);
+        values.iter().for_each(|&v| grown.extend(&[v]));
+        assert_eq!(grown, whole);
+
+        let total = whole.total();
+        assert_eq!((total.min, total.max, total.count), (0.0, 100.0, 1000));
+        assert!(!whole.is_sorted());
+    }
+
+    #[test]
+    fn test_query_keeps_spikes() {
+        let mut values = vec![1.0; 100_000];
+        values[54_321] = 50.0;
+        values[60_000] = f64::NAN;
+        let pyramid = MinMaxPyramid::build(&values, 64);
+
+        let buckets = pyramid.query(0..100_000, 500);
+        assert!(buckets.len() <= 500);
+        assert_eq!(buckets.first().unwrap().0.start, 0);
+        assert_eq!(buckets.last().unwrap().0.end, 100_000);
+        assert_eq!(buckets.iter().map(|(_, b)| b.max).fold(0.0, f64::max), 50.0);
+        assert_eq!(buckets.iter().map(|(_, b)| b.count).sum::<usize>(), 99_999);
+
+        // A narrow window drops to level 0 and still contains the spike
+        let narrow = pyramid.query(54_000..55_000, 500);
+        assert!(narrow.iter().all(|(rows, _)| rows.len() == 64));
+        assert!(narrow.iter().any(|(rows, b)| rows.contains(&54_321) && b.max == 50.0));
+        assert!(pyramid.query(200_000..300_000, 10).is_empty());
+    }
+
+    #[test]
+    fn test_bounds_on_sorted_column() {
+        let x: Vec<f64> = (0..1000).map(|i| i as f64 * 0.5).collect();
+        let pyramid = MinMaxPyramid::build(&x, 10);
+        assert!(pyramid.is_sorted());
+        // x = 100 is row 200, x = 200 is row 400
+        assert_eq!(pyramid.lower_bound(100.0), 200);
+        assert_eq!(pyramid.upper_bound(200.0), 410);
+        assert_eq!(pyramid.lower_bound(-5.0), 0);
+        assert_eq!(pyramid.upper_bound(1e9), 1000);
+        assert_eq!(visible_rows(Some(&pyramid), 100.0, 200.0, 1000), 200..410);
+        assert_eq!(visible_rows(None, -3.5, 20.2, 1000), 0..22);
+        assert_eq!(visible_rows(None, 2000.0, 3000.0, 1000), 1000..1000);
+    }
+
+    #[test]
+    fn test_bounds_skip_missing_buckets() {
+        // Rows 40..50 and 70..80 are missing, so two level-0 buckets are empty
+        let x: Vec<f64> = (0..100)
+            .map(|i| if (40..50).contains(&i) || (70..80).contains(&i) { f64::NAN } else { i as f64 })
+            .collect();
+        let pyramid = MinMaxPyramid::build(&x, 10);
+        assert!(pyramid.is_sorted());
+        for (value, lower, upper) in [(25.0, 20, 30), (45.0, 50, 40), (55.0, 50, 60), (75.0, 80, 70), (95.0, 90, 100)] {
+            assert_eq!(pyramid.lower_bound(value), lower, "lower_bound({})", value);
+            assert_eq!(pyramid.upper_bound(value), upper, "upper_bound({})", value);
+        }
+        assert_eq!(visible_rows(Some(&pyramid), 12.0, 62.0, 100), 10..70);
+
+        // Every row in range is kept, whichever buckets the search probes
+        let x: Vec<f64> = (0..1000).map(|i| if i % 160 >= 150 { f64::NAN } else { i as f64 }).collect();
+        let pyramid = MinMaxPyramid::build(&x, 10);
+        for value in (0..1000).step_by(7).map(|v| v as f64) {
+            let rows = visible_rows(Some(&pyramid), value, value + 30.0, 1000);
+            let wanted = x.iter().enumerate().filter(|(_, v)| **v >= value && **v <= value + 30.0).map(|(i, _)| i);
+            assert!(wanted.clone().all(|i| rows.contains(&i)), "{:?} misses rows for x = {}", rows, value);
+        }
+    }
+
+    #[test]
+    fn test_m4_rows_and_moments() {
+        let values: Vec<f64> = (0..4096).map(|i| ((i * 7919) % 1000) as f64 / 10.0).collect();
+        let pyramid = MinMaxPyramid::build(&values, 8);
+
+        // Matches a direct computation over the whole column
+        let total = pyramid.total();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        assert!((total.mean().unwrap() - mean).abs() < 1e-9);
+        assert!((total.std_dev().unwrap() - var.sqrt()).abs() < 1e-9);
+        assert_eq!(values[total.min_row], total.min);
+        assert_eq!(values[total.max_row], total.max);
+        assert_eq!((total.first_row, total.last_row), (0, 4095));
+
+        // Each bucket contributes at most four rows, in order, including its extremes
+        let rows = pyramid.m4_rows(1000..3000, 100);
+        assert!(rows.windows(2).all(|w| w[0] < w[1]));
+        for (span, bucket) in pyramid.query(1000..3000, 100) {
+            let inside: Vec<usize> = rows.iter().copied().filter(|r| span.contains(r)).collect();
+            assert!(inside.len() <= 4);
+            assert!(inside.contains(&bucket.min_row) && inside.contains(&bucket.max_row));
+        }
+    }
+}
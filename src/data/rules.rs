@@ -0,0 +1,327 @@
+//! Run rules for control charts (Nelson rules and the Western Electric originals)
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Identifies a run rule. Variants follow the Nelson numbering; the Western Electric
+/// rules are the subset reported by [`RuleId::we_number`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RuleId {
+    /// Nelson 1 / WE 1: one point beyond 3σ
+    BeyondThreeSigma,
+    /// Nelson 2 / WE 4: a run of points on the same side of the centre line
+    SameSide,
+    /// Nelson 3: a run of points steadily increasing or decreasing
+    Trend,
+    /// Nelson 4: a run of points alternating up and down
+    Alternating,
+    /// Nelson 5 / WE 2: k of n points beyond 2σ on the same side
+    BeyondTwoSigma,
+    /// Nelson 6 / WE 3: k of n points beyond 1σ on the same side
+    BeyondOneSigma,
+    /// Nelson 7: a run of points within 1σ on either side (stratification)
+    WithinOneSigma,
+    /// Nelson 8: a run of points beyond 1σ on either side (mixture)
+    OutsideOneSigma,
+}
+
+impl RuleId {
+    /// All rules in Nelson order
+    pub const ALL: [RuleId; 8] = [
+        RuleId::BeyondThreeSigma,
+        RuleId::SameSide,
+        RuleId::Trend,
+        RuleId::Alternating,
+        RuleId::BeyondTwoSigma,
+        RuleId::BeyondOneSigma,
+        RuleId::WithinOneSigma,
+        RuleId::OutsideOneSigma,
+    ];
+
+    /// Nelson rule number (1-8)
+    pub fn nelson_number(self) -> u8 {
+        self as u8 + 1
+    }
+
+    /// Western Electric rule number, if this rule is one of the four WE originals
+    pub fn we_number(self) -> Option<u8> {
+        match self {
+            RuleId::BeyondThreeSigma => Some(1),
+            RuleId::BeyondTwoSigma => Some(2),
+            RuleId::BeyondOneSigma => Some(3),
+            RuleId::SameSide => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Short description of what the rule detects
+    pub fn description(self) -> &'static str {
+        match self {
+            RuleId::BeyondThreeSigma => "beyond 3σ",
+            RuleId::SameSide => "same side of mean",
+            RuleId::Trend => "trending",
+            RuleId::Alternating => "alternating",
+            RuleId::BeyondTwoSigma => "beyond 2σ",
+            RuleId::BeyondOneSigma => "beyond 1σ",
+            RuleId::WithinOneSigma => "within 1σ",
+            RuleId::OutsideOneSigma => "outside 1σ, both sides",
+        }
+    }
+
+    /// Whether the rule counts k of n points rather than requiring an unbroken run
+    pub fn uses_count(self) -> bool {
+        matches!(self, RuleId::BeyondTwoSigma | RuleId::BeyondOneSigma)
+    }
+
+    /// Shortest run length the rule can be evaluated over
+    pub fn min_run_length(self) -> usize {
+        match self {
+            RuleId::BeyondThreeSigma => 1,
+            RuleId::Alternating => 3,
+            _ => 2,
+        }
+    }
+
+    /// Default run length (window size) and point count for the Nelson definition
+    fn nelson_defaults(self) -> (usize, usize) {
+        match self {
+            RuleId::BeyondThreeSigma => (1, 1),
+            RuleId::SameSide => (9, 9),
+            RuleId::Trend => (6, 6),
+            RuleId::Alternating => (14, 14),
+            RuleId::BeyondTwoSigma => (3, 2),
+            RuleId::BeyondOneSigma => (5, 4),
+            RuleId::WithinOneSigma => (15, 15),
+            RuleId::OutsideOneSigma => (8, 8),
+        }
+    }
+}
+
+impl std::fmt::Display for RuleId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Nelson {}", self.nelson_number())?;
+        if let Some(we) = self.we_number() {
+            write!(f, " / WE {}", we)?;
+        }
+        write!(f, ": {}", self.description())
+    }
+}
+
+/// Toggle and parameters for a single rule
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RuleSetting {
+    /// Whether the rule is evaluated
+    pub enabled: bool,
+    /// Number of consecutive points the rule looks at
+    pub run_length: usize,
+    /// Points within the run that must meet the condition (k of n rules only)
+    pub count: usize,
+}
+
+/// Collection of rule settings keyed by rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSet {
+    settings: BTreeMap<RuleId, RuleSetting>,
+}
+
+impl Default for RuleSet {
+    /// Western Electric rules plus the trend and alternating checks
+    fn default() -> Self {
+        let mut set = Self::western_electric();
+        set.get_mut(RuleId::Trend).enabled = true;
+        set.get_mut(RuleId::Alternating).enabled = true;
+        set
+    }
+}
+
+impl RuleSet {
+    /// All eight Nelson rules with their standard run lengths
+    pub fn nelson() -> Self {
+        let settings = RuleId::ALL.iter()
+            .map(|&id| {
+                let (run_length, count) = id.nelson_defaults();
+                (id, RuleSetting { enabled: true, run_length, count })
+            })
+            .collect();
+        Self { settings }
+    }
+
+    /// The four Western Electric rules (the same-side run is 8 points instead of 9)
+    pub fn western_electric() -> Self {
+        let mut set = Self::nelson();
+        for id in RuleId::ALL {
+            set.get_mut(id).enabled = id.we_number().is_some();
+        }
+        set.get_mut(RuleId::SameSide).run_length = 8;
+        set
+    }
+
+    /// Settings for a rule
+    pub fn get(&self, id: RuleId) -> RuleSetting {
+        self.settings.get(&id).copied().unwrap_or_else(|| {
+            let (run_length, count) = id.nelson_defaults();
+            RuleSetting { enabled: false, run_length, count }
+        })
+    }
+
+    /// Mutable settings for a rule
+    pub fn get_mut(&mut self, id: RuleId) -> &mut RuleSetting {
+        self.settings.entry(id).or_insert_with(|| {
+            let (run_length, count) = id.nelson_defaults();
+            RuleSetting { enabled: false, run_length, count }
+        })
+    }
+
+    /// Clamp run lengths to values each rule can evaluate
+    pub fn validate(&mut self) {
+        for id in RuleId::ALL {
+            let setting = self.get_mut(id);
+            setting.run_length = setting.run_length.clamp(id.min_run_length(), 50);
+            setting.count = setting.count.clamp(1, setting.run_length);
+        }
+    }
+}
+
+/// Evaluate the enabled rules against a centre line and sigma, returning the rules
+/// that flagged each point (in Nelson order). Every point in a violating window is flagged.
+pub fn evaluate(values: &[f64], mean: f64, sigma: f64, rules: &RuleSet) -> BTreeMap<usize, Vec<RuleId>> {
+    let mut flagged: BTreeMap<usize, Vec<RuleId>> = BTreeMap::new();
+    if values.is_empty() || !sigma.is_finite() || sigma <= 0.0 {
+        return flagged;
+    }
+
+    let zone = |v: f64| (v - mean) / sigma;
+
+    for id in RuleId::ALL {
+        let setting = rules.get(id);
+        let n = setting.run_length.max(1);
+        if !setting.enabled || values.len() < n {
+            continue;
+        }
+
+        for start in 0..=(values.len() - n) {
+            let window = &values[start..start + n];
+            let hit = match id {
+                RuleId::BeyondThreeSigma => window.iter().all(|&v| zone(v).abs() > 3.0),
+                RuleId::SameSide => {
+                    window.iter().all(|&v| v > mean) || window.iter().all(|&v| v < mean)
+                }
+                RuleId::Trend => {
+                    window.windows(2).all(|w| w[1] > w[0]) || window.windows(2).all(|w| w[1] < w[0])
+                }
+                RuleId::Alternating => window.windows(3).all(|w| {
+                    (w[1] > w[0] && w[1] > w[2]) || (w[1] < w[0] && w[1] < w[2])
+                }),
+                RuleId::BeyondTwoSigma | RuleId::BeyondOneSigma => {
+                    let limit = if id == RuleId::BeyondTwoSigma { 2.0 } else { 1.0 };
+                    let above = window.iter().filter(|&&v| zone(v) > limit).count();
+                    let below = window.iter().filter(|&&v| zone(v) < -limit).count();
+                    let k = setting.count.min(n);
+                    above >= k || below >= k
+                }
+                RuleId::WithinOneSigma => window.iter().all(|&v| zone(v).abs() < 1.0),
+                RuleId::OutsideOneSigma => {
+                    window.iter().all(|&v| zone(v).abs() > 1.0)
+                        && window.iter().any(|&v| zone(v) > 1.0)
+                        && window.iter().any(|&v| zone(v) < -1.0)
+                }
+            };
+
+            if hit {
+                for i in start..start + n {
+                    let ids = flagged.entry(i).or_default();
+                    if ids.last() != Some(&id) {
+                        ids.push(id);
+                    }
+                }
+            }
+        }
+    }
+
+    flagged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(id: RuleId) -> RuleSet {
+        let mut set = RuleSet::nelson();
+        for other in RuleId::ALL {
+            set.get_mut(other).enabled = other == id;
+        }
+        set
+    }
+
+    fn flagged(values: &[f64], id: RuleId) -> Vec<usize> {
+        evaluate(values, 0.0, 1.0, &only(id)).into_keys().collect()
+    }
+
+    #[test]
+    fn test_beyond_three_sigma() {
+        assert_eq!(flagged(&[0.0, 3.5, -0.2, -4.0], RuleId::BeyondThreeSigma), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_same_side_run_length() {
+        let values = [0.5; 9];
+        assert_eq!(flagged(&values, RuleId::SameSide).len(), 9);
+        assert!(flagged(&values[..8], RuleId::SameSide).is_empty());
+
+        // WE preset uses a run of 8
+        let we = evaluate(&values[..8], 0.0, 1.0, &RuleSet::western_electric());
+        assert_eq!(we.len(), 8);
+        assert!(we.values().all(|rules| rules == &vec![RuleId::SameSide]));
+    }
+
+    #[test]
+    fn test_trend_and_alternating() {
+        let rising: Vec<f64> = (0..6).map(|i| i as f64 * 0.1).collect();
+        assert_eq!(flagged(&rising, RuleId::Trend).len(), 6);
+
+        let zigzag: Vec<f64> = (0..14).map(|i| if i % 2 == 0 { 0.2 } else { -0.2 }).collect();
+        assert_eq!(flagged(&zigzag, RuleId::Alternating).len(), 14);
+        assert!(flagged(&zigzag[..13], RuleId::Alternating).is_empty());
+    }
+
+    #[test]
+    fn test_k_of_n_rules() {
+        assert_eq!(flagged(&[2.5, 0.0, 2.1], RuleId::BeyondTwoSigma), vec![0, 1, 2]);
+        assert!(flagged(&[2.5, 0.0, -2.1], RuleId::BeyondTwoSigma).is_empty());
+        assert_eq!(flagged(&[1.5, 1.2, 0.0, 1.1, 1.3], RuleId::BeyondOneSigma).len(), 5);
+    }
+
+    #[test]
+    fn test_stratification_and_mixture() {
+        assert_eq!(flagged(&[0.1; 15], RuleId::WithinOneSigma).len(), 15);
+        let mixture = [1.5, -1.5, 1.2, -1.2, 1.8, -1.1, 1.3, -1.4];
+        assert_eq!(flagged(&mixture, RuleId::OutsideOneSigma).len(), 8);
+        // A run beyond 1σ on one side only is not a mixture
+        assert!(flagged(&[1.5; 8], RuleId::OutsideOneSigma).is_empty());
+    }
+
+    #[test]
+    fn test_editable_run_length() {
+        let mut set = only(RuleId::Trend);
+        set.get_mut(RuleId::Trend).run_length = 4;
+        let values = [0.0, 0.1, 0.2, 0.3, 0.1];
+        let indices: Vec<usize> = evaluate(&values, 0.0, 1.0, &set).into_keys().collect();
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_validate_clamps() {
+        let mut set = RuleSet::nelson();
+        set.get_mut(RuleId::BeyondOneSigma).count = 9;
+        set.get_mut(RuleId::Alternating).run_length = 1;
+        set.validate();
+        assert_eq!(set.get(RuleId::BeyondOneSigma).count, 5);
+        assert_eq!(set.get(RuleId::Alternating).run_length, 3);
+    }
+
+    #[test]
+    fn test_rule_labels() {
+        assert_eq!(RuleId::SameSide.to_string(), "Nelson 2 / WE 4: same side of mean");
+        assert_eq!(RuleId::Trend.to_string(), "Nelson 3: trending");
+    }
+}
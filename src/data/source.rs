@@ -1,7 +1,14 @@
+use super::formats::{Compression, CsvOptions, FileFormat, FileScan, LoadProgress};
+use super::expr::{self, Compiled};
+use super::pyramid::{MinMaxPyramid, PyramidBuilder};
+use crate::constants::performance::{MAX_APPENDED_CHUNKS, STREAM_CHUNK_ROWS, SUMMARY_BASE_ROWS};
 use polars::prelude::*;
 use std::path::{Path, PathBuf};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Errors that can occur during data operations
 #[derive(Debug)]
@@ -10,6 +17,16 @@ pub enum DataError {
     IoError(std::io::Error),
     UnsupportedFormat(String),
     ColumnNotFound(String),
+    /// A load was cancelled before it finished
+    Cancelled,
+    /// Whole columns are not held in memory while streaming from disk
+    Streaming,
+    /// The file is shorter than when it was read
+    Truncated,
+    /// Data sets to be combined have different columns
+    SchemaMismatch(String),
+    /// A computed column's expression does not compile or clashes with a column
+    InvalidExpression(String),
 }
 
 impl From<PolarsError> for DataError {
@@ -18,6 +35,12 @@ impl From<PolarsError> for DataError {
     }
 }
 
+impl From<super::expr::ParseError> for DataError {
+    fn from(err: super::expr::ParseError) -> Self {
+        DataError::InvalidExpression(err.to_string())
+    }
+}
+
 impl From<std::io::Error> for DataError {
     fn from(err: std::io::Error) -> Self {
         DataError::IoError(err)
@@ -31,6 +54,11 @@ impl std::fmt::Display for DataError {
             DataError::IoError(e) => write!(f, "IO error: {}", e),
             DataError::UnsupportedFormat(ext) => write!(f, "Unsupported file format: {}", ext),
             DataError::ColumnNotFound(col) => write!(f, "Column not found: {}", col),
+            DataError::Cancelled => write!(f, "Load cancelled"),
+            DataError::Streaming => write!(f, "Not available while streaming from disk"),
+            DataError::Truncated => write!(f, "File was truncated or replaced"),
+            DataError::SchemaMismatch(msg) => write!(f, "Columns do not match: {}", msg),
+            DataError::InvalidExpression(msg) => write!(f, "Invalid expression: {}", msg),
         }
     }
 }
@@ -46,28 +74,58 @@ pub struct DataSource {
     materialized: DataFrame,
     /// Original file path
     file_path: Option<PathBuf>,
+    /// Detected file format and compression (None when built from a DataFrame)
+    format: Option<(FileFormat, Compression)>,
     /// Cache for numeric column conversions
     numeric_cache: RefCell<HashMap<usize, Vec<f64>>>,
+    /// Min/max pyramids per column: built on first use in memory, up front or in the
+    /// background when streaming
+    summaries: RefCell<HashMap<usize, Arc<MinMaxPyramid>>>,
+    /// Out-of-core backing; when set, `materialized` holds only the schema
+    streaming: Option<Streaming>,
+    /// Bytes of the file read so far, for picking up rows appended to it later
+    read_offset: Option<u64>,
+    /// The last row came from a line with no newline yet; `read_appended` reads that line
+    /// again and replaces the row
+    unterminated: bool,
+    /// Computed columns, in the order added; they follow the file's columns
+    computed: Vec<Computed>,
+    /// Row filter applied to `materialized`; `df` keeps every row
+    filter: Option<RowFilter>,
+}
+
+/// A column computed from the others
+struct Computed {
+    name: String,
+    compiled: Compiled,
+    /// Running totals of its `cumsum`s over the rows added so far, for evaluating appended rows
+    totals: Vec<f64>,
+}
+
+/// A predicate rows are filtered by
+struct RowFilter {
+    predicate: Expr,
+    /// Rows before filtering
+    total: usize,
+}
+
+/// A file read from disk on demand
+struct Streaming {
+    scan: FileScan,
+    height: usize,
 }
 
 #[allow(dead_code)]
 impl DataSource {
-    /// Load data from a file (CSV or Parquet)
+    /// Load data from a file. The format (CSV/TSV, Parquet, Arrow IPC, NDJSON, optionally
+    /// gzip/zstd compressed) is detected from the file content.
     pub fn load(path: &Path) -> Result<Self, DataError> {
-        let extension = path
-            .extension()
-            .and_then(|s| s.to_str())
-            .ok_or_else(|| DataError::UnsupportedFormat("No file extension".to_string()))?;
-
-        let df = match extension.to_lowercase().as_str() {
-            "parquet" => LazyFrame::scan_parquet(path, Default::default())?,
-            "csv" => LazyCsvReader::new(path)
-                .with_has_header(true)
-                .with_infer_schema_length(Some(100))
-                .with_try_parse_dates(true)  // Enable automatic date parsing
-                .finish()?,
-            ext => return Err(DataError::UnsupportedFormat(ext.to_string())),
-        };
+        Self::load_with_options(path, &CsvOptions::default())
+    }
+
+    /// Load data from a file, parsing delimited text with the given import options
+    pub fn load_with_options(path: &Path, options: &CsvOptions) -> Result<Self, DataError> {
+        let (df, format, compression) = super::formats::scan(path, options)?;
 
         let materialized = df.clone().collect()?;
 
@@ -75,10 +133,82 @@ impl DataSource {
             df,
             materialized,
             file_path: Some(path.to_path_buf()),
+            format: Some((format, compression)),
             numeric_cache: RefCell::new(HashMap::new()),
+            summaries: RefCell::new(HashMap::new()),
+            streaming: None,
+            read_offset: None,
+            unterminated: false,
+            computed: Vec::new(),
+            filter: None,
         })
     }
     
+    /// Read a whole file eagerly, reporting progress and stopping early if `cancel` is set.
+    /// This is how the background worker loads files.
+    pub fn load_with_progress(
+        path: &Path,
+        options: &CsvOptions,
+        cancel: &AtomicBool,
+        progress: impl FnMut(LoadProgress),
+    ) -> Result<Self, DataError> {
+        let (materialized, format, compression, read, unterminated) =
+            super::formats::read_with_progress(path, options, cancel, progress)?;
+        Ok(Self {
+            df: materialized.clone().lazy(),
+            materialized,
+            file_path: Some(path.to_path_buf()),
+            format: Some((format, compression)),
+            numeric_cache: RefCell::new(HashMap::new()),
+            summaries: RefCell::new(HashMap::new()),
+            streaming: None,
+            read_offset: Some(read),
+            unterminated,
+            computed: Vec::new(),
+            filter: None,
+        })
+    }
+
+    /// Open a file for streaming from disk. Only the schema, the row count and min/max
+    /// summaries of `columns` are read into memory; plots and tables query row ranges as needed.
+    pub fn open_streaming(
+        path: &Path,
+        options: &CsvOptions,
+        columns: &[usize],
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(LoadProgress),
+    ) -> Result<Self, DataError> {
+        let scan = FileScan::open(path, options)?;
+        let schema_only = scan.lf.clone().limit(0).collect()?;
+        let height = scan.height()?;
+        let names: Vec<String> = schema_only.get_column_names().iter().map(|s| s.to_string()).collect();
+
+        let mut summaries = HashMap::new();
+        for &col_idx in columns {
+            let Some(name) = names.get(col_idx) else {
+                continue;
+            };
+            let summary = summarise_column(&scan, name, cancel, |rows| {
+                progress(LoadProgress::Summarising { rows, total: height })
+            })?;
+            summaries.insert(col_idx, Arc::new(summary));
+        }
+
+        Ok(Self {
+            df: scan.lf.clone(),
+            materialized: schema_only,
+            file_path: Some(path.to_path_buf()),
+            format: Some((scan.format, scan.compression)),
+            numeric_cache: RefCell::new(HashMap::new()),
+            summaries: RefCell::new(summaries),
+            streaming: Some(Streaming { scan, height }),
+            read_offset: None,
+            unterminated: false,
+            computed: Vec::new(),
+            filter: None,
+        })
+    }
+
     /// Create DataSource from an already-loaded DataFrame (Phase 5 worker support)
     pub fn from_dataframe(df: DataFrame, path: Option<PathBuf>) -> Result<Self, DataError> {
         let lazy = df.clone().lazy();
@@ -86,7 +216,14 @@ impl DataSource {
             df: lazy,
             materialized: df,
             file_path: path,
+            format: None,
             numeric_cache: RefCell::new(HashMap::new()),
+            summaries: RefCell::new(HashMap::new()),
+            streaming: None,
+            read_offset: None,
+            unterminated: false,
+            computed: Vec::new(),
+            filter: None,
         })
     }
 
@@ -95,8 +232,20 @@ impl DataSource {
         &self.materialized
     }
 
+    /// Lazy view of the data as shown, after any row filter, to build further queries on
+    /// (a scan of the file when streaming)
+    pub fn lazy(&self) -> LazyFrame {
+        match self.streaming {
+            Some(_) => self.df.clone(),
+            None => self.materialized.clone().lazy(),
+        }
+    }
+
     /// Get column values as a Series
     pub fn column_values(&self, col: &str) -> Result<Series, DataError> {
+        if self.streaming.is_some() {
+            return Err(DataError::Streaming);
+        }
         self.materialized
             .column(col)
             .map(|c| c.as_materialized_series().clone())
@@ -114,7 +263,10 @@ impl DataSource {
 
     /// Get the number of rows
     pub fn height(&self) -> usize {
-        self.materialized.height()
+        match &self.streaming {
+            Some(streaming) => streaming.height,
+            None => self.materialized.height(),
+        }
     }
 
     /// Get the number of columns
@@ -127,20 +279,331 @@ impl DataSource {
         self.file_path.as_deref()
     }
 
-    /// Apply filters to the data
-    pub fn apply_filters(&mut self, filters: impl Fn() -> Expr) -> Result<(), DataError> {
-        let filter_expr = filters();
-        self.materialized = self.df.clone().filter(filter_expr).collect()?;
+    /// Whether the data is streamed from disk rather than held in memory
+    pub fn is_streaming(&self) -> bool {
+        self.streaming.is_some()
+    }
+
+    /// Lazy scan of the file when streaming
+    pub fn file_scan(&self) -> Option<&FileScan> {
+        self.streaming.as_ref().map(|s| &s.scan)
+    }
+
+    /// Min/max summary of a numeric column. In memory it is built on first use; when
+    /// streaming it is None until `insert_summary` has stored one.
+    pub fn summary(&self, col_idx: usize) -> Option<Arc<MinMaxPyramid>> {
+        if let Some(summary) = self.summaries.borrow().get(&col_idx) {
+            return Some(summary.clone());
+        }
+        if self.streaming.is_some() {
+            return None;
+        }
+        let summary = Arc::new(MinMaxPyramid::build(
+            &self.get_cached_column(col_idx).ok()?,
+            crate::constants::performance::PYRAMID_BASE_ROWS,
+        ));
+        self.summaries.borrow_mut().insert(col_idx, summary.clone());
+        Some(summary)
+    }
+
+    /// Store a summary built in the background
+    pub fn insert_summary(&mut self, col_idx: usize, summary: Arc<MinMaxPyramid>) {
+        self.summaries.get_mut().insert(col_idx, summary);
+    }
+
+    /// Read a range of rows of the given columns, from disk when streaming
+    pub fn rows(&self, rows: Range<usize>, col_indices: &[usize]) -> Result<DataFrame, DataError> {
+        let names = self.column_names();
+        let selected = col_indices.iter()
+            .map(|&i| names.get(i).cloned().ok_or_else(|| DataError::ColumnNotFound(format!("Index {}", i))))
+            .collect::<Result<Vec<String>, DataError>>()?;
+        match &self.streaming {
+            Some(streaming) => streaming.scan.rows(rows, &selected),
+            None => {
+                let start = rows.start.min(self.materialized.height());
+                Ok(self.materialized.select(selected)?.slice(start as i64, rows.len()))
+            }
+        }
+    }
+
+    /// Whether rows appended to the file can be read incrementally with `read_appended`:
+    /// an uncompressed delimited file loaded into memory
+    pub fn can_follow(&self) -> bool {
+        self.read_offset.is_some()
+            && matches!(self.format, Some((FileFormat::Delimited { .. }, Compression::None)))
+    }
+
+    /// Read rows appended to the file since it was last read and add them to the data,
+    /// returning how many there were. A file that has shrunk since (truncated or replaced)
+    /// reports `DataError::Truncated` and should be reloaded.
+    pub fn read_appended(&mut self, options: &CsvOptions) -> Result<usize, DataError> {
+        let (Some(path), Some(offset), Some((FileFormat::Delimited { separator }, Compression::None))) =
+            (self.file_path.as_deref(), self.read_offset, self.format)
+        else {
+            return Ok(0);
+        };
+        let len = std::fs::metadata(path)?.len();
+        if len < offset {
+            return Err(DataError::Truncated);
+        }
+        if len == offset {
+            return Ok(0);
+        }
+
+        let schema = self.source_schema();
+        let Some((rows, offset)) = super::formats::read_appended(path, offset, separator, options, schema)? else {
+            return Ok(0);
+        };
+        if std::mem::take(&mut self.unterminated) {
+            self.drop_last_row()?;
+        }
+        self.append(&rows)?;
+        self.read_offset = Some(offset);
+        Ok(rows.height())
+    }
+
+    /// Whether the last row came from a line with no newline yet, so the next rows read
+    /// with `read_appended` start by replacing it
+    pub fn last_row_unterminated(&self) -> bool {
+        self.unterminated
+    }
+
+    /// Remove the last row, whose line is being read again now that it has ended. Cached
+    /// columns and summaries are dropped, as this happens at most once per load.
+    fn drop_last_row(&mut self) -> Result<(), DataError> {
+        let rows = self.unfiltered_height().unwrap_or(self.materialized.height());
+        let data = self.df.clone().slice(0, rows.saturating_sub(1) as IdxSize).collect()?;
+        self.set_data(data)?;
+        self.numeric_cache.get_mut().clear();
+        self.summaries.get_mut().clear();
+        Ok(())
+    }
+
+    /// Add rows to the end of the data. Computed columns are evaluated for the new rows only,
+    /// reading as many held rows before them as their row functions need; cached numeric
+    /// columns and min/max summaries are extended with the new rows rather than rebuilt.
+    pub fn append(&mut self, rows: &DataFrame) -> Result<(), DataError> {
+        if self.streaming.is_some() {
+            return Err(DataError::Streaming);
+        }
+        // Held rows keep the computed values they were first given, even once rows before them
+        // are dropped. The filter only looks back too, so the rows shown before stay shown.
+        let start = self.materialized.height();
+        let mut data = self.df.clone().collect()?;
+        let mut rows = rows.select(self.base_columns())?;
+        let height = data.height();
+        for computed in &mut self.computed {
+            let lookback = computed.compiled.lookback.min(height);
+            let mut frame = data.slice((height - lookback) as i64, lookback).select(rows.get_column_names_owned())?;
+            frame.vstack_mut(&rows)?;
+            let new_rows: BooleanChunked = (0..frame.height()).map(|i| Some(i >= lookback)).collect();
+            frame.with_column(new_rows.with_name(expr::NEW_ROWS.into()).into_series())?;
+
+            let (values, sums) = computed.compiled.over_new_rows(&computed.totals);
+            let frame = frame.lazy();
+            let values = frame.clone().select([values.alias(computed.name.as_str())]).collect()?;
+            let values = values.get_columns()[0].slice(lookback as i64, usize::MAX);
+            rows.with_column(values.cast(data.column(&computed.name)?.dtype())?)?;
+            for (total, sum) in computed.totals.iter_mut().zip(column_sums(frame, sums)?) {
+                *total += sum;
+            }
+        }
+        data.vstack_mut(&rows)?;
+        // Each append adds a chunk; merge them before they slow every later operation
+        if data.max_n_chunks() > MAX_APPENDED_CHUNKS {
+            data.as_single_chunk_par();
+        }
+        self.set_data(data)?;
+        let columns = self.materialized.get_columns();
+        let added = |col_idx: usize| series_as_f64(columns[col_idx].slice(start as i64, usize::MAX).as_materialized_series());
+        for (&col_idx, values) in self.numeric_cache.get_mut().iter_mut() {
+            values.extend(added(col_idx)?);
+        }
+        for (&col_idx, summary) in self.summaries.get_mut().iter_mut() {
+            Arc::make_mut(summary).extend(&added(col_idx)?);
+        }
+        Ok(())
+    }
+
+    /// Add a column computed from the others, evaluated over the whole data
+    pub fn add_computed(&mut self, name: &str, compiled: Compiled) -> Result<(), DataError> {
+        if self.streaming.is_some() {
+            return Err(DataError::Streaming);
+        }
+        if self.materialized.column(name).is_ok() {
+            return Err(DataError::InvalidExpression(format!("there is already a column named '{}'", name)));
+        }
+        let data = self.df.clone().with_column(compiled.expr.clone().alias(name)).collect()?;
+        let (_, sums) = compiled.over_new_rows(&[]);
+        let totals = column_sums(self.df.clone().with_column(lit(true).alias(expr::NEW_ROWS)), sums)?;
+        self.set_data(data)?;
+        self.computed.push(Computed { name: name.to_string(), compiled, totals });
+        Ok(())
+    }
+
+    /// Remove a computed column. Columns after it move down one index, so caches are cleared.
+    pub fn remove_computed(&mut self, name: &str) -> Result<(), DataError> {
+        let Some(pos) = self.computed.iter().position(|c| c.name == name) else {
+            return Err(DataError::ColumnNotFound(name.to_string()));
+        };
+        let Computed { name, .. } = self.computed.remove(pos);
+        let data = self.df.clone().drop([name.as_str()]).collect()?;
+        self.set_data(data)?;
+        self.numeric_cache.get_mut().clear();
+        self.summaries.get_mut().clear();
+        Ok(())
+    }
+
+    /// Schema of the columns read from the source, leaving out computed ones
+    pub fn source_schema(&self) -> SchemaRef {
+        let schema = self.materialized.schema();
+        Arc::new(schema.iter()
+            .filter(|(name, _)| !self.computed.iter().any(|c| c.name == name.as_str()))
+            .map(|(name, dtype)| Field::new(name.clone(), dtype.clone()))
+            .collect())
+    }
+
+    /// Names of the computed columns, in column order
+    pub fn computed_columns(&self) -> impl Iterator<Item = &str> {
+        self.computed.iter().map(|c| c.name.as_str())
+    }
+
+    /// Replace all rows of the data, filtering them again if a filter is set
+    fn set_data(&mut self, data: DataFrame) -> Result<(), DataError> {
+        self.df = data.clone().lazy();
+        self.materialized = match self.filter.as_mut() {
+            Some(filter) => {
+                filter.total = data.height();
+                data.lazy().filter(filter.predicate.clone()).collect()?
+            }
+            None => data,
+        };
+        Ok(())
+    }
+
+    /// Names of the columns that are not computed
+    fn base_columns(&self) -> Vec<PlSmallStr> {
+        self.materialized
+            .get_column_names_owned()
+            .into_iter()
+            .filter(|name| !self.computed.iter().any(|c| c.name == name.as_str()))
+            .collect()
+    }
+
+    /// Remove the first `rows` rows, as a live data set does with rows its retention no
+    /// longer keeps. Computed values of the rows kept stay as they were. Cached numeric
+    /// columns are trimmed in place; min/max summaries are rebuilt on next use.
+    pub fn drop_front(&mut self, rows: usize) {
+        if rows == 0 || self.streaming.is_some() {
+            return;
+        }
+        self.summaries.get_mut().clear();
+        if self.filter.is_some() {
+            // Which of the rows shown go depends on the filter
+            self.numeric_cache.get_mut().clear();
+            if let Ok(data) = self.df.clone().slice(rows as i64, IdxSize::MAX).collect() {
+                let _ = self.set_data(data);
+            }
+            return;
+        }
+        let rows = rows.min(self.materialized.height());
+        for values in self.numeric_cache.get_mut().values_mut() {
+            values.drain(..rows.min(values.len()));
+        }
+        self.materialized = self.materialized.slice(rows as i64, usize::MAX);
+        self.df = self.materialized.clone().lazy();
+    }
+
+    /// Detected file format and compression (None when built from a DataFrame)
+    pub fn format(&self) -> Option<(FileFormat, Compression)> {
+        self.format
+    }
+
+    /// Human-readable format of the loaded file, e.g. "TSV (gzip)"
+    pub fn format_description(&self) -> Option<String> {
+        self.format.map(|(format, compression)| match compression {
+            Compression::None => format.to_string(),
+            compression => format!("{} ({})", format, compression),
+        })
+    }
+
+    /// Warnings about values that could not be read as numbers, one per affected column
+    pub fn validation_warnings(&self) -> Vec<String> {
+        let headers = self.column_names();
+        let height = self.height();
+
+        // Validate parsed data column by column to avoid materializing the dataset as row-major
+        let mut warnings = Vec::new();
+        for (col_idx, header) in headers.iter().enumerate() {
+            let nan_count = self.column_as_f64(col_idx)
+                .map(|col| col.iter().filter(|v| v.is_nan()).count())
+                .unwrap_or(0);
+            if nan_count == 0 {
+                continue;
+            }
+            let pct = (nan_count as f64 / height as f64) * 100.0;
+
+            // Get column type to be smarter about warnings
+            let is_text_col = self.get_column_series(col_idx)
+                .map(|series| {
+                    let dtype = series.dtype();
+                    matches!(dtype, DataType::String | DataType::Boolean) || dtype.is_categorical()
+                })
+                .unwrap_or(false);
+
+            // Logic:
+            // 1. If it's a text/categorical column and > 20% are NaNs (when parsed as float),
+            //    it's likely just a text column. Don't warn.
+            // 2. If it's a numeric column and has > 5% NaNs, warn about missing data.
+            // 3. If it's text but low NaN count (< 20%), it might be a dirty numeric column. Warn.
+            if is_text_col {
+                if pct <= 20.0 {
+                    warnings.push(format!(
+                        "Column '{}' (Text): {}/{} values ({:.1}%) could not be parsed as numbers",
+                        header, nan_count, height, pct
+                    ));
+                }
+            } else if pct > 5.0 {
+                warnings.push(format!(
+                    "Column '{}': {}/{} values ({:.1}%) are missing or invalid",
+                    header, nan_count, height, pct
+                ));
+            }
+        }
+        warnings
+    }
+
+    /// Show only the rows matching `predicate`, or all rows with None. A predicate that
+    /// fails to evaluate leaves the rows shown as they were.
+    pub fn apply_filters(&mut self, predicate: Option<Expr>) -> Result<(), DataError> {
+        if self.streaming.is_some() {
+            return Err(DataError::Streaming);
+        }
+        let data = self.df.clone().collect()?;
+        let total = data.height();
+        self.materialized = match &predicate {
+            Some(predicate) => data.lazy().filter(predicate.clone()).collect()?,
+            None => data,
+        };
+        self.filter = predicate.map(|predicate| RowFilter { predicate, total });
         // Clear cache when filters change
         self.numeric_cache.borrow_mut().clear();
+        self.summaries.borrow_mut().clear();
         Ok(())
     }
 
+    /// Number of rows before filtering, when a filter is applied
+    pub fn unfiltered_height(&self) -> Option<usize> {
+        self.filter.as_ref().map(|filter| filter.total)
+    }
+
     /// Re-materialize the DataFrame (useful after lazy operations)
     pub fn refresh(&mut self) -> Result<(), DataError> {
-        self.materialized = self.df.clone().collect()?;
+        let data = self.df.clone().collect()?;
+        self.set_data(data)?;
         // Clear cache when refreshed
         self.numeric_cache.borrow_mut().clear();
+        self.summaries.borrow_mut().clear();
         Ok(())
     }
 
@@ -168,54 +631,7 @@ impl DataSource {
             return Err(DataError::ColumnNotFound(format!("Index {}", col_idx)));
         }
 
-        let series = self.column_values(&col_names[col_idx])?;
-
-        // Handle datetime/date types by converting to Unix timestamps
-        match series.dtype() {
-            DataType::Datetime(_, _) => {
-                // Convert datetime to Unix timestamp (seconds)
-                let timestamps = series.datetime()
-                    .map_err(|e| DataError::PolarsError(e))?
-                    .into_iter()
-                    .map(|opt| opt.map(|ts| ts as f64 / 1_000_000.0).unwrap_or(f64::NAN))
-                    .collect();
-                return Ok(timestamps);
-            }
-            DataType::Date => {
-                // Convert date to Unix timestamp (days since epoch * seconds per day)
-                let timestamps = series.date()
-                    .map_err(|e| DataError::PolarsError(e))?
-                    .into_iter()
-                    .map(|opt| opt.map(|days| days as f64 * 86400.0).unwrap_or(f64::NAN))
-                    .collect();
-                return Ok(timestamps);
-            }
-            _ => {}
-        }
-
-        // Try to cast to f64, if that fails, extract as best we can
-        match series.cast(&DataType::Float64) {
-            Ok(s) => Ok(s.f64()
-                .map_err(|e| DataError::PolarsError(e))?
-                .into_iter()
-                .map(|opt| opt.unwrap_or(f64::NAN))
-                .collect()),
-            Err(_) => {
-                // For string columns, try to parse as f64
-                if let Ok(str_series) = series.str() {
-                    Ok(str_series
-                        .into_iter()
-                        .map(|opt| {
-                            opt.and_then(|s| s.parse::<f64>().ok())
-                                .unwrap_or(f64::NAN)
-                        })
-                        .collect())
-                } else {
-                    // Last resort: return NaN for all values
-                    Ok(vec![f64::NAN; series.len()])
-                }
-            }
-        }
+        series_as_f64(&self.column_values(&col_names[col_idx])?)
     }
 
     /// Get a column's string values as Vec<String>
@@ -322,6 +738,10 @@ impl DataSource {
 
     /// Get a specific cell value as String
     pub fn get_string(&self, row: usize, col: usize) -> Option<String> {
+        if self.streaming.is_some() {
+            let cell = self.rows(row..row + 1, &[col]).ok()?;
+            return cell_strings(cell.get_columns().first()?).into_iter().next();
+        }
         self.column_as_string(col).ok()?.get(row).cloned()
     }
 
@@ -346,12 +766,101 @@ impl DataSource {
         if col_idx >= col_names.len() {
             return false;
         }
-        if let Ok(series) = self.column_values(&col_names[col_idx]) {
-            matches!(series.dtype(), DataType::Datetime(_, _) | DataType::Date)
-        } else {
-            false
+        // The schema is kept even when streaming, so check it rather than the values
+        self.materialized
+            .column(&col_names[col_idx])
+            .is_ok_and(|c| matches!(c.dtype(), DataType::Datetime(_, _) | DataType::Date))
+    }
+}
+
+/// Evaluate the aggregates `sums` over `frame`, one value each
+fn column_sums(frame: LazyFrame, sums: Vec<Expr>) -> Result<Vec<f64>, DataError> {
+    if sums.is_empty() {
+        return Ok(Vec::new());
+    }
+    let totals = frame.select(sums).collect()?;
+    totals.get_columns().iter().map(|c| Ok(c.f64()?.get(0).unwrap_or(0.0))).collect()
+}
+
+/// Convert a column to f64: non-numeric values become NaN and Datetime/Date columns become
+/// Unix timestamps in seconds
+pub fn series_as_f64(series: &Series) -> Result<Vec<f64>, DataError> {
+    // Handle datetime/date types by converting to Unix timestamps
+    match series.dtype() {
+        DataType::Datetime(_, _) => {
+            // Convert datetime to Unix timestamp (seconds)
+            let timestamps = series.datetime()
+                .map_err(|e| DataError::PolarsError(e))?
+                .into_iter()
+                .map(|opt| opt.map(|ts| ts as f64 / 1_000_000.0).unwrap_or(f64::NAN))
+                .collect();
+            return Ok(timestamps);
         }
+        DataType::Date => {
+            // Convert date to Unix timestamp (days since epoch * seconds per day)
+            let timestamps = series.date()
+                .map_err(|e| DataError::PolarsError(e))?
+                .into_iter()
+                .map(|opt| opt.map(|days| days as f64 * 86400.0).unwrap_or(f64::NAN))
+                .collect();
+            return Ok(timestamps);
+        }
+        _ => {}
     }
+
+    // Try to cast to f64, if that fails, extract as best we can
+    match series.cast(&DataType::Float64) {
+        Ok(s) => Ok(s.f64()
+            .map_err(|e| DataError::PolarsError(e))?
+            .into_iter()
+            .map(|opt| opt.unwrap_or(f64::NAN))
+            .collect()),
+        Err(_) => {
+            // For string columns, try to parse as f64
+            if let Ok(str_series) = series.str() {
+                Ok(str_series
+                    .into_iter()
+                    .map(|opt| {
+                        opt.and_then(|s| s.parse::<f64>().ok())
+                            .unwrap_or(f64::NAN)
+                    })
+                    .collect())
+            } else {
+                // Last resort: return NaN for all values
+                Ok(vec![f64::NAN; series.len()])
+            }
+        }
+    }
+}
+
+/// Display strings for a column's values (missing values become empty strings)
+pub fn cell_strings(column: &Column) -> Vec<String> {
+    column.cast(&DataType::String)
+        .ok()
+        .and_then(|c| c.str().ok().map(|ca| ca.into_iter().map(|v| v.unwrap_or("").to_string()).collect()))
+        .unwrap_or_else(|| vec![String::new(); column.len()])
+}
+
+/// Build a min/max summary of one column by streaming it from disk in chunks.
+/// `progress` receives the number of rows summarised so far.
+pub fn summarise_column(
+    scan: &FileScan,
+    column: &str,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(usize),
+) -> Result<MinMaxPyramid, DataError> {
+    let mut builder = PyramidBuilder::new(SUMMARY_BASE_ROWS);
+    let mut rows = 0;
+    scan.column_chunks(column, STREAM_CHUNK_ROWS, |chunk| {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(DataError::Cancelled);
+        }
+        builder.extend(&series_as_f64(chunk)?);
+        rows += chunk.len();
+        progress(rows);
+        Ok(true)
+    })?;
+    Ok(builder.finish())
 }
 
 #[cfg(test)]
@@ -516,4 +1025,165 @@ mod tests {
             total
         );
     }
+
+    #[test]
+    fn test_open_streaming() {
+        let mut file = Builder::new().suffix(".csv").tempfile().unwrap();
+        writeln!(file, "t,value,label").unwrap();
+        for i in 0..5000 {
+            writeln!(file, "{},{},r{}", i, (i % 97) as f64 - 40.0, i).unwrap();
+        }
+        file.flush().unwrap();
+
+        let cancel = AtomicBool::new(false);
+        let ds = DataSource::open_streaming(file.path(), &CsvOptions::default(), &[0, 1], &cancel, |_| {}).unwrap();
+        assert!(ds.is_streaming());
+        assert_eq!(ds.height(), 5000);
+        assert_eq!(ds.column_names(), vec!["t", "value", "label"]);
+        assert!(matches!(ds.column_values("value"), Err(DataError::Streaming)));
+
+        let t = ds.summary(0).unwrap();
+        assert!(t.is_sorted());
+        assert_eq!(t.total().count, 5000);
+        let value = ds.summary(1).unwrap().total();
+        assert_eq!((value.min, value.max, value.count), (-40.0, 56.0, 5000));
+        assert!(ds.summary(2).is_none());
+
+        let window = ds.rows(4990..5010, &[1, 2]).unwrap();
+        assert_eq!(window.height(), 10);
+        assert_eq!(cell_strings(&window.get_columns()[1])[0], "r4990");
+        assert_eq!(ds.get_string(123, 2).as_deref(), Some("r123"));
+    }
+
+    #[test]
+    fn test_read_appended_extends_caches() {
+        let mut file = Builder::new().suffix(".csv").tempfile().unwrap();
+        writeln!(file, "t,value").unwrap();
+        for i in 0..100 {
+            writeln!(file, "{},{}", i, i * 2).unwrap();
+        }
+        file.flush().unwrap();
+
+        let options = CsvOptions::default();
+        let mut ds = DataSource::load_with_progress(file.path(), &options, &AtomicBool::new(false), |_| {}).unwrap();
+        assert!(ds.can_follow());
+        assert_eq!(ds.get_cached_column(1).unwrap().len(), 100);
+        assert_eq!(ds.summary(0).unwrap().total().count, 100);
+        assert_eq!(ds.read_appended(&options).unwrap(), 0);
+
+        writeln!(file, "100,200\n101,202").unwrap();
+        file.flush().unwrap();
+        assert_eq!(ds.read_appended(&options).unwrap(), 2);
+        assert_eq!(ds.height(), 102);
+        assert_eq!(ds.get_cached_column(1).unwrap()[100..], [200.0, 202.0]);
+        assert_eq!(ds.summary(0).unwrap().total().count, 102);
+        let rebuilt = MinMaxPyramid::build(&ds.get_cached_column(0).unwrap(), crate::constants::performance::PYRAMID_BASE_ROWS);
+        assert_eq!(*ds.summary(0).unwrap(), rebuilt);
+        assert_eq!(ds.get_string(101, 0).as_deref(), Some("101"));
+
+        file.as_file().set_len(10).unwrap();
+        assert!(matches!(ds.read_appended(&options), Err(DataError::Truncated)));
+        assert!(!DataSource::load(file.path()).unwrap().can_follow());
+    }
+
+    #[test]
+    fn test_read_appended_rereads_unterminated_row() {
+        // The last line has no newline: it is loaded, then replaced once it ends
+        let mut file = Builder::new().suffix(".csv").tempfile().unwrap();
+        write!(file, "t,value\n0,1.5\n1,2").unwrap();
+        file.flush().unwrap();
+
+        let options = CsvOptions::default();
+        let mut ds = DataSource::load_with_progress(file.path(), &options, &AtomicBool::new(false), |_| {}).unwrap();
+        assert_eq!(ds.get_cached_column(1).unwrap()[..], [1.5, 2.0]);
+        assert!(ds.last_row_unterminated());
+        assert_eq!(ds.read_appended(&options).unwrap(), 0);
+
+        write!(file, ".5\n2,3.5\n").unwrap();
+        file.flush().unwrap();
+        assert_eq!(ds.read_appended(&options).unwrap(), 2);
+        assert!(!ds.last_row_unterminated());
+        assert_eq!(ds.get_cached_column(1).unwrap()[..], [1.5, 2.5, 3.5]);
+        assert_eq!(ds.summary(1).unwrap().total().count, 3);
+    }
+
+    #[test]
+    fn test_computed_columns() {
+        let df = df!("t" => [0i64, 1, 2], "value" => [1.0, 3.0, 6.0]).unwrap();
+        let mut ds = DataSource::from_dataframe(df, None).unwrap();
+        let columns = ds.column_names();
+        ds.add_computed("step", expr::compile("diff(value)", &columns).unwrap()).unwrap();
+        let columns = ds.column_names();
+        ds.add_computed("scaled", expr::compile("step * 10", &columns).unwrap()).unwrap();
+        assert!(matches!(ds.add_computed("value", expr::compile("1", &columns).unwrap()), Err(DataError::InvalidExpression(_))));
+        assert_eq!(ds.column_names(), vec!["t", "value", "step", "scaled"]);
+        assert_eq!(ds.get_cached_column(3).unwrap()[1..], [20.0, 30.0]);
+
+        // Appended rows carry only the file's columns; computed ones follow them
+        ds.append(&df!("t" => [3i64], "value" => [10.0]).unwrap()).unwrap();
+        assert_eq!(ds.get_cached_column(3).unwrap()[1..], [20.0, 30.0, 40.0]);
+        assert_eq!(ds.get_f64(3, 2), Some(4.0));
+
+        ds.remove_computed("step").unwrap();
+        assert_eq!(ds.column_names(), vec!["t", "value", "scaled"]);
+        assert_eq!(ds.computed_columns().collect::<Vec<_>>(), vec!["scaled"]);
+    }
+
+    #[test]
+    fn test_append_keeps_chunks_bounded() {
+        let mut ds = DataSource::from_dataframe(df!("t" => [0i64], "value" => [0.0]).unwrap(), None).unwrap();
+        for i in 1..200i64 {
+            ds.append(&df!("t" => [i], "value" => [i as f64]).unwrap()).unwrap();
+        }
+        assert_eq!(ds.height(), 200);
+        assert!(ds.materialized.max_n_chunks() <= MAX_APPENDED_CHUNKS);
+        assert!(ds.df.clone().collect().unwrap().max_n_chunks() <= MAX_APPENDED_CHUNKS);
+        assert_eq!(ds.get_f64(199, 1), Some(199.0));
+    }
+
+    #[test]
+    fn test_computed_columns_after_drop_front() {
+        let df = df!("value" => [1.0, 2.0, 3.0]).unwrap();
+        let mut ds = DataSource::from_dataframe(df, None).unwrap();
+        let columns = ds.column_names();
+        ds.add_computed("total", expr::compile("cumsum(value)", &columns).unwrap()).unwrap();
+        ds.add_computed("window", expr::compile("rolling_sum(value, 2) + lag(value)", &columns).unwrap()).unwrap();
+        ds.get_cached_column(1).unwrap();
+        ds.get_cached_column(2).unwrap();
+
+        ds.append(&df!("value" => [4.0]).unwrap()).unwrap();
+        ds.drop_front(2);
+        ds.append(&df!("value" => [5.0]).unwrap()).unwrap();
+
+        // Values computed before the drop are kept, and later ones carry on from them
+        assert_eq!(ds.get_cached_column(1).unwrap()[..], [6.0, 10.0, 15.0]);
+        assert_eq!(ds.column_as_f64(1).unwrap(), vec![6.0, 10.0, 15.0]);
+        assert_eq!(ds.get_f64(2, 1), Some(15.0));
+        assert_eq!(ds.get_cached_column(2).unwrap()[..], [7.0, 10.0, 13.0]);
+        assert_eq!(ds.column_as_f64(2).unwrap(), vec![7.0, 10.0, 13.0]);
+    }
+
+    #[test]
+    fn test_row_filter() {
+        let df = df!("t" => [0i64, 1, 2, 3], "value" => [5.0, 50.0, 7.0, 70.0]).unwrap();
+        let mut ds = DataSource::from_dataframe(df, None).unwrap();
+        ds.apply_filters(Some(col("value").gt(lit(10.0)))).unwrap();
+        assert_eq!(ds.height(), 2);
+        assert_eq!(ds.unfiltered_height(), Some(4));
+        assert_eq!(ds.get_cached_column(0).unwrap()[..], [1.0, 3.0]);
+
+        // New rows are filtered as they arrive; dropped rows count before filtering
+        ds.append(&df!("t" => [4i64, 5], "value" => [1.0, 100.0]).unwrap()).unwrap();
+        assert_eq!(ds.get_cached_column(0).unwrap()[..], [1.0, 3.0, 5.0]);
+        assert_eq!(ds.unfiltered_height(), Some(6));
+        ds.drop_front(2);
+        assert_eq!(ds.get_cached_column(0).unwrap()[..], [3.0, 5.0]);
+
+        // A predicate that does not evaluate leaves the rows shown alone
+        assert!(ds.apply_filters(Some(col("missing").gt(lit(1)))).is_err());
+        assert_eq!(ds.height(), 2);
+        ds.apply_filters(None).unwrap();
+        assert_eq!(ds.height(), 4);
+        assert_eq!(ds.unfiltered_height(), None);
+    }
 }
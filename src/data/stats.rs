@@ -132,6 +132,751 @@ pub fn detect_outliers_vec(values: &[f64], threshold: f64) -> Vec<usize> {
     detect_outliers(&series, threshold)
 }
 
+/// Natural log of the gamma function (Lanczos approximation, g=7, n=9)
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula for the left half-plane
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFS[0], |acc, (i, &c)| acc + c / (x + i as f64 + 1.0));
+
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// c4 bias-correction constant for the sample standard deviation of n observations
+pub fn c4(n: usize) -> f64 {
+    if n < 2 {
+        return 1.0;
+    }
+    let n = n as f64;
+    (2.0 / (n - 1.0)).sqrt() * (ln_gamma(n / 2.0) - ln_gamma((n - 1.0) / 2.0)).exp()
+}
+
+/// Complementary error function (Chebyshev fit, fractional error < 1.2e-7)
+pub fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+        + t * (0.374_091_96
+        + t * (0.096_784_18
+        + t * (-0.186_288_06
+        + t * (0.278_868_07
+        + t * (-1.135_203_98
+        + t * (1.488_515_87
+        + t * (-0.822_152_23
+        + t * 0.170_872_77))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// Standard normal probability density
+pub fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Standard normal cumulative distribution
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / std::f64::consts::SQRT_2)
+}
+
+/// Standard normal quantile (Acklam's rational approximation, relative error < 1.2e-9).
+/// No Halley step: `normal_cdf` is only good to ~1e-7, so refining against it loses accuracy.
+pub fn normal_quantile(p: f64) -> f64 {
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1, 2.209_460_984_245_205e2, -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2, -3.066_479_806_614_716e1, 2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1, 1.615_858_368_580_409e2, -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1, -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3, -3.223_964_580_411_365e-1, -2.400_758_277_161_838,
+        -2.549_732_539_343_734, 4.374_664_141_464_968, 2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3, 3.224_671_290_700_398e-1, 2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.024_25;
+
+    let tail = |q: f64| {
+        ((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]
+    };
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        tail(q) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p > 1.0 - P_LOW {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -tail(q) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Regularized lower incomplete gamma function P(a, x)
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 || a <= 0.0 {
+        return 0.0;
+    }
+    let ln_prefix = a * x.ln() - x - ln_gamma(a);
+
+    if x < a + 1.0 {
+        // Series expansion
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut n = a;
+        for _ in 0..500 {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (sum.ln() + ln_prefix).exp().min(1.0)
+    } else {
+        // Continued fraction for Q(a, x) (modified Lentz)
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        (1.0 - (ln_prefix.exp() * h)).max(0.0)
+    }
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+pub fn beta_inc(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+
+    // Continued fraction converges fastest on this side of the mean
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz)
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let tiny = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < tiny {
+        d = tiny;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..500 {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        for numerator in [
+            m * (b - m) * x / ((a + m2 - 1.0) * (a + m2)),
+            -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = 1.0 + numerator / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    h
+}
+
+/// F distribution cumulative probability with (d1, d2) degrees of freedom
+pub fn f_cdf(x: f64, d1: f64, d2: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    beta_inc(d1 / 2.0, d2 / 2.0, d1 * x / (d1 * x + d2))
+}
+
+/// Chi-square cumulative distribution with `df` degrees of freedom
+pub fn chi_square_cdf(x: f64, df: f64) -> f64 {
+    gamma_p(df / 2.0, x / 2.0)
+}
+
+/// Chi-square quantile with `df` degrees of freedom (bisection on the CDF)
+pub fn chi_square_quantile(p: f64, df: f64) -> f64 {
+    if p <= 0.0 || df <= 0.0 {
+        return 0.0;
+    }
+    invert_cdf(p, df.max(1.0), |x| chi_square_cdf(x, df))
+}
+
+/// F distribution quantile with (d1, d2) degrees of freedom (bisection on the CDF)
+pub fn f_quantile(p: f64, d1: f64, d2: f64) -> f64 {
+    if p <= 0.0 || d1 <= 0.0 || d2 <= 0.0 {
+        return 0.0;
+    }
+    invert_cdf(p, 1.0, |x| f_cdf(x, d1, d2))
+}
+
+/// Invert a CDF on [0, inf) by bracketing from `hi` and bisecting
+fn invert_cdf(p: f64, hi: f64, cdf: impl Fn(f64) -> f64) -> f64 {
+    let (mut lo, mut hi) = (0.0, hi);
+    while cdf(hi) < p {
+        hi *= 2.0;
+        if hi > 1e9 {
+            return f64::INFINITY;
+        }
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if cdf(mid) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+        if hi - lo < 1e-12 * hi.max(1.0) {
+            break;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// Candidate distribution families for non-normal capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum DistributionKind {
+    Normal,
+    Lognormal,
+    Weibull,
+    BoxCox,
+    Johnson,
+}
+
+impl DistributionKind {
+    /// All families in fitting order
+    pub const ALL: [DistributionKind; 5] = [
+        DistributionKind::Normal,
+        DistributionKind::Lognormal,
+        DistributionKind::Weibull,
+        DistributionKind::BoxCox,
+        DistributionKind::Johnson,
+    ];
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            DistributionKind::Normal => "Normal",
+            DistributionKind::Lognormal => "Lognormal",
+            DistributionKind::Weibull => "Weibull",
+            DistributionKind::BoxCox => "Box-Cox",
+            DistributionKind::Johnson => "Johnson",
+        }
+    }
+
+    /// Fit this family to the values
+    pub fn fit(self, values: &[f64]) -> Option<Distribution> {
+        match self {
+            DistributionKind::Normal => fit_normal(values),
+            DistributionKind::Lognormal => fit_lognormal(values),
+            DistributionKind::Weibull => fit_weibull(values),
+            DistributionKind::BoxCox => fit_box_cox(values),
+            DistributionKind::Johnson => fit_johnson(values),
+        }
+    }
+}
+
+/// Johnson system family
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JohnsonFamily {
+    /// Unbounded: z = γ + δ·asinh((x-ξ)/λ)
+    SU,
+    /// Bounded: z = γ + δ·ln((x-ξ)/(ξ+λ-x))
+    SB,
+    /// Lognormal: z = γ + δ·ln(x-ξ)
+    SL,
+}
+
+/// Fitted continuous distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    Normal { mean: f64, std_dev: f64 },
+    /// ln(x) ~ N(mu, sigma)
+    Lognormal { mu: f64, sigma: f64 },
+    Weibull { shape: f64, scale: f64 },
+    /// (x^λ - 1)/λ ~ N(mean, std_dev)
+    BoxCox { lambda: f64, mean: f64, std_dev: f64 },
+    Johnson { family: JohnsonFamily, gamma: f64, delta: f64, xi: f64, lambda: f64 },
+}
+
+impl Distribution {
+    /// Family this distribution belongs to
+    pub fn kind(&self) -> DistributionKind {
+        match self {
+            Distribution::Normal { .. } => DistributionKind::Normal,
+            Distribution::Lognormal { .. } => DistributionKind::Lognormal,
+            Distribution::Weibull { .. } => DistributionKind::Weibull,
+            Distribution::BoxCox { .. } => DistributionKind::BoxCox,
+            Distribution::Johnson { .. } => DistributionKind::Johnson,
+        }
+    }
+
+    /// Cumulative distribution function
+    pub fn cdf(&self, x: f64) -> f64 {
+        match *self {
+            Distribution::Normal { mean, std_dev } => normal_cdf((x - mean) / std_dev),
+            Distribution::Lognormal { mu, sigma } => {
+                if x <= 0.0 { 0.0 } else { normal_cdf((x.ln() - mu) / sigma) }
+            }
+            Distribution::Weibull { shape, scale } => {
+                if x <= 0.0 { 0.0 } else { 1.0 - (-(x / scale).powf(shape)).exp() }
+            }
+            Distribution::BoxCox { lambda, mean, std_dev } => {
+                if x <= 0.0 { 0.0 } else { normal_cdf((box_cox(x, lambda) - mean) / std_dev) }
+            }
+            Distribution::Johnson { family, gamma, delta, xi, lambda } => {
+                let u = match family {
+                    JohnsonFamily::SU => ((x - xi) / lambda).asinh(),
+                    JohnsonFamily::SB => {
+                        if x <= xi {
+                            return 0.0;
+                        }
+                        if x >= xi + lambda {
+                            return 1.0;
+                        }
+                        ((x - xi) / (xi + lambda - x)).ln()
+                    }
+                    JohnsonFamily::SL => {
+                        if x <= xi {
+                            return 0.0;
+                        }
+                        (x - xi).ln()
+                    }
+                };
+                normal_cdf(gamma + delta * u)
+            }
+        }
+    }
+
+    /// Quantile (inverse CDF)
+    pub fn quantile(&self, p: f64) -> f64 {
+        let z = normal_quantile(p);
+        match *self {
+            Distribution::Normal { mean, std_dev } => mean + std_dev * z,
+            Distribution::Lognormal { mu, sigma } => (mu + sigma * z).exp(),
+            Distribution::Weibull { shape, scale } => scale * (-(1.0 - p).ln()).powf(1.0 / shape),
+            Distribution::BoxCox { lambda, mean, std_dev } => {
+                let y = mean + std_dev * z;
+                if lambda.abs() < 1e-9 {
+                    y.exp()
+                } else {
+                    let base = lambda * y + 1.0;
+                    if base > 0.0 {
+                        base.powf(1.0 / lambda)
+                    } else if lambda > 0.0 {
+                        0.0
+                    } else {
+                        f64::INFINITY
+                    }
+                }
+            }
+            Distribution::Johnson { family, gamma, delta, xi, lambda } => {
+                let w = (z - gamma) / delta;
+                match family {
+                    JohnsonFamily::SU => xi + lambda * w.sinh(),
+                    JohnsonFamily::SB => xi + lambda / (1.0 + (-w).exp()),
+                    JohnsonFamily::SL => xi + w.exp(),
+                }
+            }
+        }
+    }
+
+    /// Probability density (central difference of the CDF)
+    pub fn pdf(&self, x: f64) -> f64 {
+        let h = 1e-6 * x.abs().max(1.0);
+        ((self.cdf(x + h) - self.cdf(x - h)) / (2.0 * h)).max(0.0)
+    }
+}
+
+/// Box-Cox transform of a positive value
+pub fn box_cox(x: f64, lambda: f64) -> f64 {
+    if lambda.abs() < 1e-9 {
+        x.ln()
+    } else {
+        (x.powf(lambda) - 1.0) / lambda
+    }
+}
+
+/// Sample mean and standard deviation (n-1 denominator)
+fn mean_std(values: &[f64]) -> Option<(f64, f64)> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    (std_dev > 0.0 && std_dev.is_finite()).then_some((mean, std_dev))
+}
+
+/// Maximise a unimodal function on [lo, hi] by golden-section search
+fn golden_max(f: impl Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let mut a = hi - ratio * (hi - lo);
+    let mut b = lo + ratio * (hi - lo);
+    let (mut fa, mut fb) = (f(a), f(b));
+    for _ in 0..100 {
+        if fa < fb {
+            lo = a;
+            a = b;
+            fa = fb;
+            b = lo + ratio * (hi - lo);
+            fb = f(b);
+        } else {
+            hi = b;
+            b = a;
+            fb = fa;
+            a = hi - ratio * (hi - lo);
+            fa = f(a);
+        }
+        if hi - lo < 1e-8 {
+            break;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+/// Fit a normal distribution
+pub fn fit_normal(values: &[f64]) -> Option<Distribution> {
+    let (mean, std_dev) = mean_std(values)?;
+    Some(Distribution::Normal { mean, std_dev })
+}
+
+/// Fit a lognormal distribution (requires positive values)
+pub fn fit_lognormal(values: &[f64]) -> Option<Distribution> {
+    if values.iter().any(|&v| v <= 0.0) {
+        return None;
+    }
+    let logs: Vec<f64> = values.iter().map(|v| v.ln()).collect();
+    let (mu, sigma) = mean_std(&logs)?;
+    Some(Distribution::Lognormal { mu, sigma })
+}
+
+/// Fit a two-parameter Weibull distribution by maximum likelihood (requires positive values)
+pub fn fit_weibull(values: &[f64]) -> Option<Distribution> {
+    if values.len() < 2 || values.iter().any(|&v| v <= 0.0) {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean_log = values.iter().map(|v| v.ln()).sum::<f64>() / n;
+
+    // Shape solves sum(x^k ln x)/sum(x^k) - 1/k - mean(ln x) = 0, which is increasing in k.
+    // Values are scaled by their maximum to keep x^k in range.
+    let max = values.iter().copied().fold(f64::MIN, f64::max);
+    let score = |k: f64| {
+        let (mut sum_pow, mut sum_pow_log) = (0.0, 0.0);
+        for &v in values {
+            let p = (v / max).powf(k);
+            sum_pow += p;
+            sum_pow_log += p * v.ln();
+        }
+        sum_pow_log / sum_pow - 1.0 / k - mean_log
+    };
+
+    let (mut lo, mut hi) = (1e-3, 1e3);
+    if score(lo) > 0.0 || score(hi) < 0.0 {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = (lo * hi).sqrt();
+        if score(mid) < 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+        if hi / lo < 1.0 + 1e-12 {
+            break;
+        }
+    }
+    let shape = (lo * hi).sqrt();
+    let scale = max * (values.iter().map(|v| (v / max).powf(shape)).sum::<f64>() / n).powf(1.0 / shape);
+    Some(Distribution::Weibull { shape, scale })
+}
+
+/// Fit a Box-Cox transformed normal, choosing λ in [-5, 5] by profile likelihood (requires positive values)
+pub fn fit_box_cox(values: &[f64]) -> Option<Distribution> {
+    if values.len() < 3 || values.iter().any(|&v| v <= 0.0) {
+        return None;
+    }
+    let n = values.len() as f64;
+    let sum_log: f64 = values.iter().map(|v| v.ln()).sum();
+    let log_likelihood = |lambda: f64| {
+        let transformed: Vec<f64> = values.iter().map(|&v| box_cox(v, lambda)).collect();
+        let mean = transformed.iter().sum::<f64>() / n;
+        let variance = transformed.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / n;
+        if variance <= 0.0 || !variance.is_finite() {
+            return f64::NEG_INFINITY;
+        }
+        -0.5 * n * variance.ln() + (lambda - 1.0) * sum_log
+    };
+
+    let lambda = golden_max(log_likelihood, -5.0, 5.0);
+    let transformed: Vec<f64> = values.iter().map(|&v| box_cox(v, lambda)).collect();
+    let (mean, std_dev) = mean_std(&transformed)?;
+    Some(Distribution::BoxCox { lambda, mean, std_dev })
+}
+
+/// Fit a Johnson distribution with Slifker & Shapiro's percentile method, choosing the
+/// z spacing (0.25..=1.25) with the lowest Anderson-Darling statistic
+pub fn fit_johnson(values: &[f64]) -> Option<Distribution> {
+    if values.len() < 10 {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    (0..=10)
+        .filter_map(|i| johnson_percentile_fit(&sorted, 0.25 + 0.1 * i as f64))
+        .map(|dist| (anderson_darling_statistic(&sorted, |x| dist.cdf(x)), dist))
+        .filter(|(ad, _)| ad.is_finite())
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(_, dist)| dist)
+}
+
+/// Johnson parameters from the sample quantiles at ±z and ±3z
+fn johnson_percentile_fit(sorted: &[f64], z: f64) -> Option<Distribution> {
+    let q = |z: f64| sample_quantile(sorted, normal_cdf(z));
+    let (x3, x1, xm1, xm3) = (q(3.0 * z), q(z), q(-z), q(-3.0 * z));
+    let (m, n, p) = (x3 - x1, xm1 - xm3, x1 - xm1);
+    if m <= 0.0 || n <= 0.0 || p <= 0.0 {
+        return None;
+    }
+
+    let (mp, np) = (m / p, n / p);
+    let ratio = mp * np;
+    let centre = (x1 + xm1) / 2.0;
+
+    let dist = if ratio > 1.001 {
+        let delta = 2.0 * z / (0.5 * (mp + np)).acosh();
+        let gamma = delta * ((np - mp) / (2.0 * (ratio - 1.0).sqrt())).asinh();
+        let lambda = 2.0 * p * (ratio - 1.0).sqrt() / ((mp + np - 2.0) * (mp + np + 2.0).sqrt());
+        let xi = centre + p * (np - mp) / (2.0 * (mp + np - 2.0));
+        Distribution::Johnson { family: JohnsonFamily::SU, gamma, delta, xi, lambda }
+    } else if ratio < 0.999 {
+        let (pm, pn) = (p / m, p / n);
+        let product = (1.0 + pm) * (1.0 + pn);
+        let delta = z / (0.5 * product.sqrt()).acosh();
+        let gamma = delta * ((pn - pm) * (product - 4.0).sqrt() / (2.0 * (pm * pn - 1.0))).asinh();
+        let lambda = p * ((product - 2.0).powi(2) - 4.0).sqrt() / (pm * pn - 1.0);
+        let xi = centre - lambda / 2.0 + p * (pn - pm) / (2.0 * (pm * pn - 1.0));
+        Distribution::Johnson { family: JohnsonFamily::SB, gamma, delta, xi, lambda }
+    } else {
+        if mp <= 1.0 {
+            return None;
+        }
+        let delta = 2.0 * z / mp.ln();
+        let gamma = delta * ((mp - 1.0) / (p * mp.sqrt())).ln();
+        let xi = centre - 0.5 * p * (mp + 1.0) / (mp - 1.0);
+        Distribution::Johnson { family: JohnsonFamily::SL, gamma, delta, xi, lambda: 1.0 }
+    };
+
+    match dist {
+        Distribution::Johnson { gamma, delta, xi, lambda, .. }
+            if [gamma, delta, xi, lambda].iter().all(|v| v.is_finite()) && delta > 0.0 && lambda > 0.0 =>
+        {
+            Some(dist)
+        }
+        _ => None,
+    }
+}
+
+/// Linearly interpolated quantile of sorted data
+pub fn sample_quantile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let idx = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lo = idx.floor() as usize;
+    let hi = (lo + 1).min(sorted.len() - 1);
+    let frac = idx - lo as f64;
+    sorted[lo] * (1.0 - frac) + sorted[hi] * frac
+}
+
+/// Anderson-Darling A² statistic of sorted data against a hypothesised CDF (lower = better fit)
+pub fn anderson_darling_statistic(sorted: &[f64], cdf: impl Fn(f64) -> f64) -> f64 {
+    let n = sorted.len();
+    if n == 0 {
+        return f64::NAN;
+    }
+    let probs: Vec<f64> = sorted.iter().map(|&x| cdf(x).clamp(1e-12, 1.0 - 1e-12)).collect();
+    let sum: f64 = (0..n)
+        .map(|i| (2 * i + 1) as f64 * (probs[i].ln() + (1.0 - probs[n - 1 - i]).ln()))
+        .sum();
+    -(n as f64) - sum / n as f64
+}
+
+/// Result of a normality test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalityTest {
+    /// Test statistic (A² for Anderson-Darling, W for Shapiro-Wilk)
+    pub statistic: f64,
+    /// Probability of a statistic at least this extreme if the data were normal
+    pub p_value: f64,
+}
+
+/// Anderson-Darling normality test with mean and sigma estimated from the data.
+/// The p-value uses D'Agostino & Stephens' approximation on the small-sample adjusted A².
+pub fn anderson_darling_test(values: &[f64]) -> Option<NormalityTest> {
+    if values.len() < 8 {
+        return None;
+    }
+    let (mean, std_dev) = mean_std(values)?;
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let a2 = anderson_darling_statistic(&sorted, |x| normal_cdf((x - mean) / std_dev));
+    let n = values.len() as f64;
+    let adjusted = a2 * (1.0 + 0.75 / n + 2.25 / (n * n));
+    let p_value = if adjusted >= 0.6 {
+        (1.2937 - 5.709 * adjusted + 0.0186 * adjusted * adjusted).exp()
+    } else if adjusted >= 0.34 {
+        (0.9177 - 4.279 * adjusted - 1.38 * adjusted * adjusted).exp()
+    } else if adjusted >= 0.2 {
+        1.0 - (-8.318 + 42.796 * adjusted - 59.938 * adjusted * adjusted).exp()
+    } else {
+        1.0 - (-13.436 + 101.14 * adjusted - 223.73 * adjusted * adjusted).exp()
+    };
+
+    Some(NormalityTest { statistic: a2, p_value: p_value.clamp(0.0, 1.0) })
+}
+
+/// Shapiro-Wilk normality test (Royston's 1992 approximation, 3 <= n <= 5000)
+pub fn shapiro_wilk_test(values: &[f64]) -> Option<NormalityTest> {
+    let n = values.len();
+    if !(3..=5000).contains(&n) {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mean = sorted.iter().sum::<f64>() / n as f64;
+    let ss: f64 = sorted.iter().map(|v| (v - mean).powi(2)).sum();
+    if ss <= 0.0 {
+        return None;
+    }
+
+    // Coefficients from normal order statistic approximations
+    let nf = n as f64;
+    let m: Vec<f64> = (1..=n).map(|i| normal_quantile((i as f64 - 0.375) / (nf + 0.25))).collect();
+    let mut a = vec![0.0; n];
+    if n == 3 {
+        a[0] = -std::f64::consts::FRAC_1_SQRT_2;
+        a[2] = std::f64::consts::FRAC_1_SQRT_2;
+    } else {
+        let mm: f64 = m.iter().map(|v| v * v).sum();
+        let u = 1.0 / nf.sqrt();
+        let poly = |c: [f64; 5]| c.iter().rev().fold(0.0, |acc, &ci| (acc + ci) * u);
+        let an = m[n - 1] / mm.sqrt() + poly([0.221_157, -0.147_981, -2.071_190, 4.434_685, -2.706_056]);
+        if n > 5 {
+            let an1 = m[n - 2] / mm.sqrt() + poly([0.042_981, -0.293_762, -1.752_461, 5.682_633, -3.582_633]);
+            let phi = (mm - 2.0 * m[n - 1].powi(2) - 2.0 * m[n - 2].powi(2)) / (1.0 - 2.0 * an * an - 2.0 * an1 * an1);
+            for i in 2..n - 2 {
+                a[i] = m[i] / phi.sqrt();
+            }
+            a[n - 2] = an1;
+            a[1] = -an1;
+        } else {
+            let phi = (mm - 2.0 * m[n - 1].powi(2)) / (1.0 - 2.0 * an * an);
+            for i in 1..n - 1 {
+                a[i] = m[i] / phi.sqrt();
+            }
+        }
+        a[n - 1] = an;
+        a[0] = -an;
+    }
+
+    let w = (a.iter().zip(&sorted).map(|(ai, xi)| ai * xi).sum::<f64>().powi(2) / ss).min(1.0);
+
+    let p_value = if n == 3 {
+        let pi = std::f64::consts::PI;
+        (6.0 / pi * (w.sqrt().asin() - 0.75f64.sqrt().asin())).max(0.0)
+    } else if n <= 11 {
+        let gamma = 0.459 * nf - 2.273;
+        let transformed = -(gamma - (1.0 - w).ln()).ln();
+        let mu = 0.5440 - 0.39978 * nf + 0.025054 * nf.powi(2) - 0.0006714 * nf.powi(3);
+        let sigma = (1.3822 - 0.77857 * nf + 0.062767 * nf.powi(2) - 0.0020322 * nf.powi(3)).exp();
+        1.0 - normal_cdf((transformed - mu) / sigma)
+    } else {
+        let ln_n = nf.ln();
+        let mu = -1.5861 - 0.31082 * ln_n - 0.083751 * ln_n.powi(2) + 0.0038915 * ln_n.powi(3);
+        let sigma = (-0.4803 - 0.082676 * ln_n + 0.0030302 * ln_n.powi(2)).exp();
+        1.0 - normal_cdf(((1.0 - w).ln() - mu) / sigma)
+    };
+
+    Some(NormalityTest { statistic: w, p_value: p_value.clamp(0.0, 1.0) })
+}
+
+/// Normal Q-Q plotting positions (Blom) for n sorted observations
+pub fn normal_scores(n: usize) -> Vec<f64> {
+    let nf = n as f64;
+    (1..=n).map(|i| normal_quantile((i as f64 - 0.375) / (nf + 0.25))).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,4 +912,179 @@ mod tests {
         assert_eq!(stats.mean, 0.0);
         assert_eq!(stats.count, 0);
     }
+
+    #[test]
+    fn test_ln_gamma() {
+        // Gamma(5) = 24, Gamma(0.5) = sqrt(pi)
+        assert!((ln_gamma(5.0) - 24.0f64.ln()).abs() < 1e-10);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_c4_table_values() {
+        // Published c4 constants
+        assert!((c4(2) - 0.7979).abs() < 1e-4);
+        assert!((c4(5) - 0.9400).abs() < 1e-4);
+        assert!((c4(10) - 0.9727).abs() < 1e-4);
+        assert!((c4(25) - 0.9896).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_normal_distribution() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        assert!((normal_cdf(-3.0) - 0.001_349_9).abs() < 1e-6);
+        for p in [0.001, 0.025, 0.5, 0.9, 0.999] {
+            assert!((normal_cdf(normal_quantile(p)) - p).abs() < 1e-7, "p={}", p);
+        }
+        assert!((normal_quantile(0.975) - 1.959_964).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_normal_quantile_reference_values() {
+        // Reference quantiles to 16 digits; the rational approximation alone is good to ~1e-9
+        for (p, z) in [
+            (0.5, 0.0),
+            (0.9, 1.281_551_565_544_600_4),
+            (0.975, 1.959_963_984_540_054),
+            (0.001, -3.090_232_306_167_813_5),
+        ] {
+            assert!((normal_quantile(p) - z).abs() < 5e-9, "p={}", p);
+        }
+    }
+
+    #[test]
+    fn test_chi_square_quantile() {
+        // Table values
+        assert!((chi_square_quantile(0.025, 10.0) - 3.247).abs() < 1e-3);
+        assert!((chi_square_quantile(0.975, 10.0) - 20.483).abs() < 1e-3);
+        assert!((chi_square_quantile(0.95, 1.0) - 3.841).abs() < 1e-3);
+    }
+
+    /// Deterministic sample with the given quantile function (evenly spaced plotting positions)
+    fn sample_from(quantile: impl Fn(f64) -> f64, n: usize) -> Vec<f64> {
+        (0..n).map(|i| quantile((i as f64 + 0.5) / n as f64)).collect()
+    }
+
+    #[test]
+    fn test_fit_lognormal_and_weibull() {
+        let lognormal = Distribution::Lognormal { mu: 1.0, sigma: 0.5 };
+        match fit_lognormal(&sample_from(|p| lognormal.quantile(p), 500)) {
+            Some(Distribution::Lognormal { mu, sigma }) => {
+                assert!((mu - 1.0).abs() < 0.01);
+                assert!((sigma - 0.5).abs() < 0.02);
+            }
+            other => panic!("unexpected fit {:?}", other),
+        }
+
+        let weibull = Distribution::Weibull { shape: 2.0, scale: 10.0 };
+        match fit_weibull(&sample_from(|p| weibull.quantile(p), 500)) {
+            Some(Distribution::Weibull { shape, scale }) => {
+                assert!((shape - 2.0).abs() < 0.05, "shape {}", shape);
+                assert!((scale - 10.0).abs() < 0.1, "scale {}", scale);
+            }
+            other => panic!("unexpected fit {:?}", other),
+        }
+
+        assert!(fit_weibull(&[1.0, -2.0, 3.0]).is_none());
+    }
+
+    #[test]
+    fn test_fit_box_cox_recovers_log() {
+        // Lognormal data is normal after a log transform, so λ should be near 0
+        let lognormal = Distribution::Lognormal { mu: 0.0, sigma: 0.4 };
+        match fit_box_cox(&sample_from(|p| lognormal.quantile(p), 400)) {
+            Some(Distribution::BoxCox { lambda, .. }) => assert!(lambda.abs() < 0.05, "λ {}", lambda),
+            other => panic!("unexpected fit {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fit_johnson_round_trip() {
+        let skewed = Distribution::Lognormal { mu: 0.0, sigma: 0.6 };
+        let values = sample_from(|p| skewed.quantile(p), 400);
+        let fit = fit_johnson(&values).expect("johnson fit");
+        for p in [0.05, 0.5, 0.95] {
+            let x = skewed.quantile(p);
+            assert!((fit.cdf(x) - p).abs() < 0.02, "p={} cdf={}", p, fit.cdf(x));
+        }
+    }
+
+    #[test]
+    fn test_quantile_inverts_cdf() {
+        let dists = [
+            Distribution::Normal { mean: 5.0, std_dev: 2.0 },
+            Distribution::Weibull { shape: 1.5, scale: 3.0 },
+            Distribution::BoxCox { lambda: 0.5, mean: 2.0, std_dev: 0.3 },
+            Distribution::Johnson { family: JohnsonFamily::SU, gamma: 0.5, delta: 1.5, xi: 1.0, lambda: 2.0 },
+            Distribution::Johnson { family: JohnsonFamily::SB, gamma: 0.2, delta: 0.9, xi: 0.0, lambda: 10.0 },
+        ];
+        for dist in dists {
+            for p in [0.001_35, 0.5, 0.998_65] {
+                assert!((dist.cdf(dist.quantile(p)) - p).abs() < 1e-6, "{:?} p={}", dist, p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_anderson_darling_prefers_true_family() {
+        let weibull = Distribution::Weibull { shape: 1.2, scale: 2.0 };
+        let values = sample_from(|p| weibull.quantile(p), 300);
+        let normal = fit_normal(&values).unwrap();
+        let fitted = fit_weibull(&values).unwrap();
+        let ad_normal = anderson_darling_statistic(&values, |x| normal.cdf(x));
+        let ad_weibull = anderson_darling_statistic(&values, |x| fitted.cdf(x));
+        assert!(ad_weibull < ad_normal);
+    }
+
+    #[test]
+    fn test_normality_tests_accept_normal_data() {
+        let normal = sample_from(normal_quantile, 200);
+        let ad = anderson_darling_test(&normal).unwrap();
+        let sw = shapiro_wilk_test(&normal).unwrap();
+        assert!(ad.p_value > 0.5, "AD p {}", ad.p_value);
+        assert!(sw.statistic > 0.99 && sw.p_value > 0.5, "SW {:?}", sw);
+    }
+
+    #[test]
+    fn test_normality_tests_reject_skewed_data() {
+        let exponential = sample_from(|p| -(1.0 - p).ln(), 200);
+        let ad = anderson_darling_test(&exponential).unwrap();
+        let sw = shapiro_wilk_test(&exponential).unwrap();
+        assert!(ad.p_value < 0.01, "AD p {}", ad.p_value);
+        assert!(sw.p_value < 0.01, "SW p {}", sw.p_value);
+    }
+
+    #[test]
+    fn test_shapiro_wilk_small_samples() {
+        // n = 3 has an exact distribution; equally spaced points give W = 1
+        let sw = shapiro_wilk_test(&[1.0, 2.0, 3.0]).unwrap();
+        assert!((sw.statistic - 1.0).abs() < 1e-9);
+        assert!((sw.p_value - 1.0).abs() < 1e-6);
+
+        let sw = shapiro_wilk_test(&[1.0, 2.0, 3.0, 4.0, 100.0]).unwrap();
+        assert!(sw.p_value < 0.01);
+        assert!(shapiro_wilk_test(&[1.0, 2.0]).is_none());
+        assert!(shapiro_wilk_test(&[5.0; 10]).is_none());
+    }
+
+    #[test]
+    fn test_shapiro_wilk_reference_value() {
+        // R: shapiro.test(...) gives W = 0.78881, p-value = 0.006704
+        let values = [148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0];
+        let sw = shapiro_wilk_test(&values).unwrap();
+        assert!((sw.statistic - 0.78881).abs() < 1e-4);
+        assert!((sw.p_value - 0.006704).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_f_distribution() {
+        // Table values: F(0.95; 2, 10) = 4.103, F(0.99; 5, 20) = 4.103
+        assert!((f_cdf(4.103, 2.0, 10.0) - 0.95).abs() < 1e-3);
+        assert!((f_cdf(4.103, 5.0, 20.0) - 0.99).abs() < 1e-3);
+        assert!((beta_inc(2.0, 3.0, 0.4) - 0.5248).abs() < 1e-4);
+        assert_eq!(f_cdf(0.0, 3.0, 4.0), 0.0);
+        assert!((f_quantile(0.95, 2.0, 10.0) - 4.103).abs() < 1e-3);
+        assert!((f_quantile(0.99, 5.0, 20.0) - 4.103).abs() < 1e-3);
+    }
 }
@@ -0,0 +1,86 @@
+//! Stratification: split a series into groups by a category column
+
+use std::collections::HashMap;
+
+/// Rows of one series that share a category value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stratum {
+    /// Category value (empty labels become "(blank)")
+    pub label: String,
+    /// Source row of each value
+    pub rows: Vec<usize>,
+    /// Series values in row order
+    pub values: Vec<f64>,
+}
+
+impl Stratum {
+    /// Mean and population standard deviation of the stratum
+    pub fn mean_std(&self) -> (f64, f64) {
+        if self.values.is_empty() {
+            return (0.0, 0.0);
+        }
+        let n = self.values.len() as f64;
+        let mean = self.values.iter().sum::<f64>() / n;
+        let variance = self.values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        (mean, variance.sqrt())
+    }
+}
+
+/// Group the rows accepted by `keep` by their label, in order of first appearance.
+/// Rows with a non-finite value are skipped.
+pub fn stratify(labels: &[String], values: &[f64], keep: impl Fn(usize) -> bool) -> Vec<Stratum> {
+    let mut strata: Vec<Stratum> = Vec::new();
+    let mut lookup: HashMap<&str, usize> = HashMap::new();
+
+    for (row, (label, &value)) in labels.iter().zip(values).enumerate() {
+        if !value.is_finite() || !keep(row) {
+            continue;
+        }
+        let idx = *lookup.entry(label.as_str()).or_insert_with(|| {
+            let label = if label.is_empty() { "(blank)".to_string() } else { label.clone() };
+            strata.push(Stratum { label, rows: Vec::new(), values: Vec::new() });
+            strata.len() - 1
+        });
+        strata[idx].rows.push(row);
+        strata[idx].values.push(value);
+    }
+
+    strata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_stratify_order_and_rows() {
+        let labels = labels(&["B", "A", "B", "", "A", "B"]);
+        let values = [1.0, 10.0, 3.0, 7.0, f64::NAN, 5.0];
+        let strata = stratify(&labels, &values, |_| true);
+
+        let names: Vec<&str> = strata.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(names, vec!["B", "A", "(blank)"]);
+        assert_eq!(strata[0].rows, vec![0, 2, 5]);
+        assert_eq!(strata[0].values, vec![1.0, 3.0, 5.0]);
+        // NaN at row 4 is dropped
+        assert_eq!(strata[1].rows, vec![1]);
+
+        let (mean, std_dev) = strata[0].mean_std();
+        assert!((mean - 3.0).abs() < 1e-12);
+        assert!((std_dev - (8.0f64 / 3.0).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_stratify_respects_row_filter() {
+        let labels = labels(&["A", "B", "A", "B"]);
+        let strata = stratify(&labels, &[1.0, 2.0, 3.0, 4.0], |row| row >= 2);
+        assert_eq!(strata.len(), 2);
+        assert_eq!(strata[0].rows, vec![2]);
+        assert_eq!(strata[1].rows, vec![3]);
+        assert_eq!(Stratum { label: String::new(), rows: vec![], values: vec![] }.mean_std(), (0.0, 0.0));
+    }
+}
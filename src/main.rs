@@ -16,7 +16,6 @@ mod ui;
 // Use PlotOxide from app module
 use app::PlotOxide;
 use state::ActivePanel;
-use perf::WorkerResult;
 
 impl App for PlotOxide {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -24,32 +23,23 @@ impl App for PlotOxide {
         profiling::scope!("update");
         profiling::finish_frame!();
         
-        // Phase 5: Poll background worker for completed work
-        while let Some(result) = self.state.worker.poll() {
-            match result {
-                WorkerResult::FileLoaded { path, df } => {
-                    // Convert DataFrame to DataSource
-                    if let Ok(ds) = crate::data::DataSource::from_dataframe(df, Some(path.clone())) {
-                        self.state.data = Some(ds);
-                        self.state.current_file = Some(path);
-                        self.state.lttb_cache.invalidate();
-                        self.state.downsampler.force_settle();
-                        
-                        // Auto-select columns
-                        if self.state.column_count() > 1 {
-                            self.state.view.y_indices = vec![1];
-                        }
-                    }
-                    self.state.is_loading = false;
-                }
-                WorkerResult::LttbReady { .. } => {
-                    // Cache is updated by the cache itself
-                }
-                WorkerResult::Error { msg } => {
-                    self.state.ui.set_error(msg);
-                    self.state.is_loading = false;
-                }
-            }
+        // Phase 5: Apply finished background work; keep repainting while a load reports progress
+        // or combination, or a plot waits on downsampling
+        self.poll_worker();
+        if self.state.loading.is_some() || self.state.combining.is_some() || self.state.lttb_cache.has_pending() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+        }
+
+        // Read rows appended to a followed file, checking again after the poll interval
+        self.poll_follow();
+        if self.state.follow.enabled {
+            ctx.request_repaint_after(std::time::Duration::from_millis(constants::performance::FOLLOW_POLL_MS));
+        }
+
+        // Parse lines from a live source as they arrive
+        self.poll_live();
+        if self.state.live.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(constants::performance::LIVE_POLL_MS));
         }
         
         // Set theme
@@ -267,12 +257,10 @@ impl App for PlotOxide {
                         if ui.button("📂 Open Data File").clicked() {
                             // Trigger file dialog
                             if let Some(path) = rfd::FileDialog::new()
-                                .add_filter("Data Files", &["csv", "parquet"])
+                                .add_filter("Data Files", &crate::data::formats::SUPPORTED_EXTENSIONS)
                                 .pick_file()
                             {
-                                if let Err(e) = self.load_file(path) {
-                                    self.state.ui.set_error(e.user_message());
-                                }
+                                self.open_file(path);
                             }
                         }
                         ui.add_space(10.0);
@@ -340,15 +328,18 @@ impl App for PlotOxide {
 
         // Help dialog (Modal)
         ui::render_help_dialog(self, ctx);
+        ui::render_import_dialog(self, ctx);
+        ui::render_live_dialog(self, ctx);
+        ui::render_combine_dialog(self, ctx);
+        ui::render_computed_dialog(self, ctx);
+        ui::render_load_progress(self, ctx);
         
         // Handle drag and drop globally
         if !ctx.input(|i| i.raw.dropped_files.is_empty()) {
              ctx.input(|i| {
                 if let Some(dropped) = i.raw.dropped_files.first() {
                     if let Some(path) = &dropped.path {
-                        if let Err(e) = self.load_file(path.clone()) {
-                            self.state.ui.set_error(e.user_message());
-                        }
+                        self.open_file(path.clone());
                     }
                 }
             });
@@ -1,28 +1,75 @@
 //! LTTB Cache with zoom-level quantization (Phase 2)
 //!
-//! Reduces LTTB recomputation by 10-50x through smart caching.
+//! Reduces LTTB recomputation by 10-50x through smart caching. Each entry covers a window of
+//! the X axis snapped to a grid that scales with the zoom level, so small pans and zooms reuse
+//! it. Misses are computed on the background worker; until the result arrives a series keeps
+//! the points it was last drawn with.
 
+use super::{BackgroundWorker, WorkerRequest};
 use std::collections::HashMap;
 
-/// Cache key: (series_id, zoom_bucket)
-type CacheKey = (usize, i32);
+/// Identifies one downsampled window of a series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LttbKey {
+    pub series_id: usize,
+    /// Data version the points came from
+    pub data_version: u64,
+    /// Fingerprint of everything else that shapes the points (X column, filters)
+    pub inputs: u64,
+    pub target_points: usize,
+    /// Zoom bucket and first/last grid cell of the window; None covers the whole series
+    window: Option<(i32, i64, i64)>,
+}
+
+impl LttbKey {
+    /// Key for the window around `visible_range` (None while the plot shows everything)
+    pub fn new(
+        series_id: usize,
+        data_version: u64,
+        inputs: u64,
+        target_points: usize,
+        visible_range: Option<(f64, f64)>,
+    ) -> Self {
+        let window = visible_range
+            .filter(|(min, max)| min.is_finite() && max.is_finite() && max > min)
+            .map(|(min, max)| {
+                let bucket = LttbCache::zoom_bucket(max - min);
+                let cell = LttbCache::cell_width(bucket);
+                // One cell of margin each side, so panning within it keeps the same key
+                (bucket, (min / cell).floor() as i64 - 1, (max / cell).ceil() as i64 + 1)
+            });
+        Self { series_id, data_version, inputs, target_points, window }
+    }
+
+    /// X range this window covers (None = the whole series)
+    pub fn x_range(&self) -> Option<(f64, f64)> {
+        self.window.map(|(bucket, first, last)| {
+            let cell = LttbCache::cell_width(bucket);
+            (first as f64 * cell, last as f64 * cell)
+        })
+    }
+}
 
 /// LTTB cache with zoom-level quantization
 pub struct LttbCache {
-    cache: HashMap<CacheKey, Vec<[f64; 2]>>,
-    target_points: usize,
+    cache: HashMap<LttbKey, Vec<[f64; 2]>>,
+    /// Latest window requested per series; results for any other window are stale
+    pending: HashMap<usize, LttbKey>,
+    /// Points each series was last drawn with
+    shown: HashMap<usize, Vec<[f64; 2]>>,
     max_entries: usize,
 }
 
 impl LttbCache {
-    pub fn new(target_points: usize) -> Self {
+    pub fn new() -> Self {
         Self {
             cache: HashMap::new(),
-            target_points,
+            pending: HashMap::new(),
+            shown: HashMap::new(),
             max_entries: 100,
         }
     }
-    
+
     /// Quantize zoom level to reduce cache invalidation
     /// Each bucket represents ~40% zoom change (2^0.5)
     fn zoom_bucket(visible_range: f64) -> i32 {
@@ -31,47 +78,93 @@ impl LttbCache {
         }
         (visible_range.log2() * 2.0).floor() as i32
     }
-    
-    /// Get cached downsampled data or compute it
-    pub fn get_or_compute<F>(
-        &mut self,
-        series_id: usize,
-        visible_range: (f64, f64),
-        compute_fn: F,
-    ) -> &[[f64; 2]]
-    where
-        F: FnOnce(usize) -> Vec<[f64; 2]>,
-    {
-        let range_width = visible_range.1 - visible_range.0;
-        let bucket = Self::zoom_bucket(range_width);
-        let key = (series_id, bucket);
-        
-        if !self.cache.contains_key(&key) {
-            self.enforce_limit();
-            let points = compute_fn(self.target_points);
-            self.cache.insert(key, points);
+
+    /// Grid spacing for window edges at a zoom bucket: about a tenth of the visible range
+    fn cell_width(bucket: i32) -> f64 {
+        2f64.powf(bucket as f64 / 2.0) / 8.0
+    }
+
+    /// Downsampled points for `key`'s window of `points`. On a miss the window is sent to the
+    /// worker and the series is drawn as it was last time (or thinned by stride, if it has
+    /// never been drawn) until `receive` stores the result.
+    pub fn points(&mut self, key: LttbKey, points: &[[f64; 2]], worker: &BackgroundWorker) -> Vec<[f64; 2]> {
+        if let Some(cached) = self.cache.get(&key) {
+            let cached = cached.clone();
+            self.shown.insert(key.series_id, cached.clone());
+            return cached;
+        }
+
+        if self.pending.get(&key.series_id) != Some(&key) {
+            let data = points.iter()
+                .filter(|p| key.x_range().is_none_or(|(min, max)| p[0] >= min && p[0] <= max))
+                .map(|p| (p[0], p[1]))
+                .collect();
+            worker.request(WorkerRequest::ComputeLttb { key, data, target_points: key.target_points });
+            self.pending.insert(key.series_id, key);
+        }
+
+        match self.shown.get(&key.series_id) {
+            Some(shown) => shown.clone(),
+            None => {
+                let step = (points.len() / key.target_points.max(1)).max(1);
+                points.iter().step_by(step).copied().collect()
+            }
         }
-        
-        self.cache.get(&key).map(|v| v.as_slice()).unwrap_or(&[])
     }
-    
-    /// Check if we have cached data for this zoom level
-    pub fn has_cached(&self, series_id: usize, visible_range: (f64, f64)) -> bool {
-        let range_width = visible_range.1 - visible_range.0;
-        let bucket = Self::zoom_bucket(range_width);
-        self.cache.contains_key(&(series_id, bucket))
+
+    /// Store a result from the worker. Returns false (and drops it) when a newer window has
+    /// been requested for the series since.
+    pub fn receive(&mut self, key: LttbKey, points: Vec<[f64; 2]>) -> bool {
+        if self.pending.get(&key.series_id) != Some(&key) {
+            return false;
+        }
+        self.pending.remove(&key.series_id);
+        self.enforce_limit();
+        self.cache.insert(key, points);
+        true
     }
-    
+
+    /// Whether any series is waiting on the worker
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Check if we have cached data for this window
+    pub fn has_cached(&self, key: &LttbKey) -> bool {
+        self.cache.contains_key(key)
+    }
+
     /// Clear all cached data
     pub fn invalidate(&mut self) {
         self.cache.clear();
+        self.pending.clear();
+        self.shown.clear();
     }
-    
+
     /// Clear cache for specific series
     pub fn invalidate_series(&mut self, series_id: usize) {
-        self.cache.retain(|(id, _), _| *id != series_id);
+        self.cache.retain(|key, _| key.series_id != series_id);
+        self.pending.remove(&series_id);
+        self.shown.remove(&series_id);
     }
-    
+
+    /// Drop windows that reach `x` or beyond, after rows with X values from `x` on were
+    /// appended. Windows wholly before it still hold the same points and are kept, as are the
+    /// points each series was last drawn with until replacements arrive.
+    pub fn invalidate_from(&mut self, x: f64) {
+        let stale = |key: &LttbKey| key.x_range().is_none_or(|(_, max)| max >= x);
+        self.cache.retain(|key, _| !stale(key));
+        self.pending.retain(|_, key| !stale(key));
+    }
+
+    /// Drop windows that reach back to `x` or before, after rows with X values up to `x` were
+    /// removed from the front. Windows wholly after it still hold the same points and are kept.
+    pub fn invalidate_to(&mut self, x: f64) {
+        let stale = |key: &LttbKey| key.x_range().is_none_or(|(min, _)| min <= x);
+        self.cache.retain(|key, _| !stale(key));
+        self.pending.retain(|_, key| !stale(key));
+    }
+
     /// Limit memory usage by evicting old entries
     fn enforce_limit(&mut self) {
         if self.cache.len() >= self.max_entries {
@@ -80,13 +173,13 @@ impl LttbCache {
                 .take(self.cache.len() / 2)
                 .cloned()
                 .collect();
-            
+
             for key in to_remove {
                 self.cache.remove(&key);
             }
         }
     }
-    
+
     /// Get cache statistics
     pub fn stats(&self) -> (usize, usize) {
         (self.cache.len(), self.max_entries)
@@ -95,7 +188,7 @@ impl LttbCache {
 
 impl Default for LttbCache {
     fn default() -> Self {
-        Self::new(5000)
+        Self::new()
     }
 }
 
@@ -109,30 +202,79 @@ mod tests {
         let b1 = LttbCache::zoom_bucket(100.0);
         let b2 = LttbCache::zoom_bucket(110.0);
         assert_eq!(b1, b2, "10% zoom change should stay in same bucket");
-        
+
         // Large zoom change should change bucket
         let b3 = LttbCache::zoom_bucket(200.0);
         assert_ne!(b1, b3, "2x zoom should change bucket");
     }
-    
+
+    #[test]
+    fn test_key_window() {
+        let key = LttbKey::new(0, 1, 0, 100, Some((0.0, 100.0)));
+        let (min, max) = key.x_range().unwrap();
+        assert!(min < 0.0 && max > 100.0);
+
+        // A small pan stays in the same window; a pan by a whole view or a new version does not
+        assert_eq!(LttbKey::new(0, 1, 0, 100, Some((1.0, 101.0))), key);
+        assert_ne!(LttbKey::new(0, 1, 0, 100, Some((100.0, 200.0))), key);
+        assert_ne!(LttbKey::new(0, 2, 0, 100, Some((0.0, 100.0))), key);
+        assert_eq!(LttbKey::new(0, 1, 0, 100, None).x_range(), None);
+    }
+
+    #[test]
+    fn test_cache_hit_and_stale_results() {
+        let worker = BackgroundWorker::spawn();
+        let mut cache = LttbCache::new();
+        let points: Vec<[f64; 2]> = (0..1000).map(|i| [i as f64, (i % 7) as f64]).collect();
+
+        // A miss requests the window and thins the series by stride meanwhile
+        let first = LttbKey::new(0, 1, 0, 100, Some((0.0, 500.0)));
+        assert_eq!(cache.points(first, &points, &worker).len(), 100);
+        assert!(cache.has_pending());
+
+        // Zooming before the result arrives makes it stale
+        let second = LttbKey::new(0, 1, 0, 100, Some((0.0, 100.0)));
+        cache.points(second, &points, &worker);
+        assert!(!cache.receive(first, vec![[0.0, 0.0]]));
+        assert!(cache.receive(second, vec![[0.0, 0.0], [1.0, 1.0]]));
+        assert!(!cache.has_pending());
+
+        // Later frames in the same window hit the cache
+        assert_eq!(cache.points(LttbKey::new(0, 1, 0, 100, Some((1.0, 101.0))), &points, &worker).len(), 2);
+        assert!(!cache.has_cached(&first));
+    }
+
     #[test]
-    fn test_cache_hit() {
-        let mut cache = LttbCache::new(100);
-        
-        // First call computes
-        let mut computed = false;
-        cache.get_or_compute(0, (0.0, 100.0), |_| {
-            computed = true;
-            vec![[0.0, 0.0], [1.0, 1.0]]
-        });
-        assert!(computed);
-        
-        // Second call with similar range should hit cache
-        computed = false;
-        cache.get_or_compute(0, (0.0, 105.0), |_| {
-            computed = true;
-            vec![]
-        });
-        assert!(!computed, "Should have hit cache");
+    fn test_invalidate_from() {
+        let mut cache = LttbCache::new();
+        let early = LttbKey::new(0, 1, 0, 100, Some((0.0, 100.0)));
+        let late = LttbKey::new(0, 1, 0, 100, Some((900.0, 1000.0)));
+        let whole = LttbKey::new(1, 1, 0, 100, None);
+        for key in [early, late, whole] {
+            cache.pending.insert(key.series_id, key);
+            cache.receive(key, vec![[0.0, 0.0]]);
+        }
+
+        // Rows appended from x = 1000 on leave only the window that ends before them
+        cache.invalidate_from(1000.0);
+        assert!(cache.has_cached(&early));
+        assert!(!cache.has_cached(&late) && !cache.has_cached(&whole));
+    }
+
+    #[test]
+    fn test_invalidate_to() {
+        let mut cache = LttbCache::new();
+        let early = LttbKey::new(0, 1, 0, 100, Some((0.0, 100.0)));
+        let late = LttbKey::new(0, 1, 0, 100, Some((900.0, 1000.0)));
+        let whole = LttbKey::new(1, 1, 0, 100, None);
+        for key in [early, late, whole] {
+            cache.pending.insert(key.series_id, key);
+            cache.receive(key, vec![[0.0, 0.0]]);
+        }
+
+        // Rows up to x = 50 dropped from the front leave only the window that starts after them
+        cache.invalidate_to(50.0);
+        assert!(cache.has_cached(&late));
+        assert!(!cache.has_cached(&early) && !cache.has_cached(&whole));
     }
 }
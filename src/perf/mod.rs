@@ -13,8 +13,8 @@ mod cache;
 mod worker;
 mod downsample;
 
-pub use cache::LttbCache;
-pub use worker::{BackgroundWorker, WorkerResult};
+pub use cache::{LttbCache, LttbKey};
+pub use worker::{BackgroundWorker, LoadMode, WorkerRequest, WorkerResult};
 pub use downsample::AdaptiveDownsampler;
 
 use std::sync::Arc;
@@ -2,20 +2,67 @@
 //!
 //! Keeps UI at 60fps regardless of computation load.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
 use std::thread::{self, JoinHandle};
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
 
-use polars::prelude::*;
+use polars::prelude::{DataFrame, LazyFrame, SchemaRef};
+
+use crate::data::{DataError, DataSource};
+use crate::data::formats::{CsvOptions, FileScan, LoadProgress};
+use crate::data::live::{self, LineFormat};
+use crate::data::pyramid::MinMaxPyramid;
+use super::LttbKey;
+
+/// How a file is held once loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadMode {
+    /// Read the whole file into memory
+    InMemory,
+    /// Leave the file on disk, summarising the given columns up front
+    Streaming { summarise: Vec<usize> },
+}
 
 /// Requests that can be sent to the background worker
 pub enum WorkerRequest {
-    /// Load a file (CSV or Parquet)
-    LoadFile { path: PathBuf },
-    /// Compute LTTB downsampling
+    /// Load a file (any format supported by `data::formats`); `options` apply to delimited text.
+    /// Setting `cancel` abandons the load at its next progress step.
+    LoadFile {
+        id: u64,
+        path: PathBuf,
+        options: CsvOptions,
+        mode: LoadMode,
+        cancel: Arc<AtomicBool>,
+    },
+    /// Build the min/max summary of a streamed column; `version` is the data version it is for
+    Summarise {
+        version: u64,
+        col: usize,
+        column: String,
+        scan: Box<FileScan>,
+    },
+    /// Parse a batch of lines from live session `id` into rows, conforming them to `schema`
+    /// (the rows already held) when given. `received` is when the lines were drained.
+    ParseLines {
+        id: u64,
+        lines: Vec<String>,
+        format: LineFormat,
+        header: Option<String>,
+        schema: Option<SchemaRef>,
+        received: Instant,
+    },
+    /// Collect a plan combining open data sets into a new one, named `name`
+    Combine {
+        id: u64,
+        name: String,
+        plan: Box<LazyFrame>,
+    },
+    /// Compute LTTB downsampling of one window of a series
     ComputeLttb {
-        series_id: usize,
+        key: LttbKey,
         data: Arc<[(f64, f64)]>,
         target_points: usize,
     },
@@ -25,10 +72,39 @@ pub enum WorkerRequest {
 
 /// Results returned from the background worker
 pub enum WorkerResult {
-    /// File loaded successfully
-    FileLoaded { path: PathBuf, df: DataFrame },
+    /// A load has read more of its file
+    LoadProgress { id: u64, progress: LoadProgress },
+    /// File loaded successfully, with its validation warnings
+    FileLoaded {
+        id: u64,
+        path: PathBuf,
+        source: Box<DataSource>,
+        warnings: Vec<String>,
+    },
+    /// A load stopped after its cancel flag was set
+    LoadCancelled { id: u64 },
+    /// A load failed
+    LoadFailed { id: u64, msg: String },
+    /// A streamed column's summary is ready (or failed)
+    SummaryReady {
+        version: u64,
+        col: usize,
+        summary: Result<Arc<MinMaxPyramid>, String>,
+    },
+    /// A batch of live lines has been parsed (or failed to parse)
+    LinesParsed {
+        id: u64,
+        received: Instant,
+        rows: Result<DataFrame, String>,
+    },
+    /// Combined data set ready (or failed)
+    Combined {
+        id: u64,
+        name: String,
+        source: Result<Box<DataSource>, String>,
+    },
     /// LTTB computation complete
-    LttbReady { series_id: usize, points: Vec<[f64; 2]> },
+    LttbReady { key: LttbKey, points: Vec<[f64; 2]> },
     /// An error occurred
     Error { msg: String },
 }
@@ -60,12 +136,31 @@ impl BackgroundWorker {
     fn worker_loop(rx: Receiver<WorkerRequest>, tx: Sender<WorkerResult>) {
         while let Ok(request) = rx.recv() {
             let result = match request {
-                WorkerRequest::LoadFile { path } => {
-                    Self::load_file(&path)
+                WorkerRequest::LoadFile { id, path, options, mode, cancel } => {
+                    Self::load_file(&tx, id, &path, &options, &mode, &cancel)
+                }
+                WorkerRequest::Summarise { version, col, column, scan } => {
+                    let summary = crate::data::source::summarise_column(&scan, &column, &AtomicBool::new(false), |_| {})
+                        .map(Arc::new)
+                        .map_err(|e| e.to_string());
+                    WorkerResult::SummaryReady { version, col, summary }
                 }
-                WorkerRequest::ComputeLttb { series_id, data, target_points } => {
+                WorkerRequest::ParseLines { id, lines, format, header, schema, received } => {
+                    let rows = live::parse_lines(&lines, format, header.as_deref(), schema.as_deref())
+                        .map_err(|e| e.to_string());
+                    WorkerResult::LinesParsed { id, received, rows }
+                }
+                WorkerRequest::Combine { id, name, plan } => {
+                    let source = plan.collect()
+                        .map_err(DataError::from)
+                        .and_then(|df| DataSource::from_dataframe(df, None))
+                        .map(Box::new)
+                        .map_err(|e| e.to_string());
+                    WorkerResult::Combined { id, name, source }
+                }
+                WorkerRequest::ComputeLttb { key, data, target_points } => {
                     let points = Self::compute_lttb(&data, target_points);
-                    WorkerResult::LttbReady { series_id, points }
+                    WorkerResult::LttbReady { key, points }
                 }
                 WorkerRequest::Shutdown => break,
             };
@@ -76,40 +171,30 @@ impl BackgroundWorker {
         }
     }
     
-    fn load_file(path: &PathBuf) -> WorkerResult {
-        let extension = path
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("");
-        
-        let df_result = match extension.to_lowercase().as_str() {
-            "parquet" => {
-                // Phase 1.1: Use optimized parquet loading
-                LazyFrame::scan_parquet(path, ScanArgsParquet {
-                    low_memory: true,
-                    parallel: polars::io::parquet::read::ParallelStrategy::Auto,
-                    ..Default::default()
-                })
-                .and_then(|lf| lf.collect())
-            }
-            "csv" => {
-                LazyCsvReader::new(path)
-                    .with_has_header(true)
-                    .with_infer_schema_length(Some(100))
-                    .with_try_parse_dates(true)
-                    .finish()
-                    .and_then(|lf| lf.collect())
-            }
-            _ => {
-                return WorkerResult::Error {
-                    msg: format!("Unsupported format: {}", extension),
-                };
-            }
+    fn load_file(
+        tx: &Sender<WorkerResult>,
+        id: u64,
+        path: &Path,
+        options: &CsvOptions,
+        mode: &LoadMode,
+        cancel: &AtomicBool,
+    ) -> WorkerResult {
+        let progress = |progress| {
+            let _ = tx.send(WorkerResult::LoadProgress { id, progress });
         };
-        
-        match df_result {
-            Ok(df) => WorkerResult::FileLoaded { path: path.clone(), df },
-            Err(e) => WorkerResult::Error { msg: e.to_string() },
+
+        let loaded = match mode {
+            LoadMode::InMemory => DataSource::load_with_progress(path, options, cancel, progress),
+            LoadMode::Streaming { summarise } => DataSource::open_streaming(path, options, summarise, cancel, progress),
+        };
+        match loaded {
+            Ok(source) => {
+                // Validation reads every column, so it runs here rather than on the UI thread
+                let warnings = source.validation_warnings();
+                WorkerResult::FileLoaded { id, path: path.to_path_buf(), source: Box::new(source), warnings }
+            }
+            Err(DataError::Cancelled) => WorkerResult::LoadCancelled { id },
+            Err(e) => WorkerResult::LoadFailed { id, msg: e.to_string() },
         }
     }
     
@@ -233,15 +318,78 @@ mod tests {
         file.flush().unwrap();
         
         let worker = BackgroundWorker::spawn();
-        worker.request(WorkerRequest::LoadFile { path: file.path().to_path_buf() });
-        
-        // Wait for result
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        
-        if let Some(WorkerResult::FileLoaded { df, .. }) = worker.poll() {
-            assert_eq!(df.height(), 2);
-        } else {
-            panic!("Expected FileLoaded result");
+        worker.request(WorkerRequest::LoadFile {
+            id: 7,
+            path: file.path().to_path_buf(),
+            options: CsvOptions::default(),
+            mode: LoadMode::InMemory,
+            cancel: Arc::new(AtomicBool::new(false)),
+        });
+
+        // Progress updates arrive before the loaded file
+        let mut saw_progress = false;
+        loop {
+            match wait_for_result(&worker) {
+                WorkerResult::LoadProgress { id, .. } => {
+                    assert_eq!(id, 7);
+                    saw_progress = true;
+                }
+                WorkerResult::FileLoaded { id, source, warnings, .. } => {
+                    assert_eq!(id, 7);
+                    assert_eq!(source.height(), 2);
+                    assert!(warnings.is_empty());
+                    break;
+                }
+                _ => panic!("Expected FileLoaded result"),
+            }
+        }
+        assert!(saw_progress);
+    }
+
+    #[test]
+    fn test_worker_load_cancelled() {
+        let mut file = Builder::new().suffix(".csv").tempfile().unwrap();
+        writeln!(file, "x,y\n1,2").unwrap();
+        file.flush().unwrap();
+
+        let worker = BackgroundWorker::spawn();
+        worker.request(WorkerRequest::LoadFile {
+            id: 1,
+            path: file.path().to_path_buf(),
+            options: CsvOptions::default(),
+            mode: LoadMode::InMemory,
+            cancel: Arc::new(AtomicBool::new(true)),
+        });
+        assert!(matches!(wait_for_result(&worker), WorkerResult::LoadCancelled { id: 1 }));
+    }
+
+    #[test]
+    fn test_worker_parse_lines() {
+        let worker = BackgroundWorker::spawn();
+        worker.request(WorkerRequest::ParseLines {
+            id: 3,
+            lines: vec!["1,2".to_string(), "3,4".to_string()],
+            format: LineFormat::Csv { separator: b',' },
+            header: Some("x,y".to_string()),
+            schema: None,
+            received: Instant::now(),
+        });
+        match wait_for_result(&worker) {
+            WorkerResult::LinesParsed { id, rows, .. } => {
+                assert_eq!(id, 3);
+                assert_eq!(rows.unwrap().height(), 2);
+            }
+            _ => panic!("Expected LinesParsed result"),
+        }
+    }
+
+    fn wait_for_result(worker: &BackgroundWorker) -> WorkerResult {
+        for _ in 0..500 {
+            if let Some(result) = worker.poll() {
+                return result;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
         }
+        panic!("Worker did not respond");
     }
 }
@@ -4,9 +4,22 @@
 
 use crate::constants::filters::*;
 
+/// A row filter typed in the filter bar, e.g. `category == "A" and value > 100`
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct FilterExpression {
+    pub expression: String,
+    /// Disabled filters are kept in the bar but not applied
+    pub enabled: bool,
+    /// Why an enabled filter is not applied to the data shown, if it is not
+    pub error: Option<String>,
+}
+
 /// Filter configuration for data selection and outlier detection
 #[derive(Debug, Clone)]
 pub struct FilterConfig {
+    /// Row filters, applied to the data itself; rows must match all enabled ones
+    pub expressions: Vec<FilterExpression>,
+
     /// Exclude empty cells from the dataset
     pub filter_empty: bool,
 
@@ -32,6 +45,7 @@ pub struct FilterConfig {
 impl Default for FilterConfig {
     fn default() -> Self {
         Self {
+            expressions: Vec::new(),
             filter_empty: false,
             filter_y_min: None,
             filter_y_max: None,
@@ -43,6 +57,20 @@ impl Default for FilterConfig {
     }
 }
 
+/// Hashed so caches of filtered points can tell when the filters have changed
+impl std::hash::Hash for FilterConfig {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.expressions.hash(state);
+        self.filter_empty.hash(state);
+        self.filter_y_min.map(f64::to_bits).hash(state);
+        self.filter_y_max.map(f64::to_bits).hash(state);
+        self.filter_x_min.map(f64::to_bits).hash(state);
+        self.filter_x_max.map(f64::to_bits).hash(state);
+        self.filter_outliers.hash(state);
+        self.filter_outlier_sigma.to_bits().hash(state);
+    }
+}
+
 impl FilterConfig {
     /// Create a new FilterConfig with default values
     pub fn new() -> Self {
@@ -56,7 +84,8 @@ impl FilterConfig {
 
     /// Check if any filters are active
     pub fn has_active_filters(&self) -> bool {
-        self.filter_empty
+        self.expressions.iter().any(|f| f.enabled && f.error.is_none())
+            || self.filter_empty
             || self.filter_y_min.is_some()
             || self.filter_y_max.is_some()
             || self.filter_x_min.is_some()
@@ -64,6 +93,15 @@ impl FilterConfig {
             || self.filter_outliers
     }
 
+    /// Check if any filter that drops points by value (X/Y range or outliers) is active
+    pub fn has_range_filters(&self) -> bool {
+        self.filter_y_min.is_some()
+            || self.filter_y_max.is_some()
+            || self.filter_x_min.is_some()
+            || self.filter_x_max.is_some()
+            || self.filter_outliers
+    }
+
     /// Get the Y range filter as a tuple if both min and max are set
     pub fn y_range(&self) -> Option<(f64, f64)> {
         match (self.filter_y_min, self.filter_y_max) {
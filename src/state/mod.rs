@@ -8,15 +8,119 @@ mod spc;
 mod filters;
 mod ui;
 
-pub use view::{ViewState, LineStyle, PlotMode};
-pub use spc::{SpcConfig, WEViolation};
-pub use filters::FilterConfig;
-pub use ui::{UiState, ActivePanel, TableState, CachedStats};
+pub use view::{ViewState, LineStyle, PlotMode, OverlaySeries};
+pub use spc::{SpcConfig, WEViolation, ControlLimits, VariablesChart, AttributeChart, ChartSignal, CusumChart, EwmaChart, FrozenLimits, StrataLayout};
+pub use filters::{FilterConfig, FilterExpression};
+pub use ui::{UiState, ActivePanel, TableState, CachedStats, ImportDialog, LiveDialog, CombineDialog, CombineMode, ComputedDialog, ProbabilitySeries, StreamPage, StreamWindow};
 
+use crate::constants::plot::{DEFAULT_FOLLOW_POINTS, DEFAULT_FOLLOW_SECONDS};
 use crate::data::DataSource;
+use crate::data::formats::{CsvOptions, LoadProgress};
+use crate::data::live::{BatchLog, LiveConfig, LiveListener};
 use crate::perf::{LttbCache, AdaptiveDownsampler, BackgroundWorker, PlotBuffer};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+/// A file load running on the background worker
+pub struct LoadingState {
+    /// Request id, used to ignore results from superseded loads
+    pub id: u64,
+    /// File being loaded
+    pub path: PathBuf,
+    /// Import options the file is read with (remembered once it loads)
+    pub options: CsvOptions,
+    /// Set to abandon the load
+    pub cancel: Arc<AtomicBool>,
+    /// Latest progress report, if any has arrived
+    pub progress: Option<LoadProgress>,
+}
+
+/// What the plot keeps in view while following a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowScroll {
+    /// Leave the view where the user puts it
+    Off,
+    /// The last `FollowState::points` rows
+    LastPoints,
+    /// The last `FollowState::seconds` of X (plain X units when X is not a timestamp)
+    LastSeconds,
+}
+
+/// Live tail of the current file: rows appended to it are read in as they arrive
+pub struct FollowState {
+    pub enabled: bool,
+    pub scroll: FollowScroll,
+    pub points: usize,
+    pub seconds: f64,
+    /// When the file was last checked
+    pub last_poll: Option<Instant>,
+}
+
+impl Default for FollowState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scroll: FollowScroll::Off,
+            points: DEFAULT_FOLLOW_POINTS,
+            seconds: DEFAULT_FOLLOW_SECONDS,
+            last_poll: None,
+        }
+    }
+}
+
+/// A running live session: lines from its listener are parsed in batches on the background
+/// worker, one batch at a time so rows arrive in order
+pub struct LiveSession {
+    /// Identifies the session's batches on the worker
+    pub id: u64,
+    pub config: LiveConfig,
+    pub listener: LiveListener,
+    /// Column names line, the first line received from a CSV source
+    pub header: Option<String>,
+    /// Lines received but not yet sent for parsing
+    pub pending: Vec<String>,
+    /// Whether a batch is being parsed
+    pub parsing: bool,
+    /// Arrival times of the rows held, once the first batch has opened the session's tab
+    pub batches: Option<BatchLog>,
+    /// Tab holding the rows, opened by the first batch
+    pub tab: Option<u64>,
+    /// When rows past the retention were last dropped
+    pub last_expiry: Option<Instant>,
+    /// Rows received since the session started, including those since dropped
+    pub received: usize,
+}
+
+/// Column choices of a data set while another tab is shown
+#[derive(Debug, Clone, Default)]
+pub struct TabView {
+    pub x_index: usize,
+    pub use_row_index: bool,
+    pub y_indices: Vec<usize>,
+}
+
+/// An open data set, shown as a tab. The active tab's data is `AppState::data`; the others
+/// are parked here with the columns they were last plotted with.
+pub struct DatasetTab {
+    pub id: u64,
+    /// Label: the file name, or the source of live data
+    pub name: String,
+    pub file: Option<PathBuf>,
+    /// Data and column choices while the tab is not active
+    pub parked: Option<(DataSource, TabView)>,
+}
+
+/// A computed column as the user wrote it, saved with the session and added to every
+/// data set that has the columns it uses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComputedColumn {
+    pub name: String,
+    pub expression: String,
+}
 
 /// Main application state container
 pub struct AppState {
@@ -35,12 +139,27 @@ pub struct AppState {
     /// UI interaction state
     pub ui: UiState,
 
+    /// Open data sets, in tab order
+    pub tabs: Vec<DatasetTab>,
+
+    /// Index into `tabs` of the one whose data is `data`
+    pub active_tab: usize,
+
+    /// Id for the next tab opened
+    pub next_tab_id: u64,
+
     /// Currently loaded file path
     pub current_file: Option<PathBuf>,
 
     /// Recently opened files
     pub recent_files: Vec<PathBuf>,
 
+    /// Import options chosen per delimited file, reused when it is opened again
+    pub import_options: HashMap<PathBuf, CsvOptions>,
+
+    /// Computed column definitions, in the order added
+    pub computed_columns: Vec<ComputedColumn>,
+
     /// Performance cache for outlier statistics (column_idx -> (mean, std_dev))
     pub outlier_stats_cache: HashMap<usize, (f64, f64)>,
     
@@ -54,8 +173,20 @@ pub struct AppState {
     /// Pre-allocated plot buffer
     #[allow(dead_code)]
     pub plot_buffer: PlotBuffer,
-    /// Loading indicator
-    pub is_loading: bool,
+    /// File load in progress, if any
+    pub loading: Option<LoadingState>,
+    /// Id of the most recent load request
+    pub load_id: u64,
+    /// Stream every file from disk, not just those over the size threshold
+    pub always_stream: bool,
+    /// Follow mode for a growing file
+    pub follow: FollowState,
+    /// Live session reading from a socket or stdin
+    pub live: Option<LiveSession>,
+    /// Settings of the last live session, offered when starting the next
+    pub live_config: LiveConfig,
+    /// Combination of data sets being collected on the worker: its id and tab name
+    pub combining: Option<(u64, String)>,
     /// Show profiler window
     pub show_profiler: bool,
 }
@@ -68,14 +199,25 @@ impl Default for AppState {
             spc: SpcConfig::default(),
             filters: FilterConfig::default(),
             ui: UiState::default(),
+            tabs: Vec::new(),
+            active_tab: 0,
+            next_tab_id: 0,
             current_file: None,
             recent_files: Vec::new(),
+            import_options: HashMap::new(),
+            computed_columns: Vec::new(),
             outlier_stats_cache: HashMap::new(),
             lttb_cache: LttbCache::default(),
             downsampler: AdaptiveDownsampler::default(),
             worker: BackgroundWorker::spawn(),
             plot_buffer: PlotBuffer::default(),
-            is_loading: false,
+            loading: None,
+            load_id: 0,
+            always_stream: false,
+            follow: FollowState::default(),
+            live: None,
+            live_config: LiveConfig::default(),
+            combining: None,
             show_profiler: false,
         }
     }
@@ -93,6 +235,31 @@ impl AppState {
         *self = Self::default();
     }
 
+    /// Id of the tab shown
+    pub fn active_tab_id(&self) -> Option<u64> {
+        self.tabs.get(self.active_tab).map(|tab| tab.id)
+    }
+
+    /// Data of the tab with `id`, whether it is shown or parked
+    pub fn dataset(&self, id: u64) -> Option<&DataSource> {
+        let index = self.tabs.iter().position(|tab| tab.id == id)?;
+        match &self.tabs[index].parked {
+            Some((data, _)) => Some(data),
+            None if index == self.active_tab => self.data.as_ref(),
+            None => None,
+        }
+    }
+
+    /// Mutable data of the tab with `id`, whether it is shown or parked
+    pub fn dataset_mut(&mut self, id: u64) -> Option<&mut DataSource> {
+        let index = self.tabs.iter().position(|tab| tab.id == id)?;
+        match &mut self.tabs[index].parked {
+            Some((data, _)) => Some(data),
+            None if index == self.active_tab => self.data.as_mut(),
+            None => None,
+        }
+    }
+
     /// Check if data is loaded
     pub fn has_data(&self) -> bool {
         self.data.is_some()
@@ -110,7 +277,7 @@ impl AppState {
     pub fn row_count(&self) -> usize {
         self.data
             .as_ref()
-            .map(|ds| ds.dataframe().height())
+            .map(|ds| ds.height())
             .unwrap_or(0)
     }
 
@@ -3,15 +3,168 @@
 #![allow(dead_code)]
 
 use crate::constants::spc::*;
+use crate::data::rules::{RuleId, RuleSet};
+use crate::data::stats::DistributionKind;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// Western Electric (WE) rule violation record
+/// Run rule (Nelson / Western Electric) violation record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WEViolation {
     /// Data point index
     pub point_index: usize,
     /// Which rules were violated
-    pub rules: Vec<String>,
+    pub rules: Vec<RuleId>,
+}
+
+/// How a stratified series is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StrataLayout {
+    /// All strata on one plot, each with its own limits
+    #[default]
+    Overlay,
+    /// One plot per stratum with linked X axes
+    SmallMultiples,
+}
+
+/// Out-of-control signal from a time-weighted chart (CUSUM, EWMA)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChartSignal {
+    /// Data row index of the signalling point
+    pub point_index: usize,
+    /// Chart statistic at the signal
+    pub value: f64,
+}
+
+/// Tabular CUSUM chart with upper and lower cumulative sums
+#[derive(Debug, Clone, Default)]
+pub struct CusumChart {
+    /// Upper cumulative sum C+ per point
+    pub upper: Vec<[f64; 2]>,
+    /// Lower cumulative sum C- per point (stored as a positive value)
+    pub lower: Vec<[f64; 2]>,
+    /// Target value the sums are accumulated around
+    pub target: f64,
+    /// Decision interval H = h * sigma
+    pub decision_interval: f64,
+    /// Indices of points where either sum exceeds H
+    pub signals: Vec<usize>,
+}
+
+/// Centre line and control limits for a single control chart pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ControlLimits {
+    /// Centre line
+    pub center: f64,
+    /// Upper control limit
+    pub ucl: f64,
+    /// Lower control limit
+    pub lcl: f64,
+}
+
+impl ControlLimits {
+    /// Check if a value falls outside the control limits
+    pub fn is_out_of_control(&self, value: f64) -> bool {
+        value > self.ucl || value < self.lcl
+    }
+}
+
+/// Phase I limits frozen from a baseline row range
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FrozenLimits {
+    /// First baseline row (inclusive)
+    pub baseline_start: usize,
+    /// Last baseline row (exclusive)
+    pub baseline_end: usize,
+    /// Baseline mean (centre line)
+    pub mean: f64,
+    /// Baseline standard deviation
+    pub std_dev: f64,
+    /// Baseline defects per inspected unit, when a sample size column was set
+    #[serde(default)]
+    pub per_unit: Option<f64>,
+}
+
+impl FrozenLimits {
+    /// Control limits at the given sigma multiplier
+    pub fn limits(&self, sigma_multiplier: f64) -> ControlLimits {
+        ControlLimits {
+            center: self.mean,
+            ucl: self.mean + sigma_multiplier * self.std_dev,
+            lcl: self.mean - sigma_multiplier * self.std_dev,
+        }
+    }
+}
+
+/// Attribute control chart (p, np, c, u) with per-point limits
+///
+/// Limits are stored per point so variable sample sizes can be drawn stair-stepped.
+#[derive(Debug, Clone, Default)]
+pub struct AttributeChart {
+    /// Plotted statistic per sample (proportion, count or rate)
+    pub points: Vec<[f64; 2]>,
+    /// Centre line (p-bar, np-bar, c-bar or u-bar)
+    pub center: f64,
+    /// Upper control limit per point
+    pub ucl: Vec<f64>,
+    /// Lower control limit per point (clamped at zero)
+    pub lcl: Vec<f64>,
+}
+
+impl AttributeChart {
+    /// Check if the limits are the same for every point (fixed sample size)
+    pub fn has_constant_limits(&self) -> bool {
+        let constant = |v: &[f64]| v.windows(2).all(|w| (w[0] - w[1]).abs() < f64::EPSILON);
+        constant(&self.ucl) && constant(&self.lcl)
+    }
+
+    /// Indices of points beyond their own control limits
+    pub fn out_of_control(&self) -> Vec<usize> {
+        self.points.iter()
+            .enumerate()
+            .filter(|&(i, p)| p[1] > self.ucl[i] || p[1] < self.lcl[i])
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// EWMA control chart with time-varying limits
+#[derive(Debug, Clone, Default)]
+pub struct EwmaChart {
+    /// EWMA statistic z_i per point
+    pub points: Vec<[f64; 2]>,
+    /// Centre line (process mean)
+    pub center: f64,
+    /// Upper control limit per point
+    pub ucl: Vec<f64>,
+    /// Lower control limit per point
+    pub lcl: Vec<f64>,
+}
+
+impl EwmaChart {
+    /// Indices of EWMA points beyond their control limits
+    pub fn out_of_control(&self) -> Vec<usize> {
+        self.points.iter()
+            .enumerate()
+            .filter(|&(i, p)| p[1] > self.ucl[i] || p[1] < self.lcl[i])
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Location + dispersion chart pair for variables data (I-MR, X-bar R, ...)
+#[derive(Debug, Clone, Default)]
+pub struct VariablesChart {
+    /// Location statistic per point (individual value or subgroup mean)
+    pub location: Vec<[f64; 2]>,
+    /// Limits for the location chart
+    pub location_limits: ControlLimits,
+    /// Dispersion statistic per point (moving range, range, ...)
+    pub dispersion: Vec<[f64; 2]>,
+    /// Limits for the dispersion chart
+    pub dispersion_limits: ControlLimits,
+    /// Estimated within-process sigma used for the location limits
+    pub sigma: f64,
 }
 
 /// SPC configuration manages all Statistical Process Control features
@@ -48,6 +201,9 @@ pub struct SpcConfig {
     /// EWMA lambda/smoothing constant (default: 0.2)
     pub ewma_lambda: f64,
 
+    /// EWMA control limit width L in sigma units (default: 3.0)
+    pub ewma_l: f64,
+
     // Regression
     /// Show regression line overlay
     pub show_regression: bool,
@@ -62,9 +218,19 @@ pub struct SpcConfig {
     /// Detected WE rule violations
     pub we_violations: Vec<WEViolation>,
 
+    /// Enabled run rules and their run lengths
+    pub rules: RuleSet,
+
     /// Row indices with excursions/violations
     pub excursion_rows: Vec<usize>,
 
+    // Phase I / Phase II
+    /// Frozen Phase I limits keyed by column name
+    pub frozen_limits: HashMap<String, FrozenLimits>,
+
+    /// Baseline row range used when freezing limits (start inclusive, end exclusive)
+    pub baseline_rows: (usize, usize),
+
     // Capability analysis
     /// Show process capability metrics (Cp, Cpk)
     pub show_capability: bool,
@@ -75,12 +241,62 @@ pub struct SpcConfig {
     /// Upper specification limit (USL)
     pub spec_upper: f64,
 
+    /// Capability target value for Cpm (None = no target)
+    pub capability_target: Option<f64>,
+
+    /// Subgroup size for within sigma (1 = moving range of individuals)
+    pub capability_subgroup_size: usize,
+
+    /// Distribution for percentile capability (None = best fit by Anderson-Darling)
+    pub capability_distribution: Option<DistributionKind>,
+
     // Subgroup analysis
-    /// X-bar R chart subgroup size (default: 5)
+    /// X-bar R and X-bar S chart subgroup size (default: 5); R constants exist for 2-10
     pub xbarr_subgroup_size: usize,
 
     /// p-chart sample size (default: 50)
     pub pchart_sample_size: usize,
+
+    /// u-chart units inspected per sample when there is no sample size column (default: 1.0)
+    pub uchart_units: f64,
+
+    // CUSUM
+    /// CUSUM reference value k in sigma units (default: 0.5)
+    pub cusum_k: f64,
+
+    /// CUSUM decision interval h in sigma units (default: 5.0)
+    pub cusum_h: f64,
+
+    /// CUSUM target value (None = use the sample mean)
+    pub cusum_target: Option<f64>,
+
+    /// Signals from the active CUSUM/EWMA/T² chart
+    pub chart_signals: Vec<ChartSignal>,
+
+    /// Optional per-row sample size column for p and u charts (None = fixed `pchart_sample_size`
+    /// or `uchart_units`)
+    pub sample_size_column: Option<usize>,
+
+    // Hotelling T²
+    /// Estimate the T² mean vector and covariance from `baseline_rows` instead of all rows
+    pub hotelling_use_baseline: bool,
+
+    // Stratification
+    /// Category column that splits the first Y series into strata (None = pooled)
+    pub strata_column: Option<usize>,
+
+    /// Overlay or small-multiples display of strata
+    pub strata_layout: StrataLayout,
+
+    // Gage R&R
+    /// Column identifying the measured part
+    pub gage_part_column: Option<usize>,
+
+    /// Column identifying the operator (appraiser)
+    pub gage_operator_column: Option<usize>,
+
+    /// Measurement column (None = first selected Y series)
+    pub gage_measurement_column: Option<usize>,
 }
 
 impl Default for SpcConfig {
@@ -102,6 +318,7 @@ impl Default for SpcConfig {
             // EWMA
             show_ewma: false,
             ewma_lambda: DEFAULT_EWMA_LAMBDA,
+            ewma_l: DEFAULT_EWMA_L,
 
             // Regression
             show_regression: false,
@@ -110,16 +327,44 @@ impl Default for SpcConfig {
             // Western Electric rules
             show_we_rules: false,
             we_violations: Vec::new(),
+            rules: RuleSet::default(),
             excursion_rows: Vec::new(),
 
+            // Phase I / Phase II
+            frozen_limits: HashMap::new(),
+            baseline_rows: (0, DEFAULT_BASELINE_ROWS),
+
             // Capability
             show_capability: false,
             spec_lower: DEFAULT_SPEC_LOWER,
             spec_upper: DEFAULT_SPEC_UPPER,
+            capability_target: None,
+            capability_subgroup_size: DEFAULT_CAPABILITY_SUBGROUP,
+            capability_distribution: None,
 
             // Subgroup analysis
             xbarr_subgroup_size: DEFAULT_XBARR_SUBGROUP,
             pchart_sample_size: DEFAULT_PCHART_SAMPLE,
+            uchart_units: DEFAULT_UCHART_UNITS,
+            sample_size_column: None,
+
+            // CUSUM
+            cusum_k: DEFAULT_CUSUM_K,
+            cusum_h: DEFAULT_CUSUM_H,
+            cusum_target: None,
+            chart_signals: Vec::new(),
+
+            // Hotelling T²
+            hotelling_use_baseline: false,
+
+            // Stratification
+            strata_column: None,
+            strata_layout: StrataLayout::default(),
+
+            // Gage R&R
+            gage_part_column: None,
+            gage_operator_column: None,
+            gage_measurement_column: None,
         }
     }
 }
@@ -133,6 +378,7 @@ impl SpcConfig {
     /// Clear all WE rule violations
     pub fn clear_violations(&mut self) {
         self.we_violations.clear();
+        self.chart_signals.clear();
     }
 
     /// Check if any SPC features are enabled
@@ -162,6 +408,8 @@ impl SpcConfig {
 
         // Clamp EWMA lambda to (0, 1)
         self.ewma_lambda = self.ewma_lambda.clamp(0.01, 0.99);
+        self.ewma_l = self.ewma_l.clamp(1.0, 4.0);
+        self.rules.validate();
 
         // Ensure regression order is 1 or 2
         if self.regression_order < 1 {
@@ -171,6 +419,7 @@ impl SpcConfig {
         }
 
         // Validate spec limits (LSL should be < USL)
+        self.capability_subgroup_size = self.capability_subgroup_size.clamp(1, 10);
         if self.spec_lower >= self.spec_upper {
             self.spec_upper = self.spec_lower + 1.0;
         }
@@ -182,5 +431,12 @@ impl SpcConfig {
         if self.pchart_sample_size < 2 {
             self.pchart_sample_size = 2;
         }
+        if self.uchart_units <= 0.0 || !self.uchart_units.is_finite() {
+            self.uchart_units = DEFAULT_UCHART_UNITS;
+        }
+
+        // Keep CUSUM parameters positive
+        self.cusum_k = self.cusum_k.clamp(0.0, 3.0);
+        self.cusum_h = self.cusum_h.clamp(0.5, 20.0);
     }
 }
@@ -2,7 +2,160 @@
 
 #![allow(dead_code)]
 
+use crate::data::capability::DistributionFit;
+use crate::data::formats::CsvOptions;
+use crate::data::combine::JoinKind;
+use crate::data::live::{LiveConfig, LiveSource, Retention};
+use crate::data::msa::{GageRR, MsaError};
+use crate::data::multivariate::{HotellingT2, MultivariateError};
+use crate::data::stats::NormalityTest;
+use polars::prelude::DataFrame;
 use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Part, operator and measurement column indices of a Gage R&R study
+pub type GageColumns = (usize, usize, usize);
+
+/// Y columns and Phase I baseline rows of a Hotelling T² chart
+pub type HotellingKey = (Vec<usize>, Option<std::ops::Range<usize>>);
+
+/// Import options dialog for a delimited file, open until the file is loaded or cancelled
+#[derive(Debug, Clone)]
+pub struct ImportDialog {
+    /// File being imported
+    pub path: PathBuf,
+    /// Options being edited
+    pub options: CsvOptions,
+    /// Null markers as typed (comma-separated)
+    pub null_input: String,
+    /// Preview rows (or the read error) and the options they were read with
+    pub preview: Option<(CsvOptions, Result<DataFrame, String>)>,
+}
+
+impl ImportDialog {
+    pub fn new(path: PathBuf, options: CsvOptions) -> Self {
+        let null_input = options.null_values.join(", ");
+        Self { path, options, null_input, preview: None }
+    }
+}
+
+/// Live source dialog, open until a session is started or the dialog is closed
+#[derive(Debug, Clone)]
+pub struct LiveDialog {
+    /// Settings being edited
+    pub config: LiveConfig,
+    /// Socket address as typed, kept while stdin is selected
+    pub address: String,
+    /// Row and age limits as typed, kept while the other retention is selected
+    pub rows: usize,
+    pub seconds: f64,
+}
+
+impl LiveDialog {
+    pub fn new(config: LiveConfig) -> Self {
+        let address = match &config.source {
+            LiveSource::Tcp(addr) | LiveSource::Udp(addr) => addr.clone(),
+            LiveSource::Stdin => "127.0.0.1:5000".to_string(),
+        };
+        let (rows, seconds) = match config.retention {
+            Retention::Rows(rows) => (rows, crate::constants::plot::DEFAULT_FOLLOW_SECONDS),
+            Retention::Seconds(seconds) => (crate::constants::performance::DEFAULT_LIVE_ROWS, seconds),
+        };
+        Self { config, address, rows, seconds }
+    }
+}
+
+/// Computed column editor: the column being written
+#[derive(Debug, Clone, Default)]
+pub struct ComputedDialog {
+    pub name: String,
+    pub expression: String,
+}
+
+/// Ways of combining open data sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombineMode {
+    /// Stack data sets with the same columns
+    Concat,
+    /// Join two data sets side by side
+    Join,
+}
+
+/// Dialog for combining open data sets into a new tab
+#[derive(Debug, Clone)]
+pub struct CombineDialog {
+    pub mode: CombineMode,
+    /// Tabs to stack, by id, in tab order
+    pub stack: Vec<u64>,
+    /// Column naming the source of each stacked row
+    pub tag_column: String,
+    /// Tabs to join, by id
+    pub left: Option<u64>,
+    pub right: Option<u64>,
+    /// Key columns of the left and right tabs
+    pub left_on: String,
+    pub right_on: String,
+    pub kind: JoinKind,
+    /// Match each left row with the nearest right key instead of an equal one
+    pub nearest: bool,
+    /// Largest key distance matched by a nearest join (seconds for timestamps), if limited
+    pub tolerance: Option<f64>,
+}
+
+impl CombineDialog {
+    pub fn new(tabs: &[u64]) -> Self {
+        Self {
+            mode: CombineMode::Concat,
+            stack: tabs.to_vec(),
+            tag_column: "source".to_string(),
+            left: tabs.first().copied(),
+            right: tabs.get(1).copied(),
+            left_on: String::new(),
+            right_on: String::new(),
+            kind: JoinKind::Inner,
+            nearest: false,
+            tolerance: None,
+        }
+    }
+}
+
+/// Points read from disk for a streamed plot window
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamWindow {
+    /// Rows read
+    pub rows: std::ops::Range<usize>,
+    /// X column (None = row index) and Y columns the points were read for
+    pub columns: (Option<usize>, Vec<usize>),
+    /// Points per Y column
+    pub points: Vec<Vec<[f64; 2]>>,
+}
+
+/// Cell text for a page of rows of a streamed table
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamPage {
+    /// First row of the page
+    pub start: usize,
+    /// Columns read, in display order
+    pub columns: Vec<usize>,
+    /// Cell text per column
+    pub cells: Vec<Vec<String>>,
+}
+
+/// Normal probability plot of one column, thinned for drawing
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbabilitySeries {
+    /// Legend name, with the normality test results
+    pub name: String,
+    /// Most points drawn; the series is rebuilt if the downsample threshold changes
+    pub max_points: usize,
+    /// (normal score, ordered value) pairs
+    pub points: Vec<[f64; 2]>,
+    /// Fitted normal line
+    pub line: Vec<[f64; 2]>,
+    /// Pointwise 95% band around the line
+    pub lower: Vec<[f64; 2]>,
+    pub upper: Vec<[f64; 2]>,
+}
 
 /// Active panel in the Focus Mode layout
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -35,6 +188,7 @@ pub struct CachedStats {
     pub p95: f64,
     pub histogram: Vec<u32>,  // Bin counts for sparkline
     pub histogram_max: u32,   // Max bin count for scaling
+    pub normality: Option<NormalityTest>, // Anderson-Darling test against a fitted normal
 }
 
 /// Table state with pre-computed filter/sort indices
@@ -138,6 +292,48 @@ pub struct UiState {
     
     /// Data version counter (increments on load)
     pub data_version: u64,
+
+    /// Cached distribution fits per column for non-normal capability (cleared on load)
+    pub fit_cache: std::collections::HashMap<usize, Vec<DistributionFit>>,
+
+    /// Cached probability plot per column, None if it has too few values (cleared on load)
+    pub probability_cache: std::collections::HashMap<usize, Option<ProbabilitySeries>>,
+
+    /// Cached Gage R&R study and the columns it was run on (cleared on load)
+    pub gage_cache: Option<(GageColumns, Result<GageRR, MsaError>)>,
+
+    /// Cached T² chart and the columns and baseline it was built from (cleared on load)
+    pub hotelling_cache: Option<(HotellingKey, Result<HotellingT2, MultivariateError>)>,
+
+    /// Point selected on the T² chart for contribution decomposition
+    pub t2_selected: Option<usize>,
+
+    /// Open import options dialog, if any
+    pub import_dialog: Option<ImportDialog>,
+
+    /// Open live source dialog, if any
+    pub live_dialog: Option<LiveDialog>,
+
+    /// Open dialog for combining data sets, if any
+    pub combine_dialog: Option<CombineDialog>,
+
+    /// Open computed column editor, if any
+    pub computed_dialog: Option<ComputedDialog>,
+
+    /// Filter expression being typed in the filter bar
+    pub filter_input: String,
+
+    /// Why the filter last typed was not added
+    pub filter_error: Option<String>,
+
+    /// Streamed columns whose summaries the worker is building
+    pub pending_summaries: HashSet<usize>,
+
+    /// Rows read from disk for the streamed plot's current window (cleared on load)
+    pub stream_window: Option<StreamWindow>,
+
+    /// Page of rows read from disk for the streamed data table (cleared on load)
+    pub stream_page: Option<StreamPage>,
 }
 
 impl UiState {
@@ -154,6 +350,20 @@ impl UiState {
             stats_cache: std::collections::HashMap::new(),
             stats_cache_version: 0,
             data_version: 0,
+            fit_cache: std::collections::HashMap::new(),
+            probability_cache: std::collections::HashMap::new(),
+            gage_cache: None,
+            hotelling_cache: None,
+            t2_selected: None,
+            import_dialog: None,
+            live_dialog: None,
+            combine_dialog: None,
+            computed_dialog: None,
+            filter_input: String::new(),
+            filter_error: None,
+            pending_summaries: HashSet::new(),
+            stream_window: None,
+            stream_page: None,
         }
     }
     
@@ -162,9 +372,35 @@ impl UiState {
         self.data_version += 1;
         self.stats_cache.clear();
         self.stats_cache_version = 0;
+        self.fit_cache.clear();
+        self.probability_cache.clear();
+        self.gage_cache = None;
+        self.hotelling_cache = None;
+        self.t2_selected = None;
+        self.pending_summaries.clear();
+        self.stream_window = None;
+        self.stream_page = None;
         self.table.invalidate();
     }
 
+    /// Drop results computed from the data before rows were appended to it. The data
+    /// version stays the same, so downsampled windows of the unchanged rows stay valid.
+    pub fn on_data_appended(&mut self) {
+        self.stats_cache.clear();
+        self.fit_cache.clear();
+        self.probability_cache.clear();
+        self.gage_cache = None;
+        self.hotelling_cache = None;
+        self.table.invalidate();
+    }
+
+    /// Drop results indexed by row after rows were removed from the front. As with
+    /// appended rows, the data version stays the same.
+    pub fn on_rows_dropped(&mut self) {
+        self.on_data_appended();
+        self.t2_selected = None;
+    }
+
     /// Toggle a specific panel
     pub fn toggle_panel(&mut self, panel: ActivePanel) {
         if self.active_panel == panel {
@@ -6,13 +6,36 @@ use serde::{Deserialize, Serialize};
 
 /// Plot mode enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum PlotMode {
     Scatter,
     Histogram,
     BoxPlot,
     Pareto,
     XbarR,
+    /// X-bar and S chart for larger subgroups
+    XbarS,
+    /// Individuals & Moving Range chart
+    IMR,
     PChart,
+    /// Tabular CUSUM chart
+    Cusum,
+    /// EWMA control chart with time-varying limits
+    Ewma,
+    /// Capability histogram with spec limits and fitted normal curves
+    Capability,
+    /// Normal Q-Q plot with fitted reference line and confidence bands
+    ProbabilityPlot,
+    /// Crossed Gage R&R study (ANOVA method)
+    GageRR,
+    /// Hotelling T² chart over all selected Y series
+    HotellingT2,
+    /// Number defective chart (fixed sample size)
+    NpChart,
+    /// Defect count chart (constant area of opportunity)
+    CChart,
+    /// Defects per unit chart
+    UChart,
 }
 
 impl Default for PlotMode {
@@ -35,6 +58,15 @@ impl Default for LineStyle {
     }
 }
 
+/// A Y column of another open data set drawn over the current plot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlaySeries {
+    /// Tab holding the data set
+    pub tab: u64,
+    /// Column name
+    pub column: String,
+}
+
 /// Layout mode for responsive design
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -60,6 +92,10 @@ pub struct ViewState {
     /// Multiple Y series column indices
     pub y_indices: Vec<usize>,
 
+    /// Series from other tabs overlaid on the plot, aligned on the X column of the same
+    /// name (or on row index when that is the X axis)
+    pub overlays: Vec<OverlaySeries>,
+
     // Display options
     /// Dark mode theme toggle
     pub dark_mode: bool,
@@ -138,6 +174,7 @@ impl Default for ViewState {
             x_index: 0,
             use_row_index: false,
             y_indices: Vec::new(),
+            overlays: Vec::new(),
 
             // Display options
             dark_mode: true,
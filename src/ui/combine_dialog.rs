@@ -0,0 +1,164 @@
+use crate::app::PlotOxide;
+use crate::data::combine::{self, JoinKind, JoinOn};
+use crate::state::CombineMode;
+use eframe::egui::{self, ComboBox};
+
+pub fn render_combine_dialog(app: &mut PlotOxide, ctx: &egui::Context) {
+    let Some(mut dialog) = app.state.ui.combine_dialog.take() else {
+        return;
+    };
+
+    // Tabs that can take part: ids, names and columns
+    let tabs: Vec<(u64, String, Vec<String>)> = app.state.tabs.iter()
+        .filter_map(|tab| Some((tab.id, tab.name.clone(), app.state.dataset(tab.id)?.column_names())))
+        .collect();
+    let tab_name = |id: Option<u64>| tabs.iter().find(|(t, _, _)| Some(*t) == id).map_or("", |(_, name, _)| name.as_str());
+    let tab_columns = |id: Option<u64>| tabs.iter().find(|(t, _, _)| Some(*t) == id).map(|(_, _, c)| c.clone()).unwrap_or_default();
+
+    let mut open = true;
+    let mut combine = false;
+    let mut cancel = false;
+
+    egui::Window::new("⊕ Combine Data Sets")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut dialog.mode, CombineMode::Concat, "Stack")
+                    .on_hover_text("Append the rows of files with the same columns");
+                ui.radio_value(&mut dialog.mode, CombineMode::Join, "Join")
+                    .on_hover_text("Put the columns of two data sets side by side, matching rows on a key");
+            });
+            ui.separator();
+
+            match dialog.mode {
+                CombineMode::Concat => {
+                    for (id, name, _) in &tabs {
+                        let mut selected = dialog.stack.contains(id);
+                        if ui.checkbox(&mut selected, name).changed() {
+                            dialog.stack.retain(|t| t != id);
+                            if selected {
+                                dialog.stack.push(*id);
+                            }
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Source column:");
+                        ui.text_edit_singleline(&mut dialog.tag_column)
+                            .on_hover_text("Names the data set each row came from");
+                    });
+                }
+                CombineMode::Join => {
+                    egui::Grid::new("combine_join").num_columns(3).spacing([12.0, 6.0]).show(ui, |ui| {
+                        for (label, tab, key, salt) in [
+                            ("Left:", &mut dialog.left, &mut dialog.left_on, "combine_left"),
+                            ("Right:", &mut dialog.right, &mut dialog.right_on, "combine_right"),
+                        ] {
+                            ui.label(label);
+                            ComboBox::from_id_salt(salt).selected_text(tab_name(*tab)).show_ui(ui, |ui| {
+                                for (id, name, _) in &tabs {
+                                    ui.selectable_value(tab, Some(*id), name);
+                                }
+                            });
+                            let columns = tab_columns(*tab);
+                            if !columns.contains(key) {
+                                *key = columns.first().cloned().unwrap_or_default();
+                            }
+                            ComboBox::from_id_salt((salt, "key")).selected_text(key.as_str()).show_ui(ui, |ui| {
+                                for column in columns {
+                                    ui.selectable_value(key, column.clone(), column);
+                                }
+                            });
+                            ui.end_row();
+                        }
+
+                        ui.label("Match:");
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut dialog.nearest, false, "Equal keys");
+                            ui.radio_value(&mut dialog.nearest, true, "Nearest key")
+                                .on_hover_text("As-of join: each left row takes the right row with the closest key");
+                        });
+                        ui.end_row();
+
+                        if dialog.nearest {
+                            ui.label("Tolerance:");
+                            ui.horizontal(|ui| {
+                                let mut limited = dialog.tolerance.is_some();
+                                if ui.checkbox(&mut limited, "Within").changed() {
+                                    dialog.tolerance = limited.then_some(1.0);
+                                }
+                                if let Some(tolerance) = dialog.tolerance.as_mut() {
+                                    ui.add(egui::DragValue::new(tolerance).range(0.0..=f64::MAX).speed(0.1))
+                                        .on_hover_text("In key units; seconds for timestamps");
+                                }
+                            });
+                        } else {
+                            ui.label("Keep:");
+                            ComboBox::from_id_salt("combine_kind").selected_text(dialog.kind.name()).show_ui(ui, |ui| {
+                                for kind in JoinKind::ALL {
+                                    ui.selectable_value(&mut dialog.kind, kind, kind.name());
+                                }
+                            });
+                        }
+                        ui.end_row();
+                    });
+                }
+            }
+
+            ui.separator();
+            ui.weak("The result opens in a new tab.");
+            ui.horizontal(|ui| {
+                let ready = match dialog.mode {
+                    CombineMode::Concat => dialog.stack.len() >= 2 && !dialog.tag_column.trim().is_empty(),
+                    CombineMode::Join => dialog.left.is_some() && dialog.right.is_some(),
+                };
+                if ui.add_enabled(ready, egui::Button::new("Combine")).clicked() {
+                    combine = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if combine {
+        let planned = match dialog.mode {
+            CombineMode::Concat => {
+                // Stacked in tab order
+                let parts: Vec<(&str, &crate::data::DataSource)> = tabs.iter()
+                    .filter(|(id, _, _)| dialog.stack.contains(id))
+                    .filter_map(|(id, name, _)| Some((name.as_str(), app.state.dataset(*id)?)))
+                    .collect();
+                let name = parts.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(" + ");
+                combine::concat_plan(&parts, dialog.tag_column.trim()).map(|plan| (name, plan))
+            }
+            CombineMode::Join => {
+                let (left, right) = (dialog.left.and_then(|id| app.state.dataset(id)), dialog.right.and_then(|id| app.state.dataset(id)));
+                let on = if dialog.nearest {
+                    JoinOn::Nearest { tolerance: dialog.tolerance }
+                } else {
+                    JoinOn::Key(dialog.kind)
+                };
+                let name = format!("{} ⋈ {}", tab_name(dialog.left), tab_name(dialog.right));
+                match (left, right) {
+                    (Some(left), Some(right)) => combine::join_plan(left, right, &dialog.left_on, &dialog.right_on, on)
+                        .map(|plan| (name, plan)),
+                    _ => Err(crate::data::DataError::SchemaMismatch("pick two data sets".to_string())),
+                }
+            }
+        };
+        match planned {
+            Ok((name, plan)) => {
+                app.combine(name, plan);
+                return;
+            }
+            // The dialog stays open to fix the choice
+            Err(e) => app.state.ui.set_error(e.to_string()),
+        }
+    }
+    if open && !cancel {
+        app.state.ui.combine_dialog = Some(dialog);
+    }
+}
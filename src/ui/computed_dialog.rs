@@ -0,0 +1,113 @@
+use crate::app::PlotOxide;
+use crate::data::expr;
+use eframe::egui;
+
+pub fn render_computed_dialog(app: &mut PlotOxide, ctx: &egui::Context) {
+    let Some(mut dialog) = app.state.ui.computed_dialog.take() else {
+        return;
+    };
+    let Some(ds) = app.state.data.as_ref() else {
+        return;
+    };
+    let columns = ds.column_names();
+    let computed: Vec<String> = ds.computed_columns().map(str::to_string).collect();
+    let definitions = app.state.computed_columns.clone();
+
+    // Checked as it is typed
+    let parsed = (!dialog.expression.trim().is_empty()).then(|| expr::parse(&dialog.expression, &columns));
+    let name = dialog.name.trim().to_string();
+    let name_taken = columns.contains(&name);
+
+    let mut open = true;
+    let mut add = false;
+    let mut remove = None;
+    let mut close = false;
+
+    egui::Window::new("ƒx Computed Columns")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            if definitions.is_empty() {
+                ui.weak("No computed columns yet.");
+            } else {
+                egui::Grid::new("computed_columns").num_columns(3).spacing([12.0, 4.0]).striped(true).show(ui, |ui| {
+                    for definition in &definitions {
+                        if computed.contains(&definition.name) {
+                            ui.label(&definition.name);
+                        } else {
+                            ui.weak(&definition.name)
+                                .on_hover_text("Not in this data set: it lacks a column used, or has one with this name");
+                        }
+                        ui.monospace(&definition.expression);
+                        if ui.small_button("✕").on_hover_text("Remove from all data sets").clicked() {
+                            remove = Some(definition.name.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            }
+            ui.separator();
+
+            egui::Grid::new("computed_new").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut dialog.name);
+                ui.end_row();
+
+                ui.label("Expression:");
+                ui.add(egui::TextEdit::singleline(&mut dialog.expression)
+                    .code_editor()
+                    .desired_width(320.0)
+                    .hint_text("(temp_out - temp_in) / flow"));
+                ui.end_row();
+            });
+
+            match &parsed {
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), e.to_string());
+                }
+                _ if name_taken => {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), format!("There is already a column named '{}'", name));
+                }
+                _ => {}
+            }
+
+            egui::CollapsingHeader::new("Syntax").show(ui, |ui| {
+                ui.label("Columns by name, in `backticks` if the name has spaces or symbols.");
+                ui.label("Operators: + - * / % ^, comparisons == != < <= > >=, and / or / not.");
+                ui.horizontal_wrapped(|ui| {
+                    for (_, signature) in expr::FUNCTIONS {
+                        ui.monospace(*signature);
+                    }
+                });
+                ui.weak("lag, diff, cumsum and rolling windows look back over earlier rows.");
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let ready = matches!(parsed, Some(Ok(_))) && !name.is_empty() && !name_taken;
+                if ui.add_enabled(ready, egui::Button::new("Add")).clicked() {
+                    add = true;
+                }
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
+            ui.weak("Definitions are saved with the config.");
+        });
+
+    if let Some(name) = remove {
+        app.remove_computed_column(&name);
+    }
+    if add {
+        let expression = dialog.expression.trim().to_string();
+        match app.add_computed_column(&name, &expression) {
+            Ok(()) => dialog = Default::default(),
+            Err(e) => app.state.ui.set_error(e.to_string()),
+        }
+    }
+    if open && !close {
+        app.state.ui.computed_dialog = Some(dialog);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app::PlotOxide;
+use crate::state::StreamPage;
 use egui_extras::{Column, TableBuilder};
 
 /// Recompute filtered and sorted row indices
@@ -85,6 +86,11 @@ pub fn render_data_table_panel(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
     display_cols.sort_unstable();
     display_cols.dedup();
 
+    if ds.is_streaming() {
+        render_streaming_table(app, ui, &headers, &display_cols);
+        return;
+    }
+
     // Filter/Search controls
     ui.horizontal(|ui| {
         ui.label("🔍");
@@ -334,6 +340,70 @@ pub fn render_data_table_panel(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
     }
 }
 
+/// Table over a file streamed from disk, reading rows a page at a time as they scroll into
+/// view. Filtering and sorting need the whole file in memory, so they are not offered.
+fn render_streaming_table(app: &mut PlotOxide, ui: &mut eframe::egui::Ui, headers: &[String], display_cols: &[usize]) {
+    use crate::constants::performance::STREAM_PAGE_ROWS;
+
+    let Some(ds) = app.state.data.as_ref() else {
+        return;
+    };
+    let total_rows = ds.height();
+    let page = &mut app.state.ui.stream_page;
+
+    ui.horizontal(|ui| {
+        ui.label(format!("{} rows", total_rows));
+        ui.separator();
+        ui.weak("Streaming from disk: filter and sort need the file loaded into memory");
+    });
+    ui.separator();
+
+    TableBuilder::new(ui)
+        .striped(true)
+        .cell_layout(eframe::egui::Layout::left_to_right(eframe::egui::Align::Center))
+        .column(Column::initial(45.0).resizable(false))
+        .columns(Column::initial(100.0).resizable(true).clip(true), display_cols.len())
+        .header(22.0, |mut header| {
+            header.col(|ui| {
+                ui.strong("#");
+            });
+            for &col_idx in display_cols {
+                header.col(|ui| {
+                    ui.strong(headers.get(col_idx).map(|s| s.as_str()).unwrap_or("?"));
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(18.0, total_rows, |mut row| {
+                let row_idx = row.index();
+                // The first visible row starts a fresh read, which covers the rest of the view
+                let stale = page.as_ref().is_none_or(|p| {
+                    p.columns != display_cols || row_idx < p.start || row_idx >= p.start + 2 * STREAM_PAGE_ROWS
+                });
+                if stale {
+                    let start = row_idx / STREAM_PAGE_ROWS * STREAM_PAGE_ROWS;
+                    let cells = ds.rows(start..start + 2 * STREAM_PAGE_ROWS, display_cols)
+                        .map(|df| df.get_columns().iter().map(crate::data::source::cell_strings).collect())
+                        .unwrap_or_default();
+                    *page = Some(StreamPage { start, columns: display_cols.to_vec(), cells });
+                }
+
+                row.col(|ui| {
+                    ui.label(format!("{}", row_idx + 1));
+                });
+                for i in 0..display_cols.len() {
+                    row.col(|ui| {
+                        let cell = page.as_ref()
+                            .and_then(|p| p.cells.get(i)?.get(row_idx - p.start))
+                            .map(|s| s.as_str())
+                            .unwrap_or("");
+                        ui.label(cell);
+                    });
+                }
+            });
+        });
+}
+
 /// Copy selected rows to clipboard as TSV
 fn copy_selected_rows(
     data_source: &crate::data::DataSource,
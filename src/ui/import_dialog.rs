@@ -0,0 +1,175 @@
+use crate::app::PlotOxide;
+use crate::constants::import::PREVIEW_ROWS;
+use crate::data::formats::{self, ColumnType};
+use crate::data::source::cell_strings;
+use eframe::egui::{self, ComboBox};
+use polars::prelude::*;
+
+/// Delimiter choices (None = sniff from content)
+const SEPARATORS: [(&str, Option<u8>); 6] = [
+    ("Auto-detect", None),
+    ("Comma", Some(b',')),
+    ("Tab", Some(b'\t')),
+    ("Semicolon", Some(b';')),
+    ("Pipe", Some(b'|')),
+    ("Space", Some(b' ')),
+];
+
+/// Quote character choices (None disables quoting)
+const QUOTES: [(&str, Option<u8>); 3] = [("\"", Some(b'"')), ("'", Some(b'\'')), ("None", None)];
+
+pub fn render_import_dialog(app: &mut PlotOxide, ctx: &egui::Context) {
+    let Some(mut dialog) = app.state.ui.import_dialog.take() else {
+        return;
+    };
+
+    // Re-read the preview whenever the options change
+    if dialog.preview.as_ref().is_none_or(|(options, _)| *options != dialog.options) {
+        let rows = formats::preview(&dialog.path, &dialog.options, PREVIEW_ROWS).map_err(|e| e.to_string());
+        dialog.preview = Some((dialog.options.clone(), rows));
+    }
+
+    let title = format!(
+        "📥 Import {}",
+        dialog.path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default()
+    );
+    let mut open = true;
+    let mut load = false;
+    let mut cancel = false;
+
+    egui::Window::new(title)
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(640.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            let options = &mut dialog.options;
+            egui::Grid::new("import_options").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Delimiter:");
+                let selected = SEPARATORS.iter().find(|(_, sep)| *sep == options.separator).map_or("Custom", |(name, _)| name);
+                ComboBox::from_id_salt("import_separator").selected_text(selected).show_ui(ui, |ui| {
+                    for (name, sep) in SEPARATORS {
+                        ui.selectable_value(&mut options.separator, sep, name);
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Quote char:");
+                let selected = QUOTES.iter().find(|(_, q)| *q == options.quote_char).map_or("Custom", |(name, _)| name);
+                ComboBox::from_id_salt("import_quote").selected_text(selected).show_ui(ui, |ui| {
+                    for (name, quote) in QUOTES {
+                        ui.selectable_value(&mut options.quote_char, quote, name);
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Skip rows:");
+                ui.add(egui::DragValue::new(&mut options.skip_rows).range(0..=1000))
+                    .on_hover_text("Preamble lines before the header");
+                ui.end_row();
+
+                ui.label("Header:");
+                ui.checkbox(&mut options.has_header, "First row holds column names");
+                ui.end_row();
+
+                ui.label("Decimal separator:");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut options.decimal_comma, false, "Point (1.5)");
+                    ui.radio_value(&mut options.decimal_comma, true, "Comma (1,5)");
+                });
+                ui.end_row();
+
+                ui.label("Null markers:");
+                if ui.add(egui::TextEdit::singleline(&mut dialog.null_input).hint_text("e.g. NA, -, #N/A"))
+                    .changed()
+                {
+                    options.null_values = dialog.null_input
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|v| !v.is_empty())
+                        .map(String::from)
+                        .collect();
+                }
+                ui.end_row();
+
+                ui.label("Type inference:");
+                ui.checkbox(&mut options.full_scan_inference, "Scan all rows (slower)")
+                    .on_hover_text("By default types are inferred from the first 100 rows");
+                ui.end_row();
+            });
+
+            ui.separator();
+            ui.label(egui::RichText::new(format!("Preview (first {} rows)", PREVIEW_ROWS)).strong());
+            match dialog.preview.as_ref().map(|(_, rows)| rows) {
+                Some(Ok(df)) => render_preview(ui, df, &mut dialog.options),
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, format!("⚠ {}", e));
+                }
+                None => {}
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let can_load = matches!(dialog.preview, Some((_, Ok(_))));
+                if ui.add_enabled(can_load, egui::Button::new("Load")).clicked() {
+                    load = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+                if ui.button("Reset").on_hover_text("Restore default options").clicked() {
+                    dialog = crate::state::ImportDialog::new(dialog.path.clone(), Default::default());
+                }
+            });
+        });
+
+    if load {
+        app.load_file_with_options(dialog.path, dialog.options);
+        return;
+    }
+    if open && !cancel {
+        app.state.ui.import_dialog = Some(dialog);
+    }
+}
+
+/// Preview grid with a type override selector above each column
+fn render_preview(ui: &mut egui::Ui, df: &DataFrame, options: &mut formats::CsvOptions) {
+    let columns: Vec<(String, String, Vec<String>)> = df.get_columns()
+        .iter()
+        .map(|col| {
+            (col.name().to_string(), col.dtype().to_string(), cell_strings(col))
+        })
+        .collect();
+
+    egui::ScrollArea::both().max_height(320.0).auto_shrink([false, true]).show(ui, |ui| {
+        egui::Grid::new("import_preview").striped(true).show(ui, |ui| {
+            for (name, dtype, _) in &columns {
+                ui.vertical(|ui| {
+                    ui.strong(name);
+                    let forced = options.dtype_overrides.get(name).copied();
+                    ComboBox::from_id_salt(("import_dtype", name))
+                        .selected_text(forced.map_or(format!("Auto ({})", dtype), |ty| ty.name().to_string()))
+                        .show_ui(ui, |ui| {
+                            if ui.selectable_label(forced.is_none(), "Auto").clicked() {
+                                options.dtype_overrides.remove(name);
+                            }
+                            for ty in ColumnType::ALL {
+                                if ui.selectable_label(forced == Some(ty), ty.name()).clicked() {
+                                    options.dtype_overrides.insert(name.clone(), ty);
+                                }
+                            }
+                        });
+                });
+            }
+            ui.end_row();
+
+            for row in 0..df.height() {
+                for (_, _, values) in &columns {
+                    ui.label(values.get(row).map(String::as_str).unwrap_or(""));
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
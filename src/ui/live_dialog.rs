@@ -0,0 +1,122 @@
+use crate::app::PlotOxide;
+use crate::data::live::{LineFormat, LiveSource, Retention};
+use eframe::egui::{self, ComboBox};
+
+/// Delimiter choices for CSV lines
+const SEPARATORS: [(&str, u8); 5] = [
+    ("Comma", b','),
+    ("Tab", b'\t'),
+    ("Semicolon", b';'),
+    ("Pipe", b'|'),
+    ("Space", b' '),
+];
+
+pub fn render_live_dialog(app: &mut PlotOxide, ctx: &egui::Context) {
+    let Some(mut dialog) = app.state.ui.live_dialog.take() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut start = false;
+    let mut cancel = false;
+
+    egui::Window::new("📡 Live Source")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            egui::Grid::new("live_options").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Source:");
+                ui.horizontal(|ui| {
+                    let config = &mut dialog.config;
+                    if ui.radio(matches!(config.source, LiveSource::Tcp(_)), "TCP").clicked() {
+                        config.source = LiveSource::Tcp(dialog.address.clone());
+                    }
+                    if ui.radio(matches!(config.source, LiveSource::Udp(_)), "UDP").clicked() {
+                        config.source = LiveSource::Udp(dialog.address.clone());
+                    }
+                    if ui.radio(config.source == LiveSource::Stdin, "stdin").clicked() {
+                        config.source = LiveSource::Stdin;
+                    }
+                });
+                ui.end_row();
+
+                if let LiveSource::Tcp(addr) | LiveSource::Udp(addr) = &mut dialog.config.source {
+                    ui.label("Listen on:");
+                    if ui.add(egui::TextEdit::singleline(&mut dialog.address).hint_text("127.0.0.1:5000"))
+                        .changed()
+                    {
+                        *addr = dialog.address.clone();
+                    }
+                    ui.end_row();
+                }
+
+                ui.label("Lines:");
+                ui.horizontal(|ui| {
+                    let format = &mut dialog.config.format;
+                    if ui.radio(matches!(format, LineFormat::Csv { .. }), "CSV").on_hover_text("The first line names the columns").clicked() {
+                        *format = LineFormat::Csv { separator: b',' };
+                    }
+                    if ui.radio(*format == LineFormat::Json, "JSON").on_hover_text("One object per line").clicked() {
+                        *format = LineFormat::Json;
+                    }
+                    if let LineFormat::Csv { separator } = format {
+                        let selected = SEPARATORS.iter().find(|(_, sep)| sep == separator).map_or("Custom", |(name, _)| name);
+                        ComboBox::from_id_salt("live_separator").selected_text(selected).show_ui(ui, |ui| {
+                            for (name, sep) in SEPARATORS {
+                                ui.selectable_value(separator, sep, name);
+                            }
+                        });
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Keep:");
+                ui.horizontal(|ui| {
+                    let retention = &mut dialog.config.retention;
+                    if ui.radio(matches!(retention, Retention::Rows(_)), "Last rows").clicked() {
+                        *retention = Retention::Rows(dialog.rows);
+                    }
+                    if ui.radio(matches!(retention, Retention::Seconds(_)), "Last seconds").clicked() {
+                        *retention = Retention::Seconds(dialog.seconds);
+                    }
+                    match retention {
+                        Retention::Rows(rows) => {
+                            if ui.add(egui::DragValue::new(&mut dialog.rows).range(2..=100_000_000)).changed() {
+                                *rows = dialog.rows;
+                            }
+                        }
+                        Retention::Seconds(seconds) => {
+                            if ui.add(egui::DragValue::new(&mut dialog.seconds).range(0.1..=f64::MAX).suffix(" s"))
+                                .on_hover_text("Rows are aged by when they arrived")
+                                .changed()
+                            {
+                                *seconds = dialog.seconds;
+                            }
+                        }
+                    }
+                });
+                ui.end_row();
+            });
+
+            ui.separator();
+            ui.weak("The first rows received replace the current data.");
+            ui.horizontal(|ui| {
+                if ui.button("Start").clicked() {
+                    start = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if start {
+        app.start_live(dialog.config);
+        return;
+    }
+    if open && !cancel {
+        app.state.ui.live_dialog = Some(dialog);
+    }
+}
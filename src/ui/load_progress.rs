@@ -0,0 +1,48 @@
+use crate::app::PlotOxide;
+use crate::data::formats::LoadProgress;
+use eframe::egui;
+
+/// Progress of the file load running on the background worker, with a cancel button
+pub fn render_load_progress(app: &mut PlotOxide, ctx: &egui::Context) {
+    let Some(loading) = app.state.loading.as_ref() else {
+        return;
+    };
+
+    let name = loading.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let (fraction, status) = match loading.progress {
+        Some(LoadProgress::Reading { bytes, total }) => (
+            (total > 0).then(|| bytes as f32 / total as f32),
+            format!("Reading {:.1} / {:.1} MB", bytes as f64 / 1e6, total as f64 / 1e6),
+        ),
+        Some(LoadProgress::Parsing { rows }) => (None, format!("Parsing… {} rows", rows)),
+        Some(LoadProgress::Summarising { rows, total }) => (
+            (total > 0).then(|| rows as f32 / total as f32),
+            format!("Summarising {} / {} rows", rows, total),
+        ),
+        None => (None, "Waiting…".to_string()),
+    };
+
+    let mut cancel = false;
+    egui::Window::new("Loading")
+        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -24.0])
+        .collapsible(false)
+        .resizable(false)
+        .title_bar(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("📄 {}", name));
+            });
+            match fraction {
+                Some(fraction) => ui.add(egui::ProgressBar::new(fraction).text(status).desired_width(280.0)),
+                None => ui.add(egui::ProgressBar::new(0.0).animate(true).text(status).desired_width(280.0)),
+            };
+            if ui.button("Cancel").clicked() {
+                cancel = true;
+            }
+        });
+
+    if cancel {
+        app.cancel_load();
+    }
+}
@@ -4,6 +4,11 @@ mod plot;
 mod stats_panel;
 mod data_table;
 mod help_dialog;
+mod import_dialog;
+mod combine_dialog;
+mod computed_dialog;
+mod live_dialog;
+mod load_progress;
 
 pub use toolbar::render_toolbar_and_controls;
 pub use series_panel::render_series_panel;
@@ -11,3 +16,8 @@ pub use plot::render_plot;
 pub use stats_panel::render_stats_panel;
 pub use data_table::render_data_table_panel;
 pub use help_dialog::render_help_dialog;
+pub use import_dialog::render_import_dialog;
+pub use combine_dialog::render_combine_dialog;
+pub use computed_dialog::render_computed_dialog;
+pub use live_dialog::render_live_dialog;
+pub use load_progress::render_load_progress;
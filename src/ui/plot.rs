@@ -1,11 +1,71 @@
 use crate::app::PlotOxide;
-use crate::state::{PlotMode, LineStyle};
+use crate::data::capability::{self, CapabilityReport, DistributionFit};
+use crate::data::msa::{self, GageRR};
+use crate::data::multivariate::{self, HotellingT2};
+use crate::data::pyramid::{self, Bucket, MinMaxPyramid};
+use crate::data::source::series_as_f64;
+use crate::data::DataError;
+use crate::data::strata;
+use crate::data::stats::{self, DistributionKind};
+use crate::state::{
+    PlotMode, LineStyle, FollowScroll, AttributeChart, ChartSignal, ControlLimits, StrataLayout, ProbabilitySeries, StreamWindow, VariablesChart,
+    WEViolation,
+};
+use crate::perf::LttbKey;
 use chrono::{DateTime, Utc}; // Removed TimeZone
-use egui_plot::{Bar, BarChart, BoxElem, BoxPlot, BoxSpread, HLine, Line, Plot, Points};
+use std::hash::{Hash, Hasher};
+use egui_plot::{Bar, BarChart, BoxElem, BoxPlot, BoxSpread, HLine, Line, Plot, PlotUi, Points, VLine};
 
 /// Render the main plot area
 pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut eframe::egui::Ui) {
     profiling::scope!("render_plot");
+
+    // Files streamed from disk are drawn from their column summaries
+    if app.state.data.as_ref().is_some_and(|ds| ds.is_streaming()) {
+        render_streaming_chart(app, ui);
+        return;
+    }
+
+    // Two-pane control charts have their own layout
+    match app.state.view.plot_mode {
+        PlotMode::IMR => {
+            render_imr_chart(app, ui);
+            return;
+        }
+        PlotMode::XbarS => {
+            render_xbars_chart(app, ui);
+            return;
+        }
+        PlotMode::Cusum => {
+            render_cusum_chart(app, ui);
+            return;
+        }
+        PlotMode::Ewma => {
+            render_ewma_chart(app, ui);
+            return;
+        }
+        PlotMode::Capability => {
+            render_capability_chart(app, ui);
+            return;
+        }
+        PlotMode::ProbabilityPlot => {
+            render_probability_plot(app, ui);
+            return;
+        }
+        PlotMode::GageRR => {
+            render_gage_rr_chart(app, ui);
+            return;
+        }
+        PlotMode::HotellingT2 => {
+            render_hotelling_chart(app, ui);
+            return;
+        }
+        PlotMode::Scatter if app.state.spc.strata_column.is_some() => {
+            render_stratified_chart(app, ui);
+            return;
+        }
+        _ => {}
+    }
     
     // Get data source directly to avoid materializing row-major data
     let ds = if let Some(ds) = &app.state.data {
@@ -78,17 +138,85 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
         }
     }
 
-    // Detect if user is currently interacting (Phase 4.3)
-    let is_dragging = ctx.input(|i| i.pointer.is_decidedly_dragging());
-
     // Extract filter parameters to avoid borrow conflicts
     let downsample_threshold = app.state.view.downsample_threshold;
     let use_row_index = app.state.view.use_row_index;
     let x_index = app.state.view.x_index;
     let y_indices = app.state.view.y_indices.clone();
 
+    // While following a growing file, the X range that keeps its newest rows in view
+    let n_rows = ds.height();
+    let follow = &app.state.follow;
+    let follow_x = if follow.enabled && app.state.view.plot_mode == PlotMode::Scatter && n_rows > 0 {
+        let x = (!use_row_index).then(|| ds.get_cached_column(x_index).ok()).flatten();
+        let x_at = |row: usize| x.as_ref().map_or(row as f64, |x| x[row]);
+        let last = x_at(n_rows - 1);
+        match follow.scroll {
+            FollowScroll::Off => None,
+            FollowScroll::LastPoints => Some((x_at(n_rows.saturating_sub(follow.points)), last)),
+            FollowScroll::LastSeconds => Some((last - follow.seconds, last)),
+        }
+    } else {
+        None
+    };
+    let follow_x = follow_x.filter(|(min, max)| min.is_finite() && max.is_finite() && max > min);
+
+    // X range shown last frame (None while auto-fitting), and everything besides the series
+    // and data version that shapes the plotted points, for keying downsampled windows
+    let plot_id = ui.make_persistent_id("plot");
+    let view_x = follow_x.or_else(|| {
+        egui_plot::PlotMemory::load(ctx, plot_id)
+            .filter(|memory| !memory.auto_bounds.x && !app.state.view.reset_bounds)
+            .map(|memory| (memory.bounds().min()[0], memory.bounds().max()[0]))
+    });
+    let data_version = app.state.ui.data_version;
+    let inputs = {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (use_row_index, x_index).hash(&mut hasher);
+        app.state.filters.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // Large line series over the row index or a sorted X column are drawn from min/max
+    // pyramids: only the rows in view are touched, at about one bucket per pixel, and every
+    // spike survives. Range and outlier filters drop individual points, so they take the
+    // downsampling path below instead.
+    let x_summary = if use_row_index { None } else { ds.summary(x_index) };
+    let pyramid_view = app.state.view.plot_mode == PlotMode::Scatter
+        && n_rows > downsample_threshold
+        && !app.state.filters.has_range_filters()
+        && (use_row_index || x_summary.as_ref().is_some_and(|x| x.is_sorted()));
+    let visible_rows = pyramid_view.then(|| match view_x {
+        Some((min, max)) => pyramid::visible_rows(x_summary.as_deref(), min, max, n_rows),
+        None => 0..n_rows,
+    });
+    let m4_points = |y_idx: usize, rows: std::ops::Range<usize>, buckets: usize| -> Vec<[f64; 2]> {
+        let Some(summary) = ds.summary(y_idx) else {
+            return Vec::new();
+        };
+        let (Ok(y_ref), x_ref) = (ds.get_cached_column(y_idx), (!use_row_index).then(|| ds.get_cached_column(x_index)))
+        else {
+            return Vec::new();
+        };
+        summary.m4_rows(rows, buckets)
+            .into_iter()
+            .filter_map(|row| {
+                let x = match &x_ref {
+                    Some(Ok(x_ref)) => x_ref[row],
+                    Some(Err(_)) => return None,
+                    None => row as f64,
+                };
+                x.is_finite().then(|| [x, y_ref[row]])
+            })
+            .collect()
+    };
+
     // Create data for all series with filtering and optimized downsampling
-    let all_series: Vec<Vec<[f64; 2]>> = {
+    let all_series: Vec<Vec<[f64; 2]>> = if let Some(rows) = &visible_rows {
+        profiling::scope!("series_pyramid_query");
+        let buckets = ui.available_width().max(100.0) as usize;
+        y_indices.iter().map(|&y_idx| m4_points(y_idx, rows.clone(), buckets)).collect()
+    } else {
         profiling::scope!("series_data_prep");
 
         // Process each series
@@ -133,11 +261,10 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
                 }
             };
 
-            // Downsample if dataset is large (Phase 4.3: adaptive downsampling)
+            // Downsample if dataset is large: LTTB of the visible window, on the worker
             let downsampled = if points.len() > downsample_threshold {
-                // Convert to tuple format for downsampler
-                let tuple_points: Vec<(f64, f64)> = points.iter().map(|p| (p[0], p[1])).collect();
-                app.state.downsampler.downsample(&tuple_points, downsample_threshold, is_dragging)
+                let key = LttbKey::new(y_idx, data_version, inputs, downsample_threshold, view_x);
+                app.state.lttb_cache.points(key, &points, &app.state.worker)
             } else {
                 points
             };
@@ -148,6 +275,86 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
         series_data
     };
 
+    // The whole of each series, coarsely, for the data extent and minimap when only the
+    // visible window was queried
+    let overview: Option<Vec<Vec<[f64; 2]>>> = visible_rows.as_ref()
+        .map(|_| y_indices.iter().map(|&y_idx| m4_points(y_idx, 0..n_rows, 64)).collect());
+    let full_series = overview.as_ref().unwrap_or(&all_series);
+
+    // Centre and sigma for limits: whole-column moments when only a window is drawn
+    let limit_stats: Vec<(f64, f64)> = y_indices.iter()
+        .zip(&all_series)
+        .map(|(&y_idx, points_data)| {
+            let frozen = app.state.spc.frozen_limits.contains_key(&headers[y_idx]);
+            let whole_column = (pyramid_view && !frozen)
+                .then(|| ds.summary(y_idx))
+                .flatten()
+                .and_then(|summary| Some((summary.total().mean()?, summary.total().std_dev()?)));
+            whole_column.unwrap_or_else(|| {
+                let y_values: Vec<f64> = points_data.iter().map(|p| p[1]).collect();
+                app.limit_statistics(y_idx, &y_values)
+            })
+        })
+        .collect();
+
+    // X position where Phase II starts for each series with frozen limits
+    let phase_boundaries: Vec<Option<f64>> = y_indices.iter()
+        .map(|&y_idx| {
+            let frozen = app.state.spc.frozen_limits.get(&headers[y_idx])?;
+            if use_row_index {
+                Some(frozen.baseline_end as f64 - 0.5)
+            } else {
+                ds.get_cached_column(x_index).ok()?.get(frozen.baseline_end).copied()
+            }
+        })
+        .collect();
+
+    // Series of other open data sets, aligned on the X column of the same name (or on row
+    // index), labelled with their source. Large ones are downsampled like the series above,
+    // under a key per tab and column.
+    let mut overlay_series: Vec<(String, Vec<[f64; 2]>)> = Vec::new();
+    if app.state.view.plot_mode == PlotMode::Scatter {
+        let active = app.state.active_tab_id();
+        let x_column = (!use_row_index).then(|| headers[x_index].as_str());
+        let overlays: Vec<_> = app.state.view.overlays.iter()
+            .filter(|overlay| Some(overlay.tab) != active)
+            .cloned()
+            .collect();
+        for overlay in &overlays {
+            let tab_name = app.state.tabs.iter().find(|tab| tab.id == overlay.tab).map_or("", |tab| tab.name.as_str());
+            let label = format!("{} ({})", overlay.column, tab_name);
+            let Some(other) = app.state.dataset(overlay.tab) else {
+                overlay_series.push((label, Vec::new()));
+                continue;
+            };
+            if other.height() <= downsample_threshold {
+                let points = overlay_points(app, other, &overlay.column, x_column);
+                overlay_series.push((label, points));
+                continue;
+            }
+            let (series_id, overlay_inputs) = {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                (overlay.tab, &overlay.column).hash(&mut hasher);
+                // Top bit set, so overlay ids never meet the column indices of the series above
+                let series_id = hasher.finish() as usize | !(usize::MAX >> 1);
+                (inputs, other.height(), other.unfiltered_height()).hash(&mut hasher);
+                (series_id, hasher.finish())
+            };
+            let key = LttbKey::new(series_id, data_version, overlay_inputs, downsample_threshold, view_x);
+            // The points are only gathered when the worker has to downsample them
+            let points = if app.state.lttb_cache.has_cached(&key) {
+                Vec::new()
+            } else {
+                overlay_points(app, other, &overlay.column, x_column)
+            };
+            let downsampled = app.state.lttb_cache.points(key, &points, &app.state.worker);
+            overlay_series.push((label, downsampled));
+        }
+    }
+    let active_tab_name = (!overlay_series.is_empty())
+        .then(|| app.state.tabs.get(app.state.active_tab).map(|tab| tab.name.clone()))
+        .flatten();
+
     // Detect modifier keys for constrained zoom
     let shift_held = ctx.input(|i| i.modifiers.shift);
     let ctrl_held = ctx.input(|i| i.modifiers.ctrl || i.modifiers.command);
@@ -184,31 +391,7 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
 
     // Add custom axis formatters for timestamps
     if app.state.view.x_is_timestamp {
-        plot = plot.x_axis_formatter(|mark, _range| {
-            // Handle fractional seconds by extracting seconds and nanoseconds
-            let secs = mark.value.floor() as i64;
-            let nanos = ((mark.value.fract() * 1_000_000_000.0) as u32).min(999_999_999);
-
-            if let Some(dt) = DateTime::<Utc>::from_timestamp(secs, nanos) {
-                // Use newline for better readability on x-axis
-                dt.format("%Y-%m-%d\n%H:%M:%S").to_string()
-            } else {
-                format!("{:.2}", mark.value)
-            }
-        })
-        .label_formatter(|name, value| {
-            if name.is_empty() {
-                let secs = value.x.floor() as i64;
-                let nanos = ((value.x.fract() * 1_000_000_000.0) as u32).min(999_999_999);
-                if let Some(dt) = DateTime::<Utc>::from_timestamp(secs, nanos) {
-                    format!("{}\n{:.2}", dt.format("%Y-%m-%d %H:%M:%S"), value.y)
-                } else {
-                    format!("x: {:.3}\ny: {:.2}", value.x, value.y)
-                }
-            } else {
-                format!("{}\nx: {:.3}\ny: {:.2}", name, value.x, value.y)
-            }
-        });
+        plot = timestamp_axis(plot);
     } else {
         // Ensure x-axis labels always render with sensible formatting
         plot = plot.x_axis_formatter(|mark, _range| {
@@ -223,6 +406,10 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
     }
 
     let mut plot_response = plot.show(ui, |plot_ui| {
+        if let Some((min, max)) = follow_x {
+            plot_ui.set_plot_bounds_x(min..=max);
+            plot_ui.set_auto_bounds([false, true]);
+        }
         match app.state.view.plot_mode {
             PlotMode::Scatter => {
                 // Plot each series in scatter mode
@@ -232,8 +419,7 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
 
             // Draw sigma zone lines if enabled
             if app.state.spc.show_sigma_zones && !points_data.is_empty() {
-                let y_values: Vec<f64> = points_data.iter().map(|p| p[1]).collect();
-                let (mean, std_dev) = PlotOxide::calculate_statistics(&y_values);
+                let (mean, std_dev) = limit_stats[series_idx];
 
                 // ±1σ lines (blue)
                 plot_ui.hline(HLine::new(format!("{} +1σ", name), mean + 1.0 * std_dev)
@@ -284,50 +470,32 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
 
             // Draw SPC control limits if enabled
             if app.state.spc.show_spc_limits {
-                let y_values: Vec<f64> = points_data.iter().map(|p| p[1]).collect();
-                let (mean, std_dev) = PlotOxide::calculate_statistics(&y_values);
-                let ucl = mean + app.state.spc.sigma_multiplier * std_dev;
-                let lcl = mean - app.state.spc.sigma_multiplier * std_dev;
-
-                // Center line (mean)
-                plot_ui.hline(
-                    HLine::new(format!("{} Mean", name), mean)
-                        .color(color)
-                        .style(egui_plot::LineStyle::Dashed { length: 8.0 })
-                        .width(1.5),
-                );
-
-                // Upper control limit
-                plot_ui.hline(
-                    HLine::new(format!("{} UCL", name), ucl)
-                        .color(eframe::egui::Color32::RED)
-                        .style(egui_plot::LineStyle::Dashed { length: 10.0 })
-                        .width(2.0),
-                );
-
-                // Lower control limit
-                plot_ui.hline(
-                    HLine::new(format!("{} LCL", name), lcl)
-                        .color(eframe::egui::Color32::RED)
-                        .style(egui_plot::LineStyle::Dashed { length: 10.0 })
-                        .width(2.0),
-                );
-            }
-
-            // Draw data series
-            match app.state.view.line_style {
-                LineStyle::Line => {
-                    plot_ui.line(Line::new(name, points_data.clone()).color(color));
-                }
-                LineStyle::Points => {
-                    plot_ui.points(Points::new(name, points_data.clone()).radius(3.0).color(color));
-                }
-                LineStyle::LineAndPoints => {
-                    plot_ui.line(Line::new(name, points_data.clone()).color(color));
-                    plot_ui.points(Points::new(name, points_data.clone()).radius(3.0).color(color));
+                let (mean, std_dev) = limit_stats[series_idx];
+                let limits = ControlLimits {
+                    center: mean,
+                    ucl: mean + app.state.spc.sigma_multiplier * std_dev,
+                    lcl: mean - app.state.spc.sigma_multiplier * std_dev,
+                };
+                if let Some(boundary) = phase_boundaries[series_idx] {
+                    draw_control_limits(plot_ui, &format!("{} Phase I", name), &limits, color);
+                    plot_ui.vline(
+                        VLine::new(format!("{} Phase I | II", name), boundary)
+                            .color(eframe::egui::Color32::GRAY)
+                            .style(egui_plot::LineStyle::Dashed { length: 6.0 })
+                            .width(1.0),
+                    );
+                } else {
+                    draw_control_limits(plot_ui, name, &limits, color);
                 }
             }
 
+            // Draw data series, labelled with their source when series of other tabs are overlaid
+            let label = match &active_tab_name {
+                Some(tab_name) => format!("{} ({})", name, tab_name),
+                None => name.clone(),
+            };
+            draw_series(plot_ui, &label, points_data, color, app.state.view.line_style);
+
             // Highlight outliers
             if app.state.spc.show_outliers {
                 let y_values: Vec<f64> = points_data.iter().map(|p| p[1]).collect();
@@ -350,10 +518,16 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
             // Highlight Western Electric violations
             if app.state.spc.show_we_rules {
                 let y_values: Vec<f64> = points_data.iter().map(|p| p[1]).collect();
-                let we_indices = PlotOxide::detect_western_electric_violations(&y_values);
-                let we_points: Vec<[f64; 2]> = we_indices.iter()
-                    .map(|&i| points_data[i])
-                    .collect();
+                let (mean, std_dev) = limit_stats[series_idx];
+                let we_points: Vec<[f64; 2]> = PlotOxide::detect_western_electric_violations_with_limits(
+                    &y_values,
+                    mean,
+                    std_dev,
+                    &app.state.spc.rules,
+                )
+                .iter()
+                .map(|v| points_data[v.point_index])
+                .collect();
 
                 if !we_points.is_empty() {
                     plot_ui.points(
@@ -489,6 +663,12 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
                 }
 
                     }
+
+                // Overlays take the colours after this data set's series
+                for (i, (label, points)) in overlay_series.iter().enumerate() {
+                    let color = PlotOxide::get_series_color(app.state.view.y_indices.len() + i);
+                    draw_series(plot_ui, label, points, color, app.state.view.line_style);
+                }
                 }
                 PlotMode::Histogram => {
                     // Histogram mode - show histogram with proper bin widths
@@ -615,7 +795,7 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
                             .collect();
 
                         let (xbar_points, r_points, xbar_mean, xbar_ucl, xbar_lcl, r_mean, r_ucl, r_lcl) =
-                            PlotOxide::calculate_xbarr(&y_values, app.state.spc.xbarr_subgroup_size);
+                            PlotOxide::calculate_xbarr(&y_values, app.state.spc.xbarr_subgroup_size, app.frozen_limits(y_idx));
 
                         if !xbar_points.is_empty() {
                             // Note: egui_plot doesn't support dual Y-axes easily
@@ -671,56 +851,37 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
                                         .width(2.0)
                                 );
                             }
+                        } else if PlotOxide::get_xbarr_constants(app.state.spc.xbarr_subgroup_size).is_none() {
+                            plot_ui.text(egui_plot::Text::new(
+                                "X-bar R",
+                                egui_plot::PlotPoint::new(0.0, 0.0),
+                                format!("No X-bar R constants for subgroups of {}; use X-bar S", app.state.spc.xbarr_subgroup_size),
+                            ));
                         }
                     }
                 }
-                PlotMode::PChart => {
-                    // p-chart mode - proportion defective control chart
+                PlotMode::PChart | PlotMode::NpChart | PlotMode::CChart | PlotMode::UChart => {
+                    // Attribute control charts - counts/proportions with (possibly stair-stepped) limits
                     if let Some(&y_idx) = app.state.view.y_indices.first() {
                         let color = PlotOxide::get_series_color(0);
                         let name = &headers[y_idx];
-
-                        // Get Y values (number of defects per sample) (column-wise)
-                        let defects: Vec<f64> = get_col_data(y_idx)
-                            .into_iter()
-                            .filter(|&v| !v.is_nan() && v.is_finite())
-                            .collect();
-
-                        let (proportions, p_bar, ucl, lcl) = PlotOxide::calculate_pchart(&defects, app.state.spc.pchart_sample_size);
-
-                        if !proportions.is_empty() {
-                            // Draw proportion points and line
-                            plot_ui.line(Line::new(format!("{} Proportion", name), proportions.clone()).color(color));
-                            plot_ui.points(Points::new(format!("{} Proportion", name), proportions.clone()).radius(4.0).color(color));
-
-                            // Draw p-bar (center line)
-                            plot_ui.hline(
-                                HLine::new("p-bar (avg proportion)", p_bar)
-                                    .color(color)
-                                    .style(egui_plot::LineStyle::Dashed { length: 8.0 })
-                                    .width(2.0)
-                            );
-
-                            // Draw UCL
-                            plot_ui.hline(
-                                HLine::new("UCL", ucl)
-                                    .color(eframe::egui::Color32::RED)
-                                    .style(egui_plot::LineStyle::Dashed { length: 10.0 })
-                                    .width(2.0)
-                            );
-
-                            // Draw LCL (if > 0)
-                            if lcl > 0.0 {
-                                plot_ui.hline(
-                                    HLine::new("LCL", lcl)
-                                        .color(eframe::egui::Color32::RED)
-                                        .style(egui_plot::LineStyle::Dashed { length: 10.0 })
-                                        .width(2.0)
-                                );
-                            }
+                        let chart = build_attribute_chart(app, y_idx);
+
+                        if !chart.points.is_empty() {
+                            let label = match app.state.view.plot_mode {
+                                PlotMode::PChart => "Proportion",
+                                PlotMode::NpChart => "Number Defective",
+                                PlotMode::CChart => "Defects",
+                                _ => "Defects per Unit",
+                            };
+                            draw_attribute_chart(plot_ui, &format!("{} {}", name, label), &chart, color);
                         }
                     }
                 }
+                PlotMode::IMR | PlotMode::XbarS | PlotMode::Cusum | PlotMode::Ewma | PlotMode::Capability
+                | PlotMode::ProbabilityPlot | PlotMode::GageRR | PlotMode::HotellingT2 => {
+                    // Drawn by their own renderers, dispatched at the top of render_plot
+                }
             }
         });
 
@@ -731,7 +892,9 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
     if app.state.spc.show_outliers || app.state.spc.show_spc_limits || app.state.spc.show_we_rules {
         for series_idx in 0..app.state.view.y_indices.len() {
             // Use column data directly
-            let y_values: Vec<f64> = get_col_data(app.state.view.y_indices[series_idx]);
+            let y_idx = app.state.view.y_indices[series_idx];
+            let y_values: Vec<f64> = get_col_data(y_idx);
+            let (mean, std_dev) = app.limit_statistics(y_idx, &y_values);
 
             if app.state.spc.show_outliers {
                 let outliers = PlotOxide::detect_outliers(&y_values, app.state.spc.outlier_threshold);
@@ -739,7 +902,6 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
             }
 
             if app.state.spc.show_spc_limits {
-                let (mean, std_dev) = PlotOxide::calculate_statistics(&y_values);
                 let ucl = mean + app.state.spc.sigma_multiplier * std_dev;
                 let lcl = mean - app.state.spc.sigma_multiplier * std_dev;
 
@@ -751,7 +913,12 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
             }
 
             if app.state.spc.show_we_rules {
-                let we_detailed = PlotOxide::detect_western_electric_violations_detailed(&y_values);
+                let we_detailed = PlotOxide::detect_western_electric_violations_with_limits(
+                    &y_values,
+                    mean,
+                    std_dev,
+                    &app.state.spc.rules,
+                );
                 for violation in &we_detailed {
                     all_excursions.insert(violation.point_index);
                 }
@@ -762,6 +929,14 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
     app.state.spc.excursion_rows = all_excursions.into_iter().collect();
     app.state.spc.we_violations = all_we_violations;
 
+    // Series were prepared for last frame's bounds; redraw once more after a zoom or pan
+    let view_x_now = egui_plot::PlotMemory::load(ctx, plot_id)
+        .filter(|memory| !memory.auto_bounds.x)
+        .map(|memory| (memory.bounds().min()[0], memory.bounds().max()[0]));
+    if view_x_now != view_x {
+        ctx.request_repaint();
+    }
+
     // Handle right-click context menu
     plot_response.response.context_menu(|ui| {
         if ui.button("Reset View").clicked() {
@@ -852,7 +1027,7 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
                     for violation in &app.state.spc.we_violations {
                         if violation.point_index == closest_point_idx {
                             ui.separator();
-                            ui.colored_label(eframe::egui::Color32::from_rgb(255, 165, 0), "⚠ WE Rule Violations:");
+                            ui.colored_label(eframe::egui::Color32::from_rgb(255, 165, 0), "⚠ Rule Violations:");
                             for rule in &violation.rules {
                                 ui.label(format!("  • {}", rule));
                             }
@@ -912,7 +1087,7 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
     // Draw indicators when data extends beyond visible area
 
     // Only show edge indicators and minimap if there's actual data
-    let has_data = all_series.iter().any(|s| !s.is_empty());
+    let has_data = full_series.iter().any(|s| !s.is_empty());
 
     if !has_data {
         return;
@@ -925,7 +1100,7 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
         let mut y_min = f64::INFINITY;
         let mut y_max = f64::NEG_INFINITY;
         
-        for series in &all_series {
+        for series in full_series {
             for point in series {
                 if point[0].is_finite() {
                     x_min = x_min.min(point[0]);
@@ -1113,7 +1288,7 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
         );
         
         // Draw simplified data outline (just bounding box of each series)
-        for (series_idx, series) in all_series.iter().enumerate() {
+        for (series_idx, series) in full_series.iter().enumerate() {
             if series.is_empty() {
                 continue;
             }
@@ -1172,4 +1347,1562 @@ pub fn render_plot(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut ef
             eframe::egui::StrokeKind::Inside,
         );
     }
-}
\ No newline at end of file
+}
+
+/// Draw a centre line with dashed red UCL/LCL (shared by scatter SPC limits and control charts)
+fn draw_control_limits(plot_ui: &mut PlotUi, name: &str, limits: &ControlLimits, color: eframe::egui::Color32) {
+    // Center line
+    plot_ui.hline(
+        HLine::new(format!("{} Mean", name), limits.center)
+            .color(color)
+            .style(egui_plot::LineStyle::Dashed { length: 8.0 })
+            .width(1.5),
+    );
+
+    // Upper control limit
+    plot_ui.hline(
+        HLine::new(format!("{} UCL", name), limits.ucl)
+            .color(eframe::egui::Color32::RED)
+            .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+            .width(2.0),
+    );
+
+    // Lower control limit
+    plot_ui.hline(
+        HLine::new(format!("{} LCL", name), limits.lcl)
+            .color(eframe::egui::Color32::RED)
+            .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+            .width(2.0),
+    );
+}
+
+/// Draw one series in the chosen line style
+fn draw_series(plot_ui: &mut PlotUi, name: &str, points: &[[f64; 2]], color: eframe::egui::Color32, style: LineStyle) {
+    match style {
+        LineStyle::Line => {
+            plot_ui.line(Line::new(name, points.to_vec()).color(color));
+        }
+        LineStyle::Points => {
+            plot_ui.points(Points::new(name, points.to_vec()).radius(3.0).color(color));
+        }
+        LineStyle::LineAndPoints => {
+            plot_ui.line(Line::new(name, points.to_vec()).color(color));
+            plot_ui.points(Points::new(name, points.to_vec()).radius(3.0).color(color));
+        }
+    }
+}
+
+/// Points of `column` in another data set, against its column named `x_column` (row index
+/// when None), with the range and empty-value filters applied. Empty when either column is
+/// missing. Not downsampled.
+fn overlay_points(app: &PlotOxide, other: &crate::data::DataSource, column: &str, x_column: Option<&str>) -> Vec<[f64; 2]> {
+    let names = other.column_names();
+    let Some(Ok(y)) = names.iter().position(|n| n == column).map(|i| other.get_cached_column(i)) else {
+        return Vec::new();
+    };
+    let x = match x_column {
+        Some(x_column) => match names.iter().position(|n| n == x_column).map(|i| other.get_cached_column(i)) {
+            Some(Ok(x)) => Some(x),
+            _ => return Vec::new(),
+        },
+        None => None,
+    };
+    y.iter()
+        .enumerate()
+        .map(|(row, &y)| [x.as_ref().map_or(row as f64, |x| x[row]), y])
+        .filter(|&[x, y]| x.is_finite() && app.passes_non_outlier_filters(x, y))
+        .collect()
+}
+
+/// Draw a control chart series with out-of-control points and WE violations highlighted
+fn draw_control_series(
+    plot_ui: &mut PlotUi,
+    name: &str,
+    points: &[[f64; 2]],
+    limits: &ControlLimits,
+    violations: &[WEViolation],
+    color: eframe::egui::Color32,
+) {
+    plot_ui.line(Line::new(name, points.to_vec()).color(color));
+    plot_ui.points(Points::new(name, points.to_vec()).radius(3.0).color(color));
+
+    // Points beyond the control limits
+    let out_of_control: Vec<[f64; 2]> = points.iter()
+        .filter(|p| limits.is_out_of_control(p[1]))
+        .copied()
+        .collect();
+    if !out_of_control.is_empty() {
+        plot_ui.points(
+            Points::new(format!("{} Out of Control", name), out_of_control)
+                .color(eframe::egui::Color32::RED)
+                .filled(true)
+                .radius(5.0)
+                .shape(egui_plot::MarkerShape::Diamond),
+        );
+    }
+
+    // Run-rule violations (point_index indexes into `points`)
+    let we_points: Vec<[f64; 2]> = violations.iter()
+        .filter_map(|v| points.get(v.point_index).copied())
+        .collect();
+    if !we_points.is_empty() {
+        plot_ui.points(
+            Points::new(format!("{} WE Violations", name), we_points)
+                .color(eframe::egui::Color32::from_rgb(255, 165, 0))
+                .filled(false)
+                .radius(7.0)
+                .shape(egui_plot::MarkerShape::Square),
+        );
+    }
+}
+
+/// Build the attribute chart for the current mode from the count column and optional sample-size column
+fn build_attribute_chart(app: &PlotOxide, y_idx: usize) -> AttributeChart {
+    let Some(ds) = app.state.data.as_ref() else {
+        return AttributeChart::default();
+    };
+    let Ok(counts) = ds.get_cached_column(y_idx) else {
+        return AttributeChart::default();
+    };
+    let fixed_n = app.state.spc.pchart_sample_size;
+    // Frozen Phase I centre: defects per unit, or the mean count over a fixed sample size
+    let frozen = app.frozen_limits(y_idx);
+    let frozen_rate = |fixed_size: f64| frozen.map(|f| match (app.state.spc.sample_size_column, f.per_unit) {
+        (Some(_), Some(rate)) => rate,
+        _ => f.mean / fixed_size,
+    });
+
+    // Pair counts with per-row sample sizes, skipping rows where either is missing
+    let sized_counts = |fixed_size: f64| -> (Vec<f64>, Vec<f64>) {
+        match app.state.spc.sample_size_column.map(|col| ds.get_cached_column(col)) {
+            Some(Ok(sizes)) => counts.iter()
+                .zip(sizes.iter())
+                .filter(|&(c, n)| c.is_finite() && n.is_finite() && *n > 0.0)
+                .map(|(&c, &n)| (c, n))
+                .unzip(),
+            _ => counts.iter()
+                .filter(|c| c.is_finite())
+                .map(|&c| (c, fixed_size))
+                .unzip(),
+        }
+    };
+    let finite_counts = || -> Vec<f64> {
+        counts.iter().copied().filter(|c| c.is_finite()).collect()
+    };
+
+    match app.state.view.plot_mode {
+        PlotMode::PChart if app.state.spc.sample_size_column.is_none() => {
+            let defects = finite_counts();
+            let (points, p_bar, ucl, lcl) = PlotOxide::calculate_pchart(&defects, fixed_n, frozen_rate(fixed_n as f64));
+            AttributeChart {
+                ucl: vec![ucl; points.len()],
+                lcl: vec![lcl; points.len()],
+                points,
+                center: p_bar,
+            }
+        }
+        PlotMode::PChart => {
+            let (defects, sizes) = sized_counts(fixed_n as f64);
+            PlotOxide::calculate_pchart_variable(&defects, &sizes, frozen_rate(fixed_n as f64))
+        }
+        PlotMode::NpChart => PlotOxide::calculate_npchart(&finite_counts(), fixed_n, frozen_rate(fixed_n as f64)),
+        PlotMode::CChart => PlotOxide::calculate_cchart(&finite_counts(), frozen.map(|f| f.mean)),
+        PlotMode::UChart => {
+            let (defects, units) = sized_counts(app.state.spc.uchart_units);
+            PlotOxide::calculate_uchart(&defects, &units, frozen_rate(app.state.spc.uchart_units))
+        }
+        _ => AttributeChart::default(),
+    }
+}
+
+/// Convert per-point values into a stair-step line centred on each point
+fn stair_steps(values: &[f64]) -> Vec<[f64; 2]> {
+    values.iter()
+        .enumerate()
+        .flat_map(|(i, &v)| [[i as f64 - 0.5, v], [i as f64 + 0.5, v]])
+        .collect()
+}
+
+/// Draw an attribute chart, using stair-stepped limits when the sample size varies. The LCL
+/// is left out where it is clamped at zero.
+fn draw_attribute_chart(plot_ui: &mut PlotUi, name: &str, chart: &AttributeChart, color: eframe::egui::Color32) {
+    plot_ui.hline(
+        HLine::new(format!("{} Mean", name), chart.center)
+            .color(color)
+            .style(egui_plot::LineStyle::Dashed { length: 8.0 })
+            .width(1.5),
+    );
+    let has_lcl = chart.lcl.iter().any(|&v| v > 0.0);
+    if chart.has_constant_limits() {
+        plot_ui.hline(
+            HLine::new(format!("{} UCL", name), chart.ucl[0])
+                .color(eframe::egui::Color32::RED)
+                .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+                .width(2.0),
+        );
+        if has_lcl {
+            plot_ui.hline(
+                HLine::new(format!("{} LCL", name), chart.lcl[0])
+                    .color(eframe::egui::Color32::RED)
+                    .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+                    .width(2.0),
+            );
+        }
+    } else {
+        plot_ui.line(
+            Line::new(format!("{} UCL", name), stair_steps(&chart.ucl))
+                .color(eframe::egui::Color32::RED)
+                .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+                .width(2.0),
+        );
+        if has_lcl {
+            plot_ui.line(
+                Line::new(format!("{} LCL", name), stair_steps(&chart.lcl))
+                    .color(eframe::egui::Color32::RED)
+                    .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+                    .width(2.0),
+            );
+        }
+    }
+
+    plot_ui.line(Line::new(name, chart.points.clone()).color(color));
+    plot_ui.points(Points::new(name, chart.points.clone()).radius(4.0).color(color));
+
+    let out_of_control: Vec<[f64; 2]> = chart.out_of_control()
+        .into_iter()
+        .map(|i| chart.points[i])
+        .collect();
+    if !out_of_control.is_empty() {
+        plot_ui.points(
+            Points::new(format!("{} Out of Control", name), out_of_control)
+                .color(eframe::egui::Color32::RED)
+                .filled(true)
+                .radius(5.0)
+                .shape(egui_plot::MarkerShape::Diamond),
+        );
+    }
+}
+
+/// Rows inside the X range a plot showed last frame (all rows while it auto-fits)
+fn window_rows(ctx: &eframe::egui::Context, plot_id: eframe::egui::Id, x: Option<&MinMaxPyramid>, rows: usize) -> std::ops::Range<usize> {
+    egui_plot::PlotMemory::load(ctx, plot_id)
+        .filter(|memory| !memory.auto_bounds.x)
+        .map(|memory| pyramid::visible_rows(x, memory.bounds().min()[0], memory.bounds().max()[0], rows))
+        .unwrap_or(0..rows)
+}
+
+/// Show X values as UTC date-times on the axis and in hover labels
+fn timestamp_axis(plot: Plot<'_>) -> Plot<'_> {
+    plot.x_axis_formatter(|mark, _range| {
+        // Handle fractional seconds by extracting seconds and nanoseconds
+        let secs = mark.value.floor() as i64;
+        let nanos = ((mark.value.fract() * 1_000_000_000.0) as u32).min(999_999_999);
+
+        if let Some(dt) = DateTime::<Utc>::from_timestamp(secs, nanos) {
+            // Use newline for better readability on x-axis
+            dt.format("%Y-%m-%d\n%H:%M:%S").to_string()
+        } else {
+            format!("{:.2}", mark.value)
+        }
+    })
+    .label_formatter(|name, value| {
+        if name.is_empty() {
+            let secs = value.x.floor() as i64;
+            let nanos = ((value.x.fract() * 1_000_000_000.0) as u32).min(999_999_999);
+            if let Some(dt) = DateTime::<Utc>::from_timestamp(secs, nanos) {
+                format!("{}\n{:.2}", dt.format("%Y-%m-%d %H:%M:%S"), value.y)
+            } else {
+                format!("x: {:.3}\ny: {:.2}", value.x, value.y)
+            }
+        } else {
+            format!("{}\nx: {:.3}\ny: {:.2}", name, value.x, value.y)
+        }
+    })
+}
+
+/// Plot configured from the view settings for a standalone control chart pane
+fn control_plot<'a>(app: &PlotOxide, id: &str, height: f32, reset: bool) -> Plot<'a> {
+    let mut plot = Plot::new(id)
+        .allow_zoom(app.state.view.allow_zoom)
+        .allow_drag(app.state.view.allow_drag)
+        .allow_scroll(app.state.view.allow_zoom)
+        .show_grid(app.state.view.show_grid)
+        .height(height);
+    if reset {
+        plot = plot.reset();
+    }
+    if app.state.view.show_legend {
+        plot = plot.legend(egui_plot::Legend::default().position(egui_plot::Corner::RightTop));
+    }
+    plot
+}
+
+/// Render a location chart stacked above its dispersion chart, with linked X axes
+fn render_variables_chart(
+    app: &mut PlotOxide,
+    ui: &mut eframe::egui::Ui,
+    chart: &VariablesChart,
+    labels: (&str, &str),
+    violations: &[WEViolation],
+) {
+    let (location_label, dispersion_label) = labels;
+    let color = PlotOxide::get_series_color(0);
+    let dispersion_color = eframe::egui::Color32::from_rgb(255, 127, 14); // Orange
+
+    let pane_height = ((ui.available_height() - ui.spacing().item_spacing.y) / 2.0).max(150.0);
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+    let link_group = ui.id().with("control_chart_x");
+
+    let make_plot = |id: &str| {
+        control_plot(app, id, pane_height, reset)
+            .link_axis(link_group, [true, false])
+            .link_cursor(link_group, [true, false])
+    };
+
+    make_plot("control_chart_location").show(ui, |plot_ui| {
+        draw_control_limits(plot_ui, location_label, &chart.location_limits, color);
+        let shown: &[WEViolation] = if app.state.spc.show_we_rules { violations } else { &[] };
+        draw_control_series(plot_ui, location_label, &chart.location, &chart.location_limits, shown, color);
+    });
+
+    make_plot("control_chart_dispersion").show(ui, |plot_ui| {
+        draw_control_limits(plot_ui, dispersion_label, &chart.dispersion_limits, dispersion_color);
+        draw_control_series(plot_ui, dispersion_label, &chart.dispersion, &chart.dispersion_limits, &[], dispersion_color);
+    });
+}
+
+/// Finite values of the first selected Y series, with the source row of each value
+fn first_series_values(app: &PlotOxide) -> Option<(usize, Vec<usize>, Vec<f64>)> {
+    let &y_idx = app.state.view.y_indices.first()?;
+    let col = app.state.data.as_ref()?.get_cached_column(y_idx).ok()?;
+    let (rows, values) = col.iter()
+        .enumerate()
+        .filter(|&(_, v)| v.is_finite())
+        .map(|(row, &v)| (row, v))
+        .unzip();
+    Some((y_idx, rows, values))
+}
+
+/// Store excursions and WE violations for a chart, mapping chart point indices to table rows
+fn record_chart_excursions(
+    app: &mut PlotOxide,
+    chart: &VariablesChart,
+    violations: Vec<WEViolation>,
+    point_rows: impl Fn(usize) -> std::ops::Range<usize>,
+) {
+    let mut excursions: Vec<usize> = chart.location.iter()
+        .enumerate()
+        .filter(|(_, p)| chart.location_limits.is_out_of_control(p[1]))
+        .flat_map(|(i, _)| point_rows(i))
+        .collect();
+    if app.state.spc.show_we_rules {
+        excursions.extend(violations.iter().flat_map(|v| point_rows(v.point_index)));
+        app.state.spc.we_violations = violations.into_iter()
+            .map(|v| WEViolation { point_index: point_rows(v.point_index).start, rules: v.rules })
+            .collect();
+    } else {
+        app.state.spc.we_violations.clear();
+    }
+    excursions.sort_unstable();
+    excursions.dedup();
+    app.state.spc.excursion_rows = excursions;
+}
+
+/// Individuals & Moving Range chart for the first selected Y series
+fn render_imr_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let Some((y_idx, rows, values)) = first_series_values(app) else {
+        return;
+    };
+    let name = app.headers()[y_idx].clone();
+
+    let chart = PlotOxide::calculate_imr(&values, app.frozen_limits(y_idx));
+    if chart.location.is_empty() {
+        ui.centered_and_justified(|ui| {
+            ui.label("I-MR chart needs at least 2 numeric values.");
+        });
+        return;
+    }
+
+    // WE rules are evaluated against the MR-bar/d2 sigma rather than the overall std dev
+    let violations = PlotOxide::detect_western_electric_violations_with_limits(
+        &values,
+        chart.location_limits.center,
+        chart.sigma,
+        &app.state.spc.rules,
+    );
+
+    render_variables_chart(
+        app,
+        ui,
+        &chart,
+        (&format!("{} Individuals", name), &format!("{} Moving Range", name)),
+        &violations,
+    );
+
+    record_chart_excursions(app, &chart, violations, |i| rows[i]..rows[i] + 1);
+}
+
+/// X-bar and S chart for the first selected Y series
+fn render_xbars_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let Some((y_idx, rows, values)) = first_series_values(app) else {
+        return;
+    };
+    let name = app.headers()[y_idx].clone();
+    let subgroup_size = app.state.spc.xbarr_subgroup_size;
+
+    let chart = PlotOxide::calculate_xbars(&values, subgroup_size, app.frozen_limits(y_idx));
+    if chart.location.is_empty() {
+        ui.centered_and_justified(|ui| {
+            ui.label(format!("X-bar & S chart needs at least one full subgroup of {} values.", subgroup_size));
+        });
+        return;
+    }
+
+    // Subgroup means vary with sigma / sqrt(n)
+    let subgroup_means: Vec<f64> = chart.location.iter().map(|p| p[1]).collect();
+    let violations = PlotOxide::detect_western_electric_violations_with_limits(
+        &subgroup_means,
+        chart.location_limits.center,
+        chart.sigma / (subgroup_size as f64).sqrt(),
+        &app.state.spc.rules,
+    );
+
+    render_variables_chart(
+        app,
+        ui,
+        &chart,
+        (&format!("{} X-bar", name), &format!("{} S", name)),
+        &violations,
+    );
+
+    // Each subgroup point covers `subgroup_size` consecutive finite values
+    record_chart_excursions(app, &chart, violations, |i| {
+        rows[i * subgroup_size]..rows[(i + 1) * subgroup_size - 1] + 1
+    });
+}
+
+/// Tabular CUSUM chart for the first selected Y series
+fn render_cusum_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let Some((y_idx, rows, values)) = first_series_values(app) else {
+        return;
+    };
+    let name = app.headers()[y_idx].clone();
+
+    // Sigma comes from the moving range so a sustained shift doesn't inflate it, or from
+    // frozen Phase I limits, which also give the default target
+    let frozen = app.frozen_limits(y_idx);
+    let sigma = PlotOxide::calculate_imr(&values, frozen).sigma;
+    let spc = &app.state.spc;
+    let target = spc.cusum_target
+        .or(frozen.map(|f| f.mean))
+        .unwrap_or_else(|| values.iter().sum::<f64>() / values.len() as f64);
+    let chart = PlotOxide::calculate_cusum(&values, target, sigma, spc.cusum_k, spc.cusum_h);
+    if chart.upper.is_empty() {
+        ui.centered_and_justified(|ui| {
+            ui.label("CUSUM chart needs at least 2 non-constant numeric values.");
+        });
+        return;
+    }
+
+    // C- is drawn below zero so both sums share one pane
+    let lower: Vec<[f64; 2]> = chart.lower.iter().map(|p| [p[0], -p[1]]).collect();
+    let signal_points: Vec<[f64; 2]> = chart.signals.iter()
+        .map(|&i| if chart.upper[i][1] > chart.decision_interval { chart.upper[i] } else { lower[i] })
+        .collect();
+
+    let color = PlotOxide::get_series_color(0);
+    let lower_color = eframe::egui::Color32::from_rgb(255, 127, 14); // Orange
+    let height = ui.available_height().max(150.0);
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+
+    control_plot(app, "control_chart_cusum", height, reset).show(ui, |plot_ui| {
+        plot_ui.hline(
+            HLine::new(format!("{} Target", name), 0.0)
+                .color(color)
+                .style(egui_plot::LineStyle::Dashed { length: 8.0 })
+                .width(1.5),
+        );
+        for (label, h) in [("+H", chart.decision_interval), ("-H", -chart.decision_interval)] {
+            plot_ui.hline(
+                HLine::new(format!("{} {}", name, label), h)
+                    .color(eframe::egui::Color32::RED)
+                    .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+                    .width(2.0),
+            );
+        }
+
+        plot_ui.line(Line::new(format!("{} C+", name), chart.upper.clone()).color(color));
+        plot_ui.points(Points::new(format!("{} C+", name), chart.upper.clone()).radius(3.0).color(color));
+        plot_ui.line(Line::new(format!("{} C-", name), lower.clone()).color(lower_color));
+        plot_ui.points(Points::new(format!("{} C-", name), lower).radius(3.0).color(lower_color));
+
+        if !signal_points.is_empty() {
+            plot_ui.points(
+                Points::new(format!("{} CUSUM Signals", name), signal_points.clone())
+                    .color(eframe::egui::Color32::from_rgb(255, 165, 0))
+                    .filled(false)
+                    .radius(7.0)
+                    .shape(egui_plot::MarkerShape::Square),
+            );
+        }
+    });
+
+    app.state.spc.we_violations.clear();
+    app.state.spc.chart_signals = chart.signals.iter()
+        .zip(&signal_points)
+        .map(|(&i, p)| ChartSignal { point_index: rows[i], value: p[1] })
+        .collect();
+    app.state.spc.excursion_rows = chart.signals.iter().map(|&i| rows[i]).collect();
+}
+
+/// EWMA control chart for the first selected Y series
+fn render_ewma_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let Some((y_idx, rows, values)) = first_series_values(app) else {
+        return;
+    };
+    let name = app.headers()[y_idx].clone();
+
+    // Centre and sigma from frozen Phase I limits when the column has them
+    let frozen = app.frozen_limits(y_idx);
+    let sigma = PlotOxide::calculate_imr(&values, frozen).sigma;
+    let center = frozen.map(|f| f.mean).unwrap_or_else(|| values.iter().sum::<f64>() / values.len() as f64);
+    let spc = &app.state.spc;
+    let chart = PlotOxide::calculate_ewma_chart(&values, spc.ewma_lambda, spc.ewma_l, center, sigma);
+    if chart.points.is_empty() {
+        ui.centered_and_justified(|ui| {
+            ui.label("EWMA chart needs at least 2 non-constant numeric values.");
+        });
+        return;
+    }
+
+    let out_of_control = chart.out_of_control();
+    let label = format!("{} EWMA(λ={:.2})", name, spc.ewma_lambda);
+    let color = PlotOxide::get_series_color(0);
+    let height = ui.available_height().max(150.0);
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+
+    control_plot(app, "control_chart_ewma", height, reset).show(ui, |plot_ui| {
+        plot_ui.hline(
+            HLine::new(format!("{} Mean", label), chart.center)
+                .color(color)
+                .style(egui_plot::LineStyle::Dashed { length: 8.0 })
+                .width(1.5),
+        );
+        for (suffix, limit) in [("UCL", &chart.ucl), ("LCL", &chart.lcl)] {
+            let points: Vec<[f64; 2]> = limit.iter().enumerate().map(|(i, &v)| [i as f64, v]).collect();
+            plot_ui.line(
+                Line::new(format!("{} {}", label, suffix), points)
+                    .color(eframe::egui::Color32::RED)
+                    .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+                    .width(2.0),
+            );
+        }
+
+        plot_ui.line(Line::new(label.as_str(), chart.points.clone()).color(color));
+        plot_ui.points(Points::new(label.as_str(), chart.points.clone()).radius(3.0).color(color));
+
+        let flagged: Vec<[f64; 2]> = out_of_control.iter().map(|&i| chart.points[i]).collect();
+        if !flagged.is_empty() {
+            plot_ui.points(
+                Points::new(format!("{} Out of Control", label), flagged)
+                    .color(eframe::egui::Color32::RED)
+                    .filled(true)
+                    .radius(5.0)
+                    .shape(egui_plot::MarkerShape::Diamond),
+            );
+        }
+    });
+
+    app.state.spc.we_violations.clear();
+    app.state.spc.chart_signals = out_of_control.iter()
+        .map(|&i| ChartSignal { point_index: rows[i], value: chart.points[i][1] })
+        .collect();
+    app.state.spc.excursion_rows = out_of_control.iter().map(|&i| rows[i]).collect();
+}
+
+/// Capability histogram with spec limits, fitted normal curves and the capability report
+fn render_capability_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let Some((y_idx, _rows, values)) = first_series_values(app) else {
+        return;
+    };
+    let name = app.headers()[y_idx].clone();
+    let spc = &app.state.spc;
+    let (lsl, usl, target) = (spc.spec_lower, spc.spec_upper, spc.capability_target);
+
+    let Some(report) = capability::capability_report(&values, lsl, usl, target, spc.capability_subgroup_size) else {
+        ui.centered_and_justified(|ui| {
+            ui.label("Capability analysis needs at least 2 varying values and LSL < USL.");
+        });
+        return;
+    };
+
+    let (hist_data, _min, bin_width) = PlotOxide::calculate_histogram(&values, app.state.view.histogram_bins);
+    let bars: Vec<Bar> = hist_data.iter()
+        .map(|&[x, count]| Bar::new(x + bin_width / 2.0, count).width(bin_width * 0.95))
+        .collect();
+
+    // Normal curves scaled to histogram counts
+    let span = 4.0 * report.sigma_overall.max(report.sigma_within);
+    let (x_lo, x_hi) = (lsl.min(report.mean - span), usl.max(report.mean + span));
+    let curve = |sigma: f64| -> Vec<[f64; 2]> {
+        (0..=200)
+            .map(|i| {
+                let x = x_lo + (x_hi - x_lo) * i as f64 / 200.0;
+                let density = crate::data::stats::normal_pdf((x - report.mean) / sigma) / sigma;
+                [x, density * report.n as f64 * bin_width]
+            })
+            .collect()
+    };
+    let (overall_curve, within_curve) = (curve(report.sigma_overall), curve(report.sigma_within));
+
+    // Candidate fits are expensive (Box-Cox search, Johnson spacing scan), so cache them per column
+    let fits = match app.state.ui.fit_cache.get(&y_idx) {
+        Some(fits) => fits.clone(),
+        None => {
+            let fits = capability::fit_candidates(&values);
+            app.state.ui.fit_cache.insert(y_idx, fits.clone());
+            fits
+        }
+    };
+    let selected_fit = match app.state.spc.capability_distribution {
+        Some(kind) => fits.iter().find(|f| f.distribution.kind() == kind),
+        None => fits.first(),
+    };
+    let fitted_curve: Option<(&str, Vec<[f64; 2]>)> = selected_fit
+        .filter(|fit| fit.distribution.kind() != DistributionKind::Normal)
+        .map(|fit| {
+            let points = (0..=200)
+                .map(|i| {
+                    let x = x_lo + (x_hi - x_lo) * i as f64 / 200.0;
+                    [x, fit.distribution.pdf(x) * report.n as f64 * bin_width]
+                })
+                .collect();
+            (fit.distribution.kind().name(), points)
+        });
+
+    let color = PlotOxide::get_series_color(0);
+    let spec_color = eframe::egui::Color32::from_rgb(255, 140, 0);
+    let report_width = 240.0;
+    let height = ui.available_height().max(150.0);
+    let plot_width = (ui.available_width() - report_width - ui.spacing().item_spacing.x).max(200.0);
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+
+    ui.horizontal_top(|ui| {
+        control_plot(app, "capability_histogram", height, reset)
+            .width(plot_width)
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new(name.clone(), bars).color(color.gamma_multiply(0.6)));
+                plot_ui.line(Line::new("Overall", overall_curve).color(color).width(2.0));
+                plot_ui.line(
+                    Line::new("Within", within_curve)
+                        .color(color)
+                        .style(egui_plot::LineStyle::Dashed { length: 8.0 })
+                        .width(1.5),
+                );
+                for (label, x) in [("LSL", lsl), ("USL", usl)] {
+                    plot_ui.vline(VLine::new(label, x).color(spec_color).width(2.0));
+                }
+                if let Some((fit_name, points)) = fitted_curve {
+                    plot_ui.line(
+                        Line::new(format!("{} fit", fit_name), points)
+                            .color(eframe::egui::Color32::from_rgb(148, 103, 189)) // Purple
+                            .width(2.0),
+                    );
+                }
+                if let Some(t) = target {
+                    plot_ui.vline(
+                        VLine::new("Target", t)
+                            .color(eframe::egui::Color32::GREEN)
+                            .style(egui_plot::LineStyle::Dashed { length: 8.0 })
+                            .width(1.5),
+                    );
+                }
+            });
+
+        ui.vertical(|ui| {
+            ui.set_width(report_width);
+            eframe::egui::ScrollArea::vertical().id_salt("capability_report").show(ui, |ui| {
+                render_capability_report(ui, &name, &report);
+                if let Some(fit) = selected_fit {
+                    ui.separator();
+                    render_percentile_capability(ui, &fits, fit, lsl, usl);
+                }
+            });
+        });
+    });
+}
+
+/// Tabular capability report shown beside the capability histogram
+fn render_capability_report(ui: &mut eframe::egui::Ui, name: &str, report: &CapabilityReport) {
+    let index_color = |v: f64| {
+        if v >= 1.33 {
+            eframe::egui::Color32::GREEN
+        } else if v >= 1.0 {
+            eframe::egui::Color32::YELLOW
+        } else {
+            eframe::egui::Color32::RED
+        }
+    };
+    let confidence = crate::constants::spc::CAPABILITY_CONFIDENCE * 100.0;
+
+    ui.strong(format!("{} Capability", name));
+    ui.label(format!("n={}  μ={:.4}", report.n, report.mean));
+    ui.label(format!("σ within={:.4}", report.sigma_within));
+    ui.label(format!("σ overall={:.4}", report.sigma_overall));
+    ui.separator();
+
+    eframe::egui::Grid::new("capability_report").num_columns(3).striped(true).show(ui, |ui| {
+        ui.label("");
+        ui.label("Index");
+        ui.label(format!("{:.0}% CI", confidence));
+        ui.end_row();
+        for (label, value, ci) in [
+            ("Cp", report.cp, report.cp_ci),
+            ("Cpk", report.cpk, report.cpk_ci),
+            ("Pp", report.pp, report.pp_ci),
+            ("Ppk", report.ppk, report.ppk_ci),
+        ] {
+            ui.label(label);
+            ui.colored_label(index_color(value), format!("{:.3}", value));
+            ui.label(format!("{:.3} - {:.3}", ci.0, ci.1));
+            ui.end_row();
+        }
+        if let Some(cpm) = report.cpm {
+            ui.label("Cpm");
+            ui.colored_label(index_color(cpm), format!("{:.3}", cpm));
+            ui.label("");
+            ui.end_row();
+        }
+    });
+
+    ui.separator();
+    ui.label("PPM out of spec:");
+    ui.label(format!("  Expected within: {:.1}", report.ppm_within));
+    ui.label(format!("  Expected overall: {:.1}", report.ppm_overall));
+    ui.label(format!("  Observed: {:.1}", report.ppm_observed));
+}
+
+/// Clements percentile capability for the selected fit, with the goodness-of-fit of every candidate
+fn render_percentile_capability(
+    ui: &mut eframe::egui::Ui,
+    fits: &[DistributionFit],
+    selected: &DistributionFit,
+    lsl: f64,
+    usl: f64,
+) {
+    let pc = capability::percentile_capability(&selected.distribution, lsl, usl);
+
+    ui.strong(format!("Percentile ({})", selected.distribution.kind().name()));
+    ui.label(format!("X0.135={:.4}  X50={:.4}", pc.lower_percentile, pc.median));
+    ui.label(format!("X99.865={:.4}", pc.upper_percentile));
+    ui.label(format!("Pp={:.3}  Ppk={:.3}", pc.pp, pc.ppk));
+    ui.label(format!("Expected PPM: {:.1}", pc.ppm));
+
+    ui.separator();
+    eframe::egui::Grid::new("distribution_fits").num_columns(2).striped(true).show(ui, |ui| {
+        ui.label("Fit");
+        ui.label("AD");
+        ui.end_row();
+        for (i, fit) in fits.iter().enumerate() {
+            let label = if i == 0 {
+                format!("{} ★", fit.distribution.kind().name())
+            } else {
+                fit.distribution.kind().name().to_string()
+            };
+            if fit.distribution == selected.distribution {
+                ui.strong(label);
+            } else {
+                ui.label(label);
+            }
+            ui.label(format!("{:.3}", fit.ad_statistic));
+            ui.end_row();
+        }
+    });
+}
+
+/// Normal Q-Q plot of each selected Y series with a fitted reference line and 95% confidence bands
+fn render_probability_plot(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let max_points = app.state.view.downsample_threshold.max(100);
+
+    // Sorting and the normality tests are too slow to redo every frame, so cache them per column
+    let y_indices = app.state.view.y_indices.clone();
+    for &y_idx in &y_indices {
+        let cached = app.state.ui.probability_cache.get(&y_idx)
+            .is_some_and(|qq| qq.as_ref().is_none_or(|qq| qq.max_points == max_points));
+        if !cached {
+            let qq = probability_series(app, y_idx, max_points);
+            app.state.ui.probability_cache.insert(y_idx, qq);
+        }
+    }
+    let series: Vec<ProbabilitySeries> = y_indices.iter()
+        .filter_map(|y_idx| app.state.ui.probability_cache.get(y_idx).cloned().flatten())
+        .collect();
+
+    if series.is_empty() {
+        ui.centered_and_justified(|ui| {
+            ui.label("Probability plot needs at least 3 numeric values.");
+        });
+        return;
+    }
+
+    let height = ui.available_height().max(150.0);
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+    control_plot(app, "probability_plot", height, reset)
+        .x_axis_label("Normal quantile")
+        .show(ui, |plot_ui| {
+            for (series_idx, qq) in series.into_iter().enumerate() {
+                let color = PlotOxide::get_series_color(series_idx);
+                let band_color = color.gamma_multiply(0.6);
+                plot_ui.line(Line::new(format!("{} fit", qq.name), qq.line).color(color).width(1.5));
+                for (label, band) in [("lower", qq.lower), ("upper", qq.upper)] {
+                    plot_ui.line(
+                        Line::new(format!("{} 95% {}", qq.name, label), band)
+                            .color(band_color)
+                            .style(egui_plot::LineStyle::Dashed { length: 6.0 })
+                            .width(1.0),
+                    );
+                }
+                plot_ui.points(Points::new(qq.name, qq.points).radius(2.5).color(color));
+            }
+        });
+}
+
+/// Sort a column and fit the normal probability plot for it
+fn probability_series(app: &PlotOxide, y_idx: usize, max_points: usize) -> Option<ProbabilitySeries> {
+    let ds = app.state.data.as_ref()?;
+    let col = ds.get_cached_column(y_idx).ok()?;
+    let mut sorted: Vec<f64> = col.iter().copied().filter(|v| v.is_finite()).collect();
+    if sorted.len() < 3 {
+        return None;
+    }
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let n = sorted.len();
+    let mean = sorted.iter().sum::<f64>() / n as f64;
+    let std_dev = (sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt();
+    let scores = stats::normal_scores(n);
+    let band_z = stats::normal_quantile(0.975);
+
+    // Large columns are thinned to evenly spaced order statistics for drawing
+    let step = n.div_ceil(max_points).max(1);
+    let mut indices: Vec<usize> = (0..n).step_by(step).chain(std::iter::once(n - 1)).collect();
+    indices.dedup();
+
+    let mut qq = ProbabilitySeries {
+        name: app.headers()[y_idx].clone(),
+        max_points,
+        points: Vec::with_capacity(indices.len()),
+        line: Vec::with_capacity(indices.len()),
+        lower: Vec::with_capacity(indices.len()),
+        upper: Vec::with_capacity(indices.len()),
+    };
+    for &i in &indices {
+        let z = scores[i];
+        let fitted = mean + std_dev * z;
+        // Pointwise standard error of the i-th order statistic under the fitted normal
+        let p = stats::normal_cdf(z);
+        let se = std_dev / stats::normal_pdf(z) * (p * (1.0 - p) / n as f64).sqrt();
+        qq.points.push([z, sorted[i]]);
+        qq.line.push([z, fitted]);
+        qq.lower.push([z, fitted - band_z * se]);
+        qq.upper.push([z, fitted + band_z * se]);
+    }
+
+    let normality = app.state.ui.get_cached_stats(y_idx)
+        .and_then(|cached| cached.normality)
+        .or_else(|| stats::anderson_darling_test(&sorted));
+    if let Some(ad) = normality {
+        qq.name = format!("{} (AD={:.3}, p={:.4}", qq.name, ad.statistic, ad.p_value);
+        if let Some(sw) = stats::shapiro_wilk_test(&sorted) {
+            qq.name = format!("{}; SW W={:.3}, p={:.4}", qq.name, sw.statistic, sw.p_value);
+        }
+        qq.name.push(')');
+    }
+    Some(qq)
+}
+
+/// Run (or fetch the cached) Gage R&R study for the configured columns
+fn gage_rr_study(app: &mut PlotOxide) -> Option<Result<GageRR, String>> {
+    let spc = &app.state.spc;
+    let part_col = spc.gage_part_column?;
+    let operator_col = spc.gage_operator_column?;
+    let measurement_col = spc.gage_measurement_column.or_else(|| app.state.view.y_indices.first().copied())?;
+    let key = (part_col, operator_col, measurement_col);
+
+    if let Some((cached_key, result)) = &app.state.ui.gage_cache
+        && *cached_key == key
+    {
+        return Some(result.clone().map_err(|e| e.to_string()));
+    }
+
+    let ds = app.state.data.as_ref()?;
+    let columns = ds.column_as_string(part_col)
+        .and_then(|parts| Ok((parts, ds.column_as_string(operator_col)?)))
+        .and_then(|(parts, operators)| Ok((parts, operators, ds.get_cached_column(measurement_col)?.to_vec())));
+    let (parts, operators, measurements) = match columns {
+        Ok(columns) => columns,
+        Err(e) => return Some(Err(e.to_string())),
+    };
+
+    let result = msa::gage_rr_anova(&parts, &operators, &measurements);
+    app.state.ui.gage_cache = Some((key, result.clone()));
+    Some(result.map_err(|e| e.to_string()))
+}
+
+/// Gage R&R study: operator-by-part interaction plot above a range chart by operator, with the ANOVA report
+fn render_gage_rr_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let grr = match gage_rr_study(app) {
+        Some(Ok(grr)) => grr,
+        Some(Err(message)) => {
+            ui.centered_and_justified(|ui| {
+                ui.label(format!("Gage R&R: {}", message));
+            });
+            return;
+        }
+        None => {
+            ui.centered_and_justified(|ui| {
+                ui.label("Select part, operator and measurement columns for the Gage R&R study.");
+            });
+            return;
+        }
+    };
+
+    let parts = grr.parts.len();
+    let r_bar = grr.cell_ranges.iter().flatten().sum::<f64>() / (parts * grr.operators.len()) as f64;
+    let (_, d3, d4) = PlotOxide::get_xbarr_constants(grr.trials).unwrap_or((0.0, 0.0, 2.114));
+    let range_limits = ControlLimits { center: r_bar, ucl: d4 * r_bar, lcl: d3 * r_bar };
+
+    let report_width = 280.0;
+    let plot_width = (ui.available_width() - report_width - ui.spacing().item_spacing.x).max(200.0);
+    let pane_height = ((ui.available_height() - ui.spacing().item_spacing.y) / 2.0).max(150.0);
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+
+    ui.horizontal_top(|ui| {
+        ui.vertical(|ui| {
+            ui.set_width(plot_width);
+
+            // Part is on X (1-based); each operator's cell means form one line
+            control_plot(app, "gage_interaction", pane_height, reset)
+                .width(plot_width)
+                .show(ui, |plot_ui| {
+                    for (i, (operator, means)) in grr.operators.iter().zip(&grr.cell_means).enumerate() {
+                        let color = PlotOxide::get_series_color(i);
+                        let points: Vec<[f64; 2]> = means.iter()
+                            .enumerate()
+                            .map(|(j, &m)| [(j + 1) as f64, m])
+                            .collect();
+                        plot_ui.line(Line::new(operator.clone(), points.clone()).color(color));
+                        plot_ui.points(Points::new(operator.clone(), points).radius(3.0).color(color));
+                    }
+                });
+
+            // Ranges are laid out operator by operator, parts in order within each block
+            control_plot(app, "gage_range", pane_height, reset)
+                .width(plot_width)
+                .show(ui, |plot_ui| {
+                    draw_control_limits(plot_ui, "Range", &range_limits, eframe::egui::Color32::GRAY);
+                    for (i, (operator, ranges)) in grr.operators.iter().zip(&grr.cell_ranges).enumerate() {
+                        let points: Vec<[f64; 2]> = ranges.iter()
+                            .enumerate()
+                            .map(|(j, &r)| [(i * parts + j + 1) as f64, r])
+                            .collect();
+                        let name = format!("{} Range", operator);
+                        draw_control_series(plot_ui, &name, &points, &range_limits, &[], PlotOxide::get_series_color(i));
+                    }
+                });
+        });
+
+        ui.vertical(|ui| {
+            ui.set_width(report_width);
+            eframe::egui::ScrollArea::vertical().id_salt("gage_report").show(ui, |ui| {
+                render_gage_rr_report(ui, &grr);
+            });
+        });
+    });
+}
+
+/// ANOVA table and variance components shown beside the Gage R&R charts
+fn render_gage_rr_report(ui: &mut eframe::egui::Ui, grr: &GageRR) {
+    ui.strong("Gage R&R (ANOVA)");
+    ui.label(format!(
+        "{} parts × {} operators × {} trials",
+        grr.parts.len(),
+        grr.operators.len(),
+        grr.trials
+    ));
+    ui.separator();
+
+    eframe::egui::Grid::new("gage_anova").num_columns(5).striped(true).show(ui, |ui| {
+        for header in ["Source", "DF", "MS", "F", "p"] {
+            ui.label(header);
+        }
+        ui.end_row();
+        let interaction = (!grr.interaction_pooled).then_some(("Part × Oper", &grr.anova_interaction));
+        let rows = [("Part", &grr.anova_part), ("Operator", &grr.anova_operator)]
+            .into_iter()
+            .chain(interaction)
+            .chain([("Repeatability", &grr.anova_repeatability), ("Total", &grr.anova_total)]);
+        for (label, row) in rows {
+            ui.label(label);
+            ui.label(format!("{:.0}", row.df));
+            ui.label(format!("{:.4}", row.ms));
+            ui.label(row.f.map(|f| format!("{:.2}", f)).unwrap_or_default());
+            ui.label(row.p.map(|p| format!("{:.3}", p)).unwrap_or_default());
+            ui.end_row();
+        }
+    });
+    if grr.interaction_pooled {
+        ui.label(format!(
+            "Interaction pooled (p={:.3})",
+            grr.anova_interaction.p.unwrap_or(f64::NAN)
+        ));
+    }
+
+    ui.separator();
+    eframe::egui::Grid::new("gage_components").num_columns(3).striped(true).show(ui, |ui| {
+        ui.label("Source");
+        ui.label("%Contrib");
+        ui.label("%StudyVar");
+        ui.end_row();
+        for (label, component) in [
+            ("Total Gage R&R", &grr.total_grr),
+            ("  Repeatability", &grr.repeatability),
+            ("  Reproducibility", &grr.reproducibility),
+            ("    Operator", &grr.operator),
+            ("    Part × Oper", &grr.interaction),
+            ("Part-to-Part", &grr.part_to_part),
+            ("Total Variation", &grr.total),
+        ] {
+            ui.label(label);
+            ui.label(format!("{:.2}", component.pct_contribution));
+            ui.label(format!("{:.2}", component.pct_study_var));
+            ui.end_row();
+        }
+    });
+
+    ui.separator();
+    // AIAG guidance: under 10% acceptable, 10-30% marginal, over 30% unacceptable
+    let pct = grr.total_grr.pct_study_var;
+    let color = if pct < 10.0 {
+        eframe::egui::Color32::GREEN
+    } else if pct <= 30.0 {
+        eframe::egui::Color32::YELLOW
+    } else {
+        eframe::egui::Color32::RED
+    };
+    ui.colored_label(color, format!("%GRR = {:.2}%", pct));
+    ui.label(format!("Distinct categories (ndc) = {}", grr.ndc));
+}
+
+/// One stratum of the first Y series, ready to draw
+struct StratumChart {
+    name: String,
+    color: eframe::egui::Color32,
+    limits: ControlLimits,
+    /// Points drawn for the series (downsampled when large)
+    shown: Vec<[f64; 2]>,
+    out_of_control: Vec<[f64; 2]>,
+    violations: Vec<[f64; 2]>,
+}
+
+/// Draw a stratum's points and markers; limits are drawn by the caller
+fn draw_stratum(plot_ui: &mut PlotUi, chart: &StratumChart, line_style: LineStyle) {
+    let name = chart.name.as_str();
+    if line_style != LineStyle::Points {
+        plot_ui.line(Line::new(name, chart.shown.clone()).color(chart.color));
+    }
+    if line_style != LineStyle::Line {
+        plot_ui.points(Points::new(name, chart.shown.clone()).radius(3.0).color(chart.color));
+    }
+    if !chart.out_of_control.is_empty() {
+        plot_ui.points(
+            Points::new(format!("{} Out of Control", name), chart.out_of_control.clone())
+                .color(eframe::egui::Color32::RED)
+                .filled(true)
+                .radius(5.0)
+                .shape(egui_plot::MarkerShape::Diamond),
+        );
+    }
+    if !chart.violations.is_empty() {
+        plot_ui.points(
+            Points::new(format!("{} WE Violations", name), chart.violations.clone())
+                .color(eframe::egui::Color32::from_rgb(255, 165, 0))
+                .filled(false)
+                .radius(7.0)
+                .shape(egui_plot::MarkerShape::Square),
+        );
+    }
+}
+
+/// First Y series split by the group-by column, each stratum with its own control limits
+fn render_stratified_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let (Some(strata_col), Some(&y_idx)) = (app.state.spc.strata_column, app.state.view.y_indices.first()) else {
+        return;
+    };
+    let Some(ds) = app.state.data.as_ref() else {
+        return;
+    };
+    let headers = app.headers();
+
+    let (Ok(labels), Ok(y_ref)) = (ds.column_as_string(strata_col), ds.get_cached_column(y_idx)) else {
+        return;
+    };
+    let values = y_ref.to_vec();
+    drop(y_ref);
+    let x_values: Vec<f64> = if app.state.view.use_row_index {
+        (0..values.len()).map(|i| i as f64).collect()
+    } else {
+        ds.get_cached_column(app.state.view.x_index).map(|r| r.to_vec()).unwrap_or_default()
+    };
+    if x_values.len() != values.len() {
+        return;
+    }
+
+    let groups = strata::stratify(&labels, &values, |row| app.passes_filters(row, x_values[row], values[row], y_idx));
+    if groups.len() > crate::constants::spc::MAX_STRATA {
+        ui.centered_and_justified(|ui| {
+            ui.label(format!(
+                "'{}' has {} distinct values; pick a category column with at most {}.",
+                headers[strata_col],
+                groups.len(),
+                crate::constants::spc::MAX_STRATA
+            ));
+        });
+        return;
+    }
+
+    let sigma_mult = app.state.spc.sigma_multiplier;
+    let show_rules = app.state.spc.show_we_rules;
+    let threshold = app.state.view.downsample_threshold;
+    let rules = &app.state.spc.rules;
+    let downsampler = &mut app.state.downsampler;
+    let mut excursions = Vec::new();
+    let mut row_violations = Vec::new();
+
+    let charts: Vec<StratumChart> = groups.iter()
+        .enumerate()
+        .map(|(k, stratum)| {
+            let (mean, std_dev) = stratum.mean_std();
+            let limits = ControlLimits {
+                center: mean,
+                ucl: mean + sigma_mult * std_dev,
+                lcl: mean - sigma_mult * std_dev,
+            };
+            let points: Vec<[f64; 2]> = stratum.rows.iter().map(|&row| [x_values[row], values[row]]).collect();
+
+            let ooc: Vec<usize> = (0..points.len()).filter(|&i| limits.is_out_of_control(points[i][1])).collect();
+            let violations = if show_rules {
+                PlotOxide::detect_western_electric_violations_with_limits(&stratum.values, mean, std_dev, rules)
+            } else {
+                Vec::new()
+            };
+            excursions.extend(ooc.iter().chain(violations.iter().map(|v| &v.point_index)).map(|&i| stratum.rows[i]));
+
+            let chart = StratumChart {
+                name: format!("{} = {}", headers[strata_col], stratum.label),
+                color: PlotOxide::get_series_color(k),
+                limits,
+                shown: if points.len() > threshold {
+                    let tuples: Vec<(f64, f64)> = points.iter().map(|p| (p[0], p[1])).collect();
+                    downsampler.downsample(&tuples, threshold, false)
+                } else {
+                    points.clone()
+                },
+                out_of_control: ooc.iter().map(|&i| points[i]).collect(),
+                violations: violations.iter().map(|v| points[v.point_index]).collect(),
+            };
+            row_violations.extend(violations.into_iter().map(|v| WEViolation {
+                point_index: stratum.rows[v.point_index],
+                rules: v.rules,
+            }));
+            chart
+        })
+        .collect();
+
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+    let line_style = app.state.view.line_style;
+
+    match app.state.spc.strata_layout {
+        StrataLayout::Overlay => {
+            let height = ui.available_height().max(200.0);
+            control_plot(app, "strata_overlay", height, reset).show(ui, |plot_ui| {
+                for chart in &charts {
+                    // Limits share the stratum color so each set can be told apart
+                    plot_ui.hline(
+                        HLine::new(format!("{} Mean", chart.name), chart.limits.center)
+                            .color(chart.color)
+                            .style(egui_plot::LineStyle::Dashed { length: 8.0 })
+                            .width(1.5),
+                    );
+                    for (label, y) in [("UCL", chart.limits.ucl), ("LCL", chart.limits.lcl)] {
+                        plot_ui.hline(
+                            HLine::new(format!("{} {}", chart.name, label), y)
+                                .color(chart.color)
+                                .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+                                .width(2.0),
+                        );
+                    }
+                    draw_stratum(plot_ui, chart, line_style);
+                }
+            });
+        }
+        StrataLayout::SmallMultiples => {
+            let columns = (charts.len() as f64).sqrt().ceil().max(1.0) as usize;
+            let grid_rows = charts.len().div_ceil(columns).max(1);
+            let spacing = ui.spacing().item_spacing;
+            let pane_width = ((ui.available_width() - spacing.x * (columns - 1) as f32) / columns as f32).max(150.0);
+            let pane_height = ((ui.available_height() - spacing.y * (grid_rows - 1) as f32) / grid_rows as f32).max(150.0);
+            let link_group = ui.id().with("strata_x");
+
+            eframe::egui::ScrollArea::vertical().id_salt("strata_multiples").show(ui, |ui| {
+                for (row, row_charts) in charts.chunks(columns).enumerate() {
+                    ui.horizontal(|ui| {
+                        for (col, chart) in row_charts.iter().enumerate() {
+                            control_plot(app, &format!("strata_pane_{}", row * columns + col), pane_height, reset)
+                                .width(pane_width)
+                                .link_axis(link_group, [true, false])
+                                .link_cursor(link_group, [true, false])
+                                .show(ui, |plot_ui| {
+                                    draw_control_limits(plot_ui, &chart.name, &chart.limits, chart.color);
+                                    draw_stratum(plot_ui, chart, line_style);
+                                });
+                        }
+                    });
+                }
+            });
+        }
+    }
+
+    excursions.sort_unstable();
+    excursions.dedup();
+    app.state.spc.excursion_rows = excursions;
+    app.state.spc.we_violations = row_violations;
+}
+
+/// Build (or fetch the cached) T² chart over the given Y columns
+fn hotelling_chart(app: &mut PlotOxide, y_indices: &[usize]) -> Result<HotellingT2, String> {
+    let spc = &app.state.spc;
+    let baseline = spc.hotelling_use_baseline.then_some(spc.baseline_rows.0..spc.baseline_rows.1);
+    let key = (y_indices.to_vec(), baseline.clone());
+
+    if let Some((cached_key, result)) = &app.state.ui.hotelling_cache
+        && *cached_key == key
+    {
+        return result.clone().map_err(|e| e.to_string());
+    }
+
+    let Some(ds) = app.state.data.as_ref() else {
+        return Err("No data loaded".to_string());
+    };
+    // Every selected column must be read, or contributions would be labelled with the wrong names
+    let columns = y_indices.iter()
+        .map(|&y_idx| ds.get_cached_column(y_idx).map(|c| c.to_vec()))
+        .collect::<Result<Vec<Vec<f64>>, _>>()
+        .map_err(|e| e.to_string())?;
+    let column_refs: Vec<&[f64]> = columns.iter().map(|c| c.as_slice()).collect();
+
+    let result = multivariate::hotelling_t2(&column_refs, baseline, crate::constants::spc::HOTELLING_ALPHA);
+    app.state.ui.hotelling_cache = Some((key, result.clone()));
+    result.map_err(|e| e.to_string())
+}
+
+/// Hotelling T² chart over all selected Y series, with per-variable contributions for the clicked point
+fn render_hotelling_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let headers = app.headers();
+    let y_indices = app.state.view.y_indices.clone();
+    let names: Vec<String> = y_indices.iter().map(|&i| headers[i].clone()).collect();
+    let chart = match hotelling_chart(app, &y_indices) {
+        Ok(chart) => chart,
+        Err(e) => {
+            ui.centered_and_justified(|ui| {
+                ui.label(format!("Hotelling T²: {}", e));
+            });
+            return;
+        }
+    };
+
+    // X is the source row so points line up with the data table
+    let points: Vec<[f64; 2]> = chart.rows.iter().zip(&chart.t2).map(|(&r, &t2)| [r as f64, t2]).collect();
+    let out_of_control = chart.out_of_control();
+    let ooc_points: Vec<[f64; 2]> = out_of_control.iter().map(|&i| points[i]).collect();
+    let threshold = app.state.view.downsample_threshold;
+    let shown = if points.len() > threshold {
+        let tuples: Vec<(f64, f64)> = points.iter().map(|p| (p[0], p[1])).collect();
+        app.state.downsampler.downsample(&tuples, threshold, false)
+    } else {
+        points.clone()
+    };
+
+    let selected = app.state.ui.t2_selected.filter(|&i| i < points.len());
+    let contributions = selected.and_then(|i| chart.contributions(i));
+    let color = PlotOxide::get_series_color(0);
+    let report_width = 220.0;
+    let plot_width = (ui.available_width() - report_width - ui.spacing().item_spacing.x).max(200.0);
+    let pane_height = ((ui.available_height() - ui.spacing().item_spacing.y) / 2.0).max(150.0);
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+    let mut clicked_x = None;
+
+    ui.horizontal_top(|ui| {
+        ui.vertical(|ui| {
+            ui.set_width(plot_width);
+            control_plot(app, "hotelling_t2", pane_height, reset)
+                .width(plot_width)
+                .show(ui, |plot_ui| {
+                    plot_ui.hline(
+                        HLine::new("T² UCL", chart.ucl)
+                            .color(eframe::egui::Color32::RED)
+                            .style(egui_plot::LineStyle::Dashed { length: 10.0 })
+                            .width(2.0),
+                    );
+                    plot_ui.line(Line::new("T²", shown).color(color));
+                    if !ooc_points.is_empty() {
+                        plot_ui.points(
+                            Points::new("T² Out of Control", ooc_points)
+                                .color(eframe::egui::Color32::RED)
+                                .filled(true)
+                                .radius(5.0)
+                                .shape(egui_plot::MarkerShape::Diamond),
+                        );
+                    }
+                    if let Some(i) = selected {
+                        plot_ui.points(
+                            Points::new("", vec![points[i]])
+                                .color(eframe::egui::Color32::from_rgb(255, 215, 0))
+                                .filled(false)
+                                .radius(10.0),
+                        );
+                    }
+                    if plot_ui.response().clicked() {
+                        clicked_x = plot_ui.pointer_coordinate().map(|p| p.x);
+                    }
+                });
+
+            control_plot(app, "hotelling_contributions", pane_height, reset)
+                .width(plot_width)
+                .show(ui, |plot_ui| {
+                    let Some(contributions) = &contributions else {
+                        return;
+                    };
+                    let bars: Vec<Bar> = contributions.iter()
+                        .zip(&names)
+                        .enumerate()
+                        .map(|(j, (c, name))| {
+                            Bar::new(j as f64, c.contribution)
+                                .width(0.6)
+                                .name(name)
+                                .fill(PlotOxide::get_series_color(j))
+                        })
+                        .collect();
+                    plot_ui.bar_chart(BarChart::new("Contribution", bars));
+                });
+        });
+
+        ui.vertical(|ui| {
+            ui.set_width(report_width);
+            ui.strong("Hotelling T²");
+            ui.label(format!("{} variables, Phase I m={}", names.len(), chart.phase_one));
+            ui.label(format!("UCL={:.3}", chart.ucl));
+            ui.label(format!("{} of {} points above UCL", out_of_control.len(), points.len()));
+            ui.separator();
+            let (Some(i), Some(contributions)) = (selected, &contributions) else {
+                ui.label("Click a point to decompose its T².");
+                return;
+            };
+            ui.label(format!("Row {}: T²={:.3}", chart.rows[i] + 1, chart.t2[i]));
+            eframe::egui::Grid::new("t2_contributions").num_columns(3).striped(true).show(ui, |ui| {
+                ui.label("Variable");
+                ui.label("Contrib");
+                ui.label("Univ. t²");
+                ui.end_row();
+                for (name, c) in names.iter().zip(contributions) {
+                    ui.label(name);
+                    ui.label(format!("{:.3}", c.contribution));
+                    ui.label(format!("{:.3}", c.univariate));
+                    ui.end_row();
+                }
+            });
+        });
+    });
+
+    // Select the point nearest the clicked row
+    if let Some(x) = clicked_x {
+        let idx = chart.rows.partition_point(|&r| (r as f64) < x);
+        let nearest = [idx.checked_sub(1), (idx < chart.rows.len()).then_some(idx)]
+            .into_iter()
+            .flatten()
+            .min_by(|&a, &b| (chart.rows[a] as f64 - x).abs().total_cmp(&(chart.rows[b] as f64 - x).abs()));
+        app.state.ui.t2_selected = nearest;
+        if let Some(i) = nearest {
+            app.state.ui.scroll_to_row = Some(chart.rows[i]);
+        }
+    }
+
+    app.state.spc.we_violations.clear();
+    app.state.spc.chart_signals = out_of_control.iter()
+        .map(|&i| ChartSignal { point_index: chart.rows[i], value: chart.t2[i] })
+        .collect();
+    app.state.spc.excursion_rows = out_of_control.iter().map(|&i| chart.rows[i]).collect();
+}
+
+/// X position of each summary bucket: the middle row, or the middle of the X values it spans
+fn bucket_x(rows: &std::ops::Range<usize>, x: Option<&Bucket>) -> Option<f64> {
+    match x {
+        Some(x) if x.is_empty() => None,
+        Some(x) => Some((x.min + x.max) / 2.0),
+        None => Some((rows.start + rows.end.saturating_sub(1)) as f64 / 2.0),
+    }
+}
+
+/// Zigzag through each bucket's min and max so every spike in the range stays visible
+fn summary_envelope(
+    y: &MinMaxPyramid,
+    x: Option<&MinMaxPyramid>,
+    rows: std::ops::Range<usize>,
+    max_buckets: usize,
+) -> Vec<[f64; 2]> {
+    let y_buckets = y.query(rows.clone(), max_buckets);
+    let x_buckets = x.map(|x| x.query(rows, max_buckets));
+    y_buckets.iter()
+        .enumerate()
+        .filter(|(_, (_, bucket))| !bucket.is_empty())
+        .filter_map(|(i, (span, bucket))| {
+            let x_bucket = match &x_buckets {
+                Some(x_buckets) => Some(&x_buckets.get(i)?.1),
+                None => None,
+            };
+            Some((bucket_x(span, x_bucket)?, bucket))
+        })
+        .flat_map(|(x, bucket)| [[x, bucket.min], [x, bucket.max]])
+        .collect()
+}
+
+/// Read rows from disk as points per Y column, skipping missing values
+fn read_stream_window(
+    app: &PlotOxide,
+    rows: std::ops::Range<usize>,
+    x_col: Option<usize>,
+    y_cols: &[usize],
+) -> Result<Vec<Vec<[f64; 2]>>, DataError> {
+    let Some(ds) = app.state.data.as_ref() else {
+        return Ok(Vec::new());
+    };
+    let mut columns: Vec<usize> = x_col.into_iter().collect();
+    for &col in y_cols {
+        if !columns.contains(&col) {
+            columns.push(col);
+        }
+    }
+    let df = ds.rows(rows.clone(), &columns)?;
+    let values = df.get_columns()
+        .iter()
+        .map(|c| series_as_f64(c.as_materialized_series()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let xs: Vec<f64> = match x_col {
+        Some(_) => values[0].clone(),
+        None => rows.map(|r| r as f64).collect(),
+    };
+    Ok(y_cols.iter()
+        .map(|col| {
+            let ys = &values[columns.iter().position(|c| c == col).unwrap_or_default()];
+            xs.iter()
+                .zip(ys)
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+                .map(|(&x, &y)| [x, y])
+                .collect()
+        })
+        .collect())
+}
+
+/// Plot of a file streamed from disk. Each series is drawn from its min/max summary at about
+/// one bucket per pixel, and from the raw rows once few enough of them are in view.
+fn render_streaming_chart(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    use crate::constants::performance::STREAM_RAW_ROWS;
+
+    let x_col = (!app.state.view.use_row_index).then_some(app.state.view.x_index);
+    let y_cols = app.state.view.y_indices.clone();
+    let headers = app.headers();
+    let x_summary = x_col.map(|col| app.streamed_summary(col));
+    let y_summaries: Vec<_> = y_cols.iter().map(|&col| app.streamed_summary(col)).collect();
+
+    let waiting: Vec<&str> = x_col.filter(|_| matches!(x_summary, Some(None)))
+        .into_iter()
+        .chain(y_cols.iter().zip(&y_summaries).filter(|(_, s)| s.is_none()).map(|(&col, _)| col))
+        .filter_map(|col| headers.get(col).map(String::as_str))
+        .collect();
+    if matches!(x_summary, Some(None)) || y_summaries.iter().all(Option::is_none) {
+        ui.centered_and_justified(|ui| {
+            ui.label(format!("Summarising {} for streaming…", waiting.join(", ")));
+        });
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+        return;
+    }
+    let x_summary = x_summary.flatten();
+    let total_rows = app.state.data.as_ref().map_or(0, |ds| ds.height());
+
+    // Map the X range shown last frame back to rows
+    let plot_id = ui.id().with("streaming_plot");
+    let reset = std::mem::take(&mut app.state.view.reset_bounds);
+    let rows = match reset {
+        true => 0..total_rows,
+        false => window_rows(ui.ctx(), plot_id, x_summary.as_deref(), total_rows),
+    };
+
+    let raw = rows.len() <= STREAM_RAW_ROWS;
+    let series: Vec<Vec<[f64; 2]>> = if raw {
+        let columns = (x_col, y_cols.clone());
+        let cached = app.state.ui.stream_window.as_ref()
+            .filter(|w| w.rows == rows && w.columns == columns)
+            .map(|w| w.points.clone());
+        match cached {
+            Some(points) => points,
+            None => {
+                let points = read_stream_window(app, rows.clone(), x_col, &y_cols).unwrap_or_else(|e| {
+                    app.state.ui.set_error(e.to_string());
+                    vec![Vec::new(); y_cols.len()]
+                });
+                app.state.ui.stream_window = Some(StreamWindow { rows: rows.clone(), columns, points: points.clone() });
+                points
+            }
+        }
+    } else {
+        let max_buckets = ui.available_width().max(100.0) as usize;
+        y_summaries.iter()
+            .map(|y| match y {
+                Some(y) => summary_envelope(y, x_summary.as_deref(), rows.clone(), max_buckets),
+                None => Vec::new(),
+            })
+            .collect()
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "Streaming from disk: rows {}–{} of {} ({})",
+            rows.start + usize::from(!rows.is_empty()),
+            rows.end,
+            total_rows,
+            if raw { "raw" } else { "min/max summary" }
+        ));
+        if !waiting.is_empty() {
+            ui.spinner();
+            ui.label(format!("Summarising {}", waiting.join(", ")));
+        }
+        if app.state.view.plot_mode != PlotMode::Scatter {
+            ui.weak("Other plot modes need the file loaded into memory");
+        }
+    });
+
+    let mut plot = control_plot(app, "streaming_plot", ui.available_height(), reset).id(plot_id);
+    if app.state.view.x_is_timestamp {
+        plot = timestamp_axis(plot);
+    }
+    plot.show(ui, |plot_ui| {
+        for (series_idx, (&y_idx, points)) in y_cols.iter().zip(series).enumerate() {
+            let name = headers.get(y_idx).cloned().unwrap_or_default();
+            let color = PlotOxide::get_series_color(series_idx);
+            plot_ui.line(Line::new(name, points).color(color));
+        }
+    });
+    if rows != window_rows(ui.ctx(), plot_id, x_summary.as_deref(), total_rows) {
+        ui.ctx().request_repaint();
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app::PlotOxide;
+use crate::state::OverlaySeries;
 
 /// Render the Y series selection panel (left sidebar)
 pub fn render_series_panel(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut eframe::egui::Ui) {
@@ -103,6 +104,8 @@ pub fn render_series_panel(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui:
                 }
             });
         }
+
+        render_overlay_choices(app, ui);
     });
 
     // Clear point selection if series changed
@@ -111,3 +114,51 @@ pub fn render_series_panel(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui:
         app.state.view.reset_bounds = true;
     }
 }
+
+/// Columns of the other open data sets, each toggling an overlay on the current plot
+fn render_overlay_choices(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let active = app.state.active_tab_id();
+    let tabs: Vec<(u64, String, Vec<String>)> = app.state.tabs.iter()
+        .filter(|tab| Some(tab.id) != active)
+        .filter_map(|tab| Some((tab.id, tab.name.clone(), app.state.dataset(tab.id)?.column_names())))
+        .collect();
+
+    // Overlays line up on the X column of the same name
+    let x_name = (!app.state.view.use_row_index)
+        .then(|| app.headers().get(app.state.view.x_index).cloned())
+        .flatten();
+
+    for (tab, name, columns) in tabs {
+        ui.separator();
+        eframe::egui::CollapsingHeader::new(format!("📄 {}", name))
+            .id_salt(("overlay_tab", tab))
+            .default_open(app.state.view.overlays.iter().any(|o| o.tab == tab))
+            .show(ui, |ui| {
+                if let Some(x_name) = x_name.as_ref().filter(|x| !columns.contains(x)) {
+                    ui.weak(format!("No \"{}\" column: plot against Row Index to overlay", x_name));
+                }
+                for column in columns {
+                    let overlay = OverlaySeries { tab, column };
+                    // Overlays take the colours after the current data set's series
+                    let position = app.state.view.overlays.iter()
+                        .filter(|o| Some(o.tab) != active)
+                        .position(|o| *o == overlay);
+                    let response = ui.selectable_label(position.is_some(), &overlay.column);
+                    if let Some(position) = position {
+                        ui.painter().circle_filled(
+                            response.rect.left_center() - eframe::egui::vec2(10.0, 0.0),
+                            4.0,
+                            PlotOxide::get_series_color(app.state.view.y_indices.len() + position),
+                        );
+                    }
+                    if response.on_hover_text("Overlay on the plot").clicked() {
+                        match position {
+                            Some(_) => app.state.view.overlays.retain(|o| *o != overlay),
+                            None => app.state.view.overlays.push(overlay),
+                        }
+                        app.state.view.reset_bounds = true;
+                    }
+                }
+            });
+    }
+}
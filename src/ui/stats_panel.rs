@@ -1,5 +1,6 @@
 use crate::app::PlotOxide;
-use crate::state::CachedStats;
+use crate::data::{capability, stats};
+use crate::state::{CachedStats, PlotMode};
 
 const HISTOGRAM_BINS: usize = 20;
 
@@ -75,6 +76,7 @@ fn calculate_full_stats(values: &[f64]) -> CachedStats {
         p95,
         histogram,
         histogram_max,
+        normality: stats::anderson_darling_test(&clean),
     }
 }
 
@@ -108,6 +110,44 @@ fn draw_sparkline(ui: &mut eframe::egui::Ui, stats: &CachedStats, color: eframe:
     }
 }
 
+/// Summary statistics of streamed columns, taken from their min/max summaries
+fn render_streaming_stats(app: &mut PlotOxide, ui: &mut eframe::egui::Ui, headers: &[String]) {
+    ui.small("Streaming from disk: median, percentiles and normality need the file in memory");
+    for (series_idx, &y_idx) in app.state.view.y_indices.clone().iter().enumerate() {
+        let color = PlotOxide::get_series_color(series_idx);
+        let name = headers.get(y_idx).map(|s| s.as_str()).unwrap_or("?");
+        let summary = app.streamed_summary(y_idx);
+
+        ui.group(|ui| {
+            ui.set_min_width(260.0);
+            ui.colored_label(color, format!("● {}", name));
+            let Some(summary) = summary else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Summarising…");
+                });
+                return;
+            };
+            let total = summary.total();
+            let Some(mean) = total.mean() else {
+                ui.label("No numeric values");
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label(format!("n={}", total.count));
+                ui.separator();
+                ui.label(format!("Range: {:.4}", total.max - total.min));
+            });
+            ui.horizontal(|ui| {
+                ui.label(format!("Min: {:.4}", total.min));
+                ui.separator();
+                ui.label(format!("Max: {:.4}", total.max));
+            });
+            ui.label(format!("μ={:.4}", mean));
+        });
+    }
+}
+
 /// Render the statistics summary panel
 pub fn render_stats_panel(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
     // P0 FIX: Use DataSource directly instead of app.data() which does expensive row-major conversion
@@ -121,6 +161,11 @@ pub fn render_stats_panel(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
     
     let headers = app.headers();
     let _data_version = app.state.ui.data_version;
+
+    if ds.is_streaming() {
+        render_streaming_stats(app, ui, &headers);
+        return;
+    }
     
     // Process each selected Y series
     for (series_idx, &y_idx) in app.state.view.y_indices.iter().enumerate() {
@@ -198,57 +243,99 @@ pub fn render_stats_panel(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
                 ui.small(format!("P95={:.2}", stats.p95));
             });
             
+            // Anderson-Darling normality check
+            if let Some(ad) = stats.normality {
+                ui.horizontal(|ui| {
+                    ui.small(format!("AD A²={:.3}", ad.statistic));
+                    let p_color = if ad.p_value < 0.05 {
+                        eframe::egui::Color32::RED
+                    } else {
+                        ui.visuals().text_color()
+                    };
+                    ui.colored_label(p_color, eframe::egui::RichText::new(format!("p={:.4}", ad.p_value)).small())
+                        .on_hover_text("p < 0.05: data unlikely to be normal");
+                });
+            }
+
             // Process capability if SPC enabled
             if app.state.spc.show_capability {
-                let (_cp, _cpk) = PlotOxide::calculate_process_capability(
-                    &[stats.mean], // Use cached mean
-                    app.state.spc.spec_lower,
-                    app.state.spc.spec_upper,
+                let spc = &app.state.spc;
+                let finite: Vec<f64> = ds.get_cached_column(y_idx)
+                    .map(|col| col.iter().copied().filter(|v| v.is_finite()).collect())
+                    .unwrap_or_default();
+                let report = capability::capability_report(
+                    &finite,
+                    spc.spec_lower,
+                    spc.spec_upper,
+                    spc.capability_target,
+                    spc.capability_subgroup_size,
                 );
-                
-                // Recalculate with proper values
-                let cp = (app.state.spc.spec_upper - app.state.spc.spec_lower) / (6.0 * stats.std_dev);
-                let cpu = (app.state.spc.spec_upper - stats.mean) / (3.0 * stats.std_dev);
-                let cpl = (stats.mean - app.state.spc.spec_lower) / (3.0 * stats.std_dev);
-                let cpk = cpu.min(cpl);
-                
-                ui.horizontal(|ui| {
-                    let cp_color = if cp >= 1.33 { 
-                        eframe::egui::Color32::GREEN 
-                    } else if cp >= 1.0 { 
-                        eframe::egui::Color32::YELLOW 
-                    } else { 
-                        eframe::egui::Color32::RED 
-                    };
-                    let cpk_color = if cpk >= 1.33 { 
-                        eframe::egui::Color32::GREEN 
-                    } else if cpk >= 1.0 { 
-                        eframe::egui::Color32::YELLOW 
-                    } else { 
-                        eframe::egui::Color32::RED 
+
+                if let Some(report) = report {
+                    let index_color = |v: f64| {
+                        if v >= 1.33 {
+                            eframe::egui::Color32::GREEN
+                        } else if v >= 1.0 {
+                            eframe::egui::Color32::YELLOW
+                        } else {
+                            eframe::egui::Color32::RED
+                        }
                     };
-                    
-                    ui.label("Cp:");
-                    ui.colored_label(cp_color, format!("{:.3}", cp));
-                    ui.separator();
-                    ui.label("Cpk:");
-                    ui.colored_label(cpk_color, format!("{:.3}", cpk));
-                });
-                
-                // Percentage within spec
-                // We need the actual values for this, so compute from cached column
-                if let Some(ds) = &app.state.data {
-                    if let Ok(col) = ds.get_cached_column(y_idx) {
-                        let in_spec = col.iter()
-                            .filter(|&&v| v.is_finite() && v >= app.state.spc.spec_lower && v <= app.state.spc.spec_upper)
-                            .count();
-                        let pct = (in_spec as f64 / stats.count as f64) * 100.0;
-                        ui.label(format!("Within spec: {:.1}% ({}/{})", pct, in_spec, stats.count));
+
+                    // Cp/Cpk use within sigma, Pp/Ppk use overall sigma
+                    for pair in [[("Cp", report.cp), ("Cpk", report.cpk)], [("Pp", report.pp), ("Ppk", report.ppk)]] {
+                        ui.horizontal(|ui| {
+                            for (i, (label, value)) in pair.into_iter().enumerate() {
+                                if i > 0 {
+                                    ui.separator();
+                                }
+                                ui.label(format!("{}:", label));
+                                ui.colored_label(index_color(value), format!("{:.3}", value));
+                            }
+                        });
+                    }
+                    if let Some(cpm) = report.cpm {
+                        ui.horizontal(|ui| {
+                            ui.label("Cpm:");
+                            ui.colored_label(index_color(cpm), format!("{:.3}", cpm));
+                        });
                     }
+
+                    let in_spec = report.n - (report.ppm_observed * report.n as f64 / 1e6).round() as usize;
+                    let pct = (in_spec as f64 / report.n as f64) * 100.0;
+                    ui.label(format!("Within spec: {:.1}% ({}/{})", pct, in_spec, report.n));
                 }
             }
         });
         
         ui.add_space(4.0);
     }
+
+    // Signals from the active time-weighted control chart
+    let chart_name = match app.state.view.plot_mode {
+        PlotMode::Cusum => "CUSUM",
+        PlotMode::Ewma => "EWMA",
+        PlotMode::HotellingT2 => "T²",
+        _ => return,
+    };
+    ui.group(|ui| {
+        ui.set_min_width(260.0);
+        let signals = &app.state.spc.chart_signals;
+        if signals.is_empty() {
+            ui.label(format!("{}: in control", chart_name));
+            return;
+        }
+        ui.colored_label(
+            eframe::egui::Color32::RED,
+            format!("{}: {} out-of-control point(s)", chart_name, signals.len()),
+        );
+        eframe::egui::ScrollArea::vertical()
+            .id_salt("chart_signals")
+            .max_height(150.0)
+            .show(ui, |ui| {
+                for signal in signals {
+                    ui.small(format!("Row {}: {:.4}", signal.point_index + 1, signal.value));
+                }
+            });
+    });
 }
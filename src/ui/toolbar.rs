@@ -1,22 +1,23 @@
 use crate::app::PlotOxide;
-use crate::state::{PlotMode, LineStyle};
+use crate::data::stats::DistributionKind;
+use crate::data::formats::FileFormat;
+use crate::data::live::LiveSource;
+use crate::state::{PlotMode, LineStyle, StrataLayout, ImportDialog, LiveDialog, CombineDialog, ComputedDialog, FollowScroll};
 use crate::widgets::{SpcControls, FilterControls};
 use eframe::egui::ComboBox;
 
 /// Render the toolbar and control panels
 /// Returns false if no Y series selected (skip plot rendering), true otherwise
-pub fn render_toolbar_and_controls(app: &mut PlotOxide, ctx: &eframe::egui::Context, ui: &mut eframe::egui::Ui) -> bool {
+pub fn render_toolbar_and_controls(app: &mut PlotOxide, _ctx: &eframe::egui::Context, ui: &mut eframe::egui::Ui) -> bool {
     // Compact toolbar with icon buttons
     ui.horizontal(|ui| {
         // File operations
         if ui.button("📂").on_hover_text("Open Data File").clicked() {
             if let Some(path) = rfd::FileDialog::new()
-                .add_filter("Data Files", &["csv", "parquet"])
+                .add_filter("Data Files", &crate::data::formats::SUPPORTED_EXTENSIONS)
                 .pick_file()
             {
-                if let Err(e) = app.load_file(path) {
-                    app.state.ui.set_error(e.user_message());
-                }
+                app.open_file(path);
             }
         }
 
@@ -31,47 +32,173 @@ pub fn render_toolbar_and_controls(app: &mut PlotOxide, ctx: &eframe::egui::Cont
                     for path in app.state.recent_files.clone().iter() {
                         if let Some(name) = path.file_name() {
                             if ui.button(name.to_string_lossy()).clicked() {
-                                let path_clone = path.clone();
-                                if let Err(e) = app.load_file(path_clone) {
-                                    app.state.ui.set_error(e.user_message());
-                                }
+                                app.load_file(path.clone());
                             }
                         }
                     }
                 });
         }
+
+        ui.checkbox(&mut app.state.always_stream, "Stream")
+            .on_hover_text("Read files from disk on demand instead of loading them into memory.\nFiles over 1 GB are always streamed.");
+
+        if ui.add_enabled(app.state.has_data(), eframe::egui::Button::new("💾")).on_hover_text("Export CSV").clicked() {
+            app.export_csv();
+        }
+
+        let in_memory = app.state.data.as_ref().is_some_and(|ds| !ds.is_streaming());
+        if ui.add_enabled(in_memory, eframe::egui::Button::new("ƒx")).on_hover_text("Computed columns").clicked() {
+            app.state.ui.computed_dialog = Some(ComputedDialog::default());
+        }
+
+        if ui.button("⚙").on_hover_text("Save Config").clicked() {
+            app.save_config();
+        }
+        if ui.button("📥").on_hover_text("Load Config").clicked() {
+            app.load_config();
+        }
+
+        if ui.button("📡").on_hover_text("Live data from a socket or stdin").clicked() {
+            app.state.ui.live_dialog = Some(LiveDialog::new(app.state.live_config.clone()));
+        }
     });
 
+    // Status of a live session, with the SPC signals in the rows it holds
+    if let Some(live) = app.state.live.as_ref() {
+        let status = if live.listener.is_closed() {
+            "closed"
+        } else if live.batches.is_none() {
+            "waiting for data"
+        } else {
+            "receiving"
+        };
+        let signals = app.state.spc.we_violations.len();
+        let mut stop = false;
+        ui.horizontal(|ui| {
+            // The bound address shows the port picked for ":0"
+            let source = match (&live.config.source, live.listener.local_addr()) {
+                (LiveSource::Tcp(_), Some(addr)) => format!("TCP {}", addr),
+                (LiveSource::Udp(_), Some(addr)) => format!("UDP {}", addr),
+                (source, _) => source.to_string(),
+            };
+            ui.label(format!("📡 {} ({})", source, status));
+            ui.weak(format!("{} rows held, {} received", app.state.row_count(), live.received));
+            if signals > 0 {
+                ui.colored_label(eframe::egui::Color32::from_rgb(255, 140, 0), format!("⚠ {} rule violations", signals))
+                    .on_hover_text("Run-rule violations in the rows held, for charts with rules shown");
+            }
+            stop = ui.small_button("⏹ Stop").on_hover_text("Stop reading; the rows held stay loaded").clicked();
+        });
+        if stop {
+            app.stop_live();
+        }
+    }
+
     // ui.separator();
 
+    // Open data sets, one tab each
+    if !app.state.tabs.is_empty() {
+        let mut show = None;
+        let mut close = None;
+        ui.horizontal_wrapped(|ui| {
+            for (index, tab) in app.state.tabs.iter().enumerate() {
+                let response = ui.selectable_label(index == app.state.active_tab, &tab.name);
+                let response = match &tab.file {
+                    Some(file) => response.on_hover_text(file.display().to_string()),
+                    None => response,
+                };
+                if response.clicked() {
+                    show = Some(index);
+                }
+                if ui.small_button("✕").on_hover_text("Close").clicked() {
+                    close = Some(index);
+                }
+                ui.separator();
+            }
+
+            // Stack or join open data sets into a new tab
+            if app.state.tabs.len() >= 2 && ui.small_button("⊕").on_hover_text("Combine data sets").clicked() {
+                let ids: Vec<u64> = app.state.tabs.iter().map(|tab| tab.id).collect();
+                app.state.ui.combine_dialog = Some(CombineDialog::new(&ids));
+            }
+            if let Some((_, name)) = &app.state.combining {
+                ui.spinner();
+                ui.weak(format!("Combining {}…", name));
+            }
+        });
+        if let Some(index) = show {
+            app.switch_tab(index);
+        }
+        if let Some(index) = close {
+            app.close_tab(index);
+        }
+    }
+
     // Display current file with icon
-    app.state.current_file
-        .as_ref()
-        .map(|file| {
-        ui.label(
-            format!("📄 {}", 
-                file.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown")
+    if let Some(file) = app.state.current_file.clone() {
+        let format = app.state.data.as_ref().and_then(|ds| ds.format());
+        ui.horizontal(|ui| {
+            ui.label(
+                format!("📄 {}",
+                    file.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("Unknown")
+                )
             )
-        )
-        .on_hover_text(file.display().to_string())
-        });
-    
+            .on_hover_text(match app.state.data.as_ref().and_then(|ds| ds.format_description()) {
+                Some(format) => format!("{}\n{}", file.display(), format),
+                None => file.display().to_string(),
+            });
 
-    ui.separator();
+            if app.state.data.as_ref().is_some_and(|ds| ds.is_streaming()) {
+                ui.weak("streaming").on_hover_text("Drawn from column summaries; raw rows are read from disk when zoomed in");
+            }
 
-    // Handle drag and drop using Option combinators
-    ctx.input(|i| {
-        i.raw.dropped_files
-            .first()
-            .and_then(|f| f.path.as_ref())
-            .map(|path| {
-                if let Err(e) = app.load_file(path.clone()) {
-                    app.state.ui.set_error(e.user_message());
+            // Delimited text can be re-read with different import options
+            if matches!(format, Some((FileFormat::Delimited { .. }, _)))
+                && ui.small_button("⚙").on_hover_text("Import options").clicked()
+            {
+                let options = app.state.import_options.get(&file).cloned().unwrap_or_default();
+                app.state.ui.import_dialog = Some(ImportDialog::new(file.clone(), options));
+            }
+
+            // Live tail of files that are still being written
+            if app.state.data.as_ref().is_some_and(|ds| ds.can_follow()) {
+                let follow = &mut app.state.follow;
+                ui.checkbox(&mut follow.enabled, "Follow")
+                    .on_hover_text("Read rows appended to the file as they arrive");
+                if follow.enabled {
+                    ComboBox::from_id_salt("follow_scroll")
+                        .selected_text(match follow.scroll {
+                            FollowScroll::Off => "Keep view",
+                            FollowScroll::LastPoints => "Last points",
+                            FollowScroll::LastSeconds => "Last seconds",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut follow.scroll, FollowScroll::Off, "Keep view");
+                            ui.selectable_value(&mut follow.scroll, FollowScroll::LastPoints, "Last points");
+                            ui.selectable_value(&mut follow.scroll, FollowScroll::LastSeconds, "Last seconds")
+                                .on_hover_text("X units when X is not a timestamp");
+                        });
+                    match follow.scroll {
+                        FollowScroll::Off => {}
+                        FollowScroll::LastPoints => {
+                            ui.add(eframe::egui::DragValue::new(&mut follow.points).range(2..=10_000_000));
+                        }
+                        FollowScroll::LastSeconds => {
+                            ui.add(eframe::egui::DragValue::new(&mut follow.seconds).range(0.001..=f64::MAX).suffix(" s"));
+                        }
+                    }
                 }
-            });
-    });
+            }
+        });
+    }
+
+    if app.state.has_data() {
+        render_filter_bar(app, ui);
+    }
+
+    ui.separator();
 
     // Show plot only if we have data
     if app.state.has_data() {
@@ -160,7 +287,18 @@ pub fn render_toolbar_and_controls(app: &mut PlotOxide, ctx: &eframe::egui::Cont
                     ui.radio_value(&mut app.state.view.plot_mode, PlotMode::BoxPlot, "Box Plot");
                     ui.radio_value(&mut app.state.view.plot_mode, PlotMode::Pareto, "Pareto");
                     ui.radio_value(&mut app.state.view.plot_mode, PlotMode::XbarR, "X-bar & R");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::XbarS, "X-bar & S");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::IMR, "I-MR");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::Cusum, "CUSUM");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::Ewma, "EWMA");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::Capability, "Capability");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::ProbabilityPlot, "Probability");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::GageRR, "Gage R&R");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::HotellingT2, "Hotelling T²");
                     ui.radio_value(&mut app.state.view.plot_mode, PlotMode::PChart, "p-chart");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::NpChart, "np-chart");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::CChart, "c-chart");
+                    ui.radio_value(&mut app.state.view.plot_mode, PlotMode::UChart, "u-chart");
                 });
 
                 // Mode-specific controls
@@ -174,10 +312,147 @@ pub fn render_toolbar_and_controls(app: &mut PlotOxide, ctx: &eframe::egui::Cont
                     PlotMode::XbarR => {
                         ui.vertical(|ui| {
                             ui.label("Subgroup:");
-                            ui.add(eframe::egui::Slider::new(&mut app.state.spc.xbarr_subgroup_size, 2..=10));
+                            ui.add(eframe::egui::Slider::new(&mut app.state.spc.xbarr_subgroup_size, 2..=50));
+                        });
+                    }
+                    PlotMode::XbarS => {
+                        ui.vertical(|ui| {
+                            ui.label("Subgroup:");
+                            ui.add(eframe::egui::Slider::new(&mut app.state.spc.xbarr_subgroup_size, 2..=50));
+                        });
+                        ui.checkbox(&mut app.state.spc.show_we_rules, "WE Rules");
+                    }
+                    PlotMode::IMR => {
+                        ui.checkbox(&mut app.state.spc.show_we_rules, "WE Rules");
+                    }
+                    PlotMode::Cusum => {
+                        ui.vertical(|ui| {
+                            ui.label("k (σ):");
+                            ui.add(eframe::egui::Slider::new(&mut app.state.spc.cusum_k, 0.1..=2.0).step_by(0.05));
+                            ui.label("h (σ):");
+                            ui.add(eframe::egui::Slider::new(&mut app.state.spc.cusum_h, 1.0..=10.0).step_by(0.5));
+                        });
+                        ui.vertical(|ui| {
+                            let mut use_target = app.state.spc.cusum_target.is_some();
+                            if ui.checkbox(&mut use_target, "Target").changed() {
+                                // Start from the sample mean the chart has been using
+                                let mean = app.state.view.y_indices.first().and_then(|&y_idx| {
+                                    let col = app.state.data.as_ref()?.get_cached_column(y_idx).ok()?;
+                                    let finite: Vec<f64> = col.iter().copied().filter(|v| v.is_finite()).collect();
+                                    (!finite.is_empty()).then(|| finite.iter().sum::<f64>() / finite.len() as f64)
+                                });
+                                app.state.spc.cusum_target = use_target.then(|| mean.unwrap_or(0.0));
+                            }
+                            if let Some(target) = app.state.spc.cusum_target.as_mut() {
+                                ui.add(eframe::egui::DragValue::new(target).speed(0.1));
+                            } else {
+                                ui.label("(sample mean)");
+                            }
+                        });
+                    }
+                    PlotMode::Ewma => {
+                        ui.vertical(|ui| {
+                            ui.label("λ:");
+                            ui.add(eframe::egui::Slider::new(&mut app.state.spc.ewma_lambda, 0.05..=0.5).step_by(0.05));
+                            ui.label("L (σ):");
+                            ui.add(eframe::egui::Slider::new(&mut app.state.spc.ewma_l, 2.0..=3.5).step_by(0.1));
+                        });
+                    }
+                    PlotMode::Capability => {
+                        ui.vertical(|ui| {
+                            ui.label("LSL:");
+                            ui.add(eframe::egui::DragValue::new(&mut app.state.spc.spec_lower).speed(0.1));
+                            ui.label("USL:");
+                            ui.add(eframe::egui::DragValue::new(&mut app.state.spc.spec_upper).speed(0.1));
+                        });
+                        ui.vertical(|ui| {
+                            let mut use_target = app.state.spc.capability_target.is_some();
+                            if ui.checkbox(&mut use_target, "Target").changed() {
+                                let midpoint = (app.state.spc.spec_lower + app.state.spc.spec_upper) / 2.0;
+                                app.state.spc.capability_target = use_target.then_some(midpoint);
+                            }
+                            if let Some(target) = app.state.spc.capability_target.as_mut() {
+                                ui.add(eframe::egui::DragValue::new(target).speed(0.1));
+                            }
+                            ui.label("Subgroup:")
+                                .on_hover_text("1 = within sigma from moving ranges");
+                            ui.add(eframe::egui::Slider::new(&mut app.state.spc.capability_subgroup_size, 1..=10));
+                        });
+                        ui.vertical(|ui| {
+                            ui.label("Bins:");
+                            ui.add(eframe::egui::Slider::new(&mut app.state.view.histogram_bins, 5..=50));
+                            let selected = app.state.spc.capability_distribution
+                                .map(|kind| kind.name())
+                                .unwrap_or("Best fit");
+                            ComboBox::from_label("Distribution")
+                                .selected_text(selected)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut app.state.spc.capability_distribution, None, "Best fit");
+                                    for kind in DistributionKind::ALL {
+                                        ui.selectable_value(&mut app.state.spc.capability_distribution, Some(kind), kind.name());
+                                    }
+                                });
+                        });
+                    }
+                    PlotMode::PChart | PlotMode::UChart => {
+                        ui.vertical(|ui| {
+                            let fixed = app.state.spc.sample_size_column.is_none();
+                            if app.state.view.plot_mode == PlotMode::PChart {
+                                ui.label("Sample n:");
+                                ui.add_enabled(fixed, eframe::egui::Slider::new(&mut app.state.spc.pchart_sample_size, 10..=200));
+                            } else {
+                                ui.label("Units per sample:");
+                                ui.add_enabled(fixed, eframe::egui::Slider::new(&mut app.state.spc.uchart_units, 0.1..=100.0)
+                                    .logarithmic(true));
+                            }
+                            let selected = app.state.spc.sample_size_column
+                                .and_then(|i| headers.get(i))
+                                .map(|h| h.as_str())
+                                .unwrap_or("Fixed n");
+                            ComboBox::from_label("Sample Size Column")
+                                .selected_text(selected)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut app.state.spc.sample_size_column, None, "Fixed n");
+                                    for (i, h) in headers.iter().enumerate() {
+                                        ui.selectable_value(&mut app.state.spc.sample_size_column, Some(i), h);
+                                    }
+                                });
                         });
                     }
-                    PlotMode::PChart => {
+                    PlotMode::HotellingT2 => {
+                        ui.vertical(|ui| {
+                            ui.checkbox(&mut app.state.spc.hotelling_use_baseline, "Phase I from baseline")
+                                .on_hover_text("Estimate mean and covariance from the baseline rows instead of all rows");
+                            if app.state.spc.hotelling_use_baseline {
+                                let row_count = app.state.data.as_ref().map(|ds| ds.height()).unwrap_or(0);
+                                render_baseline_rows(ui, &mut app.state.spc.baseline_rows, row_count);
+                            }
+                        });
+                    }
+                    PlotMode::GageRR => {
+                        ui.vertical(|ui| {
+                            let spc = &mut app.state.spc;
+                            for (label, column, none_text) in [
+                                ("Part", &mut spc.gage_part_column, "Select"),
+                                ("Operator", &mut spc.gage_operator_column, "Select"),
+                                ("Measurement", &mut spc.gage_measurement_column, "First Y series"),
+                            ] {
+                                let selected = column
+                                    .and_then(|i| headers.get(i))
+                                    .map(|h| h.as_str())
+                                    .unwrap_or(none_text);
+                                ComboBox::from_label(label)
+                                    .selected_text(selected)
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(column, None, none_text);
+                                        for (i, h) in headers.iter().enumerate() {
+                                            ui.selectable_value(column, Some(i), h);
+                                        }
+                                    });
+                            }
+                        });
+                    }
+                    PlotMode::NpChart => {
                         ui.vertical(|ui| {
                             ui.label("Sample n:");
                             ui.add(eframe::egui::Slider::new(&mut app.state.spc.pchart_sample_size, 10..=200));
@@ -190,6 +465,26 @@ pub fn render_toolbar_and_controls(app: &mut PlotOxide, ctx: &eframe::egui::Cont
                             ui.radio_value(&mut app.state.view.line_style, LineStyle::Points, "Points");
                             ui.radio_value(&mut app.state.view.line_style, LineStyle::LineAndPoints, "Both");
                         });
+                        ui.vertical(|ui| {
+                            let selected = app.state.spc.strata_column
+                                .and_then(|i| headers.get(i))
+                                .map(|h| h.as_str())
+                                .unwrap_or("None");
+                            ComboBox::from_label("Group by")
+                                .selected_text(selected)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut app.state.spc.strata_column, None, "None");
+                                    for (i, h) in headers.iter().enumerate() {
+                                        ui.selectable_value(&mut app.state.spc.strata_column, Some(i), h);
+                                    }
+                                })
+                                .response
+                                .on_hover_text("Split the first Y series into strata with their own control limits");
+                            if app.state.spc.strata_column.is_some() {
+                                ui.radio_value(&mut app.state.spc.strata_layout, StrataLayout::Overlay, "Overlay");
+                                ui.radio_value(&mut app.state.spc.strata_layout, StrataLayout::SmallMultiples, "Small multiples");
+                            }
+                        });
                     }
                     _ => {}
                 }
@@ -205,6 +500,14 @@ pub fn render_toolbar_and_controls(app: &mut PlotOxide, ctx: &eframe::egui::Cont
                     SpcControls::new(&mut app.state.spc).show(ui);
                 });
 
+            // Phase I limit locking (collapsible)
+            eframe::egui::CollapsingHeader::new("🔒 Phase I Limits")
+                .id_salt("phase_controls")
+                .default_open(false)
+                .show(ui, |ui| {
+                    render_phase_controls(app, ui, &headers);
+                });
+
             // Data Filtering Controls (collapsible)
             eframe::egui::CollapsingHeader::new("🔍 Filters")
                 .id_salt("filter_controls")
@@ -229,3 +532,122 @@ pub fn render_toolbar_and_controls(app: &mut PlotOxide, ctx: &eframe::egui::Cont
         false
     }
 }
+
+/// Baseline row range editor, shown 1-based like the data table
+fn render_baseline_rows(ui: &mut eframe::egui::Ui, baseline_rows: &mut (usize, usize), row_count: usize) {
+    ui.horizontal(|ui| {
+        let (start, end) = baseline_rows;
+        let mut first = *start + 1;
+        let mut last = *end;
+        ui.label("Baseline rows:");
+        ui.add(eframe::egui::DragValue::new(&mut first).range(1..=row_count.max(1)));
+        ui.label("to");
+        ui.add(eframe::egui::DragValue::new(&mut last).range(first..=row_count.max(first)));
+        *start = first - 1;
+        *end = last;
+    });
+}
+
+/// Baseline row range and per-series freeze/unfreeze buttons for Phase I limits
+fn render_phase_controls(app: &mut PlotOxide, ui: &mut eframe::egui::Ui, headers: &[String]) {
+    let row_count = app.state.data.as_ref().map(|ds| ds.height()).unwrap_or(0);
+    render_baseline_rows(ui, &mut app.state.spc.baseline_rows, row_count);
+
+    for y_idx in app.state.view.y_indices.clone() {
+        let Some(name) = headers.get(y_idx) else {
+            continue;
+        };
+        ui.horizontal(|ui| {
+            if let Some(frozen) = app.state.spc.frozen_limits.get(name).copied() {
+                ui.label(format!(
+                    "{}: μ={:.4} σ={:.4} (rows {}-{})",
+                    name, frozen.mean, frozen.std_dev, frozen.baseline_start + 1, frozen.baseline_end
+                ));
+                if ui.small_button("Unfreeze").clicked() {
+                    app.unfreeze_limits(y_idx);
+                }
+            } else {
+                ui.label(format!("{}: live limits", name));
+                if ui.small_button("Freeze").on_hover_text("Lock limits from the baseline rows").clicked()
+                    && let Err(e) = app.freeze_limits(y_idx)
+                {
+                    app.state.ui.set_error(e.user_message());
+                }
+            }
+        });
+    }
+}
+
+/// Row filter expressions: a field to type one in, and a chip per filter that toggles it
+fn render_filter_bar(app: &mut PlotOxide, ui: &mut eframe::egui::Ui) {
+    let streaming = app.state.data.as_ref().is_some_and(|ds| ds.is_streaming());
+    let mut add = false;
+    let mut toggled = false;
+    let mut remove = None;
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("🔍");
+        let response = ui.add_enabled(
+            !streaming,
+            eframe::egui::TextEdit::singleline(&mut app.state.ui.filter_input)
+                .code_editor()
+                .desired_width(280.0)
+                .hint_text("category == \"A\" and value > 100"),
+        );
+        let response = if streaming {
+            response.on_disabled_hover_text("Rows are not filtered while streaming from disk")
+        } else {
+            response.on_hover_text("Filter rows, e.g. timestamp >= 2024-01-02 and not (status == \"idle\"). Enter adds the filter.")
+        };
+        if response.changed() {
+            app.state.ui.filter_error = None;
+        }
+        if response.lost_focus() && ui.input(|i| i.key_pressed(eframe::egui::Key::Enter)) {
+            add = true;
+        }
+
+        for (index, filter) in app.state.filters.expressions.iter_mut().enumerate() {
+            // Filters that could not be applied show as off, with the reason
+            let text = match filter.error {
+                None => eframe::egui::RichText::new(&filter.expression).monospace(),
+                Some(_) => eframe::egui::RichText::new(&filter.expression).monospace().strikethrough(),
+            };
+            let chip = ui.selectable_label(filter.enabled && filter.error.is_none(), text);
+            let chip = match &filter.error {
+                None => chip.on_hover_text(if filter.enabled { "Click to turn off" } else { "Click to turn on" }),
+                Some(error) => chip.on_hover_text(format!("{}\nClick to turn off", error)),
+            };
+            if chip.clicked() {
+                filter.enabled = !filter.enabled;
+                toggled = true;
+            }
+            if ui.small_button("✕").on_hover_text("Remove filter").clicked() {
+                remove = Some(index);
+            }
+        }
+
+        if let Some(total) = app.state.data.as_ref().and_then(|ds| ds.unfiltered_height()) {
+            ui.weak(format!("{} of {} rows", app.state.row_count(), total));
+        }
+    });
+    if let Some(error) = &app.state.ui.filter_error {
+        ui.colored_label(eframe::egui::Color32::from_rgb(220, 80, 80), error);
+    }
+
+    if add {
+        let expression = app.state.ui.filter_input.trim().to_string();
+        if !expression.is_empty() {
+            match app.add_row_filter(&expression) {
+                Ok(()) => app.state.ui.filter_input.clear(),
+                Err(e) => app.state.ui.filter_error = Some(e.to_string()),
+            }
+        }
+    }
+    if let Some(index) = remove {
+        app.state.filters.expressions.remove(index);
+        toggled = true;
+    }
+    if toggled {
+        app.apply_row_filters();
+    }
+}
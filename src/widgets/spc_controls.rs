@@ -1,5 +1,6 @@
 //! SPC (Statistical Process Control) controls widget
 
+use crate::data::rules::{RuleId, RuleSet};
 use crate::state::SpcConfig;
 use egui::{Response, Ui};
 
@@ -29,8 +30,14 @@ impl<'a> SpcControls<'a> {
             // Sigma Zones
             ui.checkbox(&mut self.config.show_sigma_zones, "Zones");
 
-            // Western Electric Rules
+            // Western Electric / Nelson run rules
             ui.checkbox(&mut self.config.show_we_rules, "WE Rules");
+            if self.config.show_we_rules {
+                egui::CollapsingHeader::new("Rules")
+                    .id_salt("run_rules")
+                    .default_open(false)
+                    .show(ui, |ui| show_rules(ui, &mut self.config.rules));
+            }
 
             // ui.separator();
 
@@ -80,3 +87,33 @@ impl<'a> SpcControls<'a> {
         .response
     }
 }
+
+/// Rule presets and per-rule toggles with editable run lengths
+fn show_rules(ui: &mut Ui, rules: &mut RuleSet) {
+    ui.horizontal(|ui| {
+        if ui.small_button("WE").on_hover_text("Western Electric rules 1-4").clicked() {
+            *rules = RuleSet::western_electric();
+        }
+        if ui.small_button("Nelson").on_hover_text("All eight Nelson rules").clicked() {
+            *rules = RuleSet::nelson();
+        }
+        if ui.small_button("Default").clicked() {
+            *rules = RuleSet::default();
+        }
+    });
+
+    for id in RuleId::ALL {
+        let setting = rules.get_mut(id);
+        ui.checkbox(&mut setting.enabled, format!("N{}", id.nelson_number()))
+            .on_hover_text(id.to_string());
+        if setting.enabled && id != RuleId::BeyondThreeSigma {
+            ui.horizontal(|ui| {
+                if id.uses_count() {
+                    ui.add(egui::DragValue::new(&mut setting.count).range(1..=setting.run_length));
+                    ui.label("of");
+                }
+                ui.add(egui::DragValue::new(&mut setting.run_length).range(id.min_run_length()..=50));
+            });
+        }
+    }
+}